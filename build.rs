@@ -23,6 +23,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             tonic_build::compile_protos("proto/resolver.proto")?;
             tonic_build::compile_protos("proto/key_value.proto")?;
             tonic_build::compile_protos("proto/crypto.proto")?;
+            tonic_build::compile_protos("proto/prefetch.proto")?;
+            tonic_build::compile_protos("proto/cache.proto")?;
+            tonic_build::compile_protos("proto/health.proto")?;
+            tonic_build::compile_protos("proto/config.proto")?;
+            tonic_build::compile_protos("proto/wallet.proto")?;
         } else {
             println!("cargo:warning=protoc not found, disabling gRPC support");
             println!("cargo:rustc-cfg=grpc_disabled");
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use ant_core::data::XorName;
 use log::{info, debug, error, warn};
 use mockall::mock;
@@ -8,10 +10,11 @@ use crate::client::CachingClient;
 use crate::client::PublicArchiveCachingClient;*/
 #[double]
 use crate::client::StreamingClient;
-use crate::client::ARCHIVE_CACHE_KEY;
+use crate::client::{ARCHIVE_CACHE_KEY, ARCHIVE_NEGATIVE_CACHE_KEY};
 #[double]
 use crate::client::TArchiveCachingClient;
 use crate::error::archive_error::ArchiveError;
+use crate::error::GetError;
 use crate::model::archive::Archive;
 
 #[derive(Clone)]
@@ -24,6 +27,7 @@ mock! {
     pub ArchiveCachingClient {
         pub fn new(caching_client: CachingClient, streaming_client: StreamingClient) -> Self;
         pub async fn archive_get(&self, addr: XorName) -> Result<Archive, ArchiveError>;
+        pub fn archive_negative_cache_bypass(&self, addr: &XorName);
     }
     impl Clone for ArchiveCachingClient {
         fn clone(&self) -> Self;
@@ -37,11 +41,18 @@ impl ArchiveCachingClient {
 
     pub async fn archive_get(&self, addr: XorName) -> Result<Archive, ArchiveError> {
         // todo: could remove caching of sub-calls, unless called directly elsewhere?
+        let negative_cache_key = format!("{}{}", ARCHIVE_NEGATIVE_CACHE_KEY, hex::encode(addr));
+        if let Some(reason) = self.caching_client.get_negative_cache().get(&negative_cache_key) {
+            info!("negative cache for archive [{}] from hybrid cache", hex::encode(addr));
+            return Err(ArchiveError::GetError(GetError::RecordNotFound(reason)));
+        }
         let local_caching_client = self.caching_client.clone();
         let local_address = addr.clone();
         let local_streaming_client = self.streaming_client.clone();
         let cache_key = format!("{}{}", ARCHIVE_CACHE_KEY, hex::encode(local_address));
-        let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&cache_key.clone(), || async move {
+        let is_not_archive = Arc::new(AtomicBool::new(false));
+        let local_is_not_archive = is_not_archive.clone();
+        let cache_entry = match self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&cache_key.clone(), || async move {
             // todo: can these be injected?
             /*let public_archive_caching_client = PublicArchiveCachingClient::new(local_caching_client.clone(), local_streaming_client.clone());*/
             let tarchive_caching_client = TArchiveCachingClient::new(local_caching_client.clone(), local_streaming_client.clone());
@@ -70,6 +81,10 @@ impl ArchiveCachingClient {
                                 }
                             },
                             Err(err) => {
+                                // only data which was retrieved but is not a tarchive is definitely not an archive
+                                if matches!(err, GetError::Decode(_)) {
+                                    local_is_not_archive.store(true, Ordering::Relaxed);
+                                }
                                 error!("Failed to retrieve tarchive at [{}] from hybrid cache: {:?}", hex::encode(addr), err);
                                 Err(anyhow::anyhow!(format!("Failed to retrieve tarchive at [{}] from hybrid cache: {:?}", hex::encode(addr), err)))
                             },
@@ -81,7 +96,17 @@ impl ArchiveCachingClient {
                     Err(anyhow::anyhow!(format!("Failed to retrieve public archive at [{}] from hybrid cache: {:?}", addr.to_hex(), err)))
                 }
             }*/
-        }).await?;
+        }).await {
+            Ok(cache_entry) => cache_entry,
+            Err(e) => {
+                // store negative cache to avoid repeated lookups of non-archives, but not when the
+                // data could not be retrieved, e.g. as the network is offline or timed out
+                if is_not_archive.load(Ordering::Relaxed) {
+                    self.caching_client.get_negative_cache().insert(negative_cache_key, e.to_string());
+                }
+                return Err(e.into());
+            }
+        };
         info!("retrieved archive for [{}] from hybrid cache", hex::encode(addr));
         match rmp_serde::from_slice(cache_entry.value()) {
             Ok(archive) => Ok(archive),
@@ -92,4 +117,8 @@ impl ArchiveCachingClient {
             }
         }
     }
+
+    pub fn archive_negative_cache_bypass(&self, addr: &XorName) {
+        self.caching_client.get_negative_cache().bypass(&format!("{}{}", ARCHIVE_NEGATIVE_CACHE_KEY, hex::encode(addr)));
+    }
 }
\ No newline at end of file
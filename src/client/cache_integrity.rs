@@ -0,0 +1,232 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use actix_web::web::Data;
+use ant_core::data::{DataChunk, XorName};
+use async_job::{Job, Schedule};
+use async_trait::async_trait;
+use bytes::Bytes;
+use foyer::{HybridCache, Load};
+use indexmap::IndexSet;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+const MAX_TRACKED_CHUNKS: usize = 1_000_000;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default, PartialEq)]
+pub struct CacheIntegrityStats {
+    #[schema(read_only)]
+    pub verified: u64,
+    #[schema(read_only)]
+    pub corrupted: u64,
+    #[schema(read_only)]
+    pub scrubbed: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheIntegrityCounters {
+    verified: AtomicU64,
+    corrupted: AtomicU64,
+    scrubbed: AtomicU64,
+}
+
+/// Verifies that cached chunks still hash to their address and evicts those which do not.
+///
+/// foyer cannot enumerate its entries, so chunks are tracked as they pass through
+/// `ChunkCachingClient`. The scrubber job then walks the tracked chunks, a batch at a time,
+/// reading them from the disk tier only, so that the files are verified and hot entries are not
+/// evicted from memory.
+#[derive(Clone)]
+pub struct CacheIntegrity {
+    hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
+    tracked_chunks: Data<Mutex<IndexSet<XorName>>>,
+    scrub_cursor: Data<AtomicUsize>,
+    scrub_batch_size: usize,
+    counters: Data<CacheIntegrityCounters>,
+}
+
+impl CacheIntegrity {
+    pub fn new(hybrid_cache: Data<HybridCache<String, Vec<u8>>>, scrub_batch_size: usize) -> Self {
+        Self {
+            hybrid_cache,
+            tracked_chunks: Data::new(Mutex::new(IndexSet::new())),
+            scrub_cursor: Data::new(AtomicUsize::new(0)),
+            scrub_batch_size,
+            counters: Data::new(CacheIntegrityCounters::default()),
+        }
+    }
+
+    pub fn verify(&self, address: &XorName, content: &[u8]) -> bool {
+        self.counters.verified.fetch_add(1, Ordering::Relaxed);
+        if DataChunk::from_content(Bytes::copy_from_slice(content)).address == *address {
+            true
+        } else {
+            warn!("chunk content for [{}] does not match its address - cache is corrupted", hex::encode(address));
+            self.counters.corrupted.fetch_add(1, Ordering::Relaxed);
+            false
+        }
+    }
+
+    pub fn evict(&self, address: &XorName) {
+        self.hybrid_cache.remove(&hex::encode(address));
+        self.tracked_chunks.lock().unwrap().shift_remove(address);
+    }
+
+    pub fn track(&self, address: &XorName) {
+        let mut tracked_chunks = self.tracked_chunks.lock().unwrap();
+        if tracked_chunks.len() < MAX_TRACKED_CHUNKS {
+            tracked_chunks.insert(*address);
+        }
+    }
+
+    pub async fn scrub(&self) -> usize {
+        let addresses = self.next_scrub_batch();
+        let mut corrupted = 0;
+        for address in addresses.iter() {
+            match self.hybrid_cache.storage().load(&hex::encode(address)).await {
+                Ok(Load::Entry { value, .. }) => {
+                    self.counters.scrubbed.fetch_add(1, Ordering::Relaxed);
+                    if !self.verify(address, &value) {
+                        self.evict(address);
+                        corrupted += 1;
+                    }
+                }
+                Ok(Load::Piece { .. }) | Ok(Load::Throttled) => debug!("chunk [{}] not yet readable from disk - skipping scrub", hex::encode(address)),
+                Ok(Load::Miss) => {
+                    debug!("chunk [{}] no longer cached - no longer tracking", hex::encode(address));
+                    self.tracked_chunks.lock().unwrap().shift_remove(address);
+                }
+                Err(e) => warn!("Failed to read chunk [{}] from hybrid cache to scrub: {:?}", hex::encode(address), e),
+            }
+        }
+        if corrupted > 0 {
+            info!("scrubbed [{}] chunks and evicted [{}] corrupted chunks", addresses.len(), corrupted);
+        }
+        corrupted
+    }
+
+    fn next_scrub_batch(&self) -> Vec<XorName> {
+        let tracked_chunks = self.tracked_chunks.lock().unwrap();
+        if tracked_chunks.is_empty() {
+            return Vec::new();
+        }
+        let start = self.scrub_cursor.load(Ordering::Relaxed) % tracked_chunks.len();
+        let batch_size = self.scrub_batch_size.min(tracked_chunks.len());
+        self.scrub_cursor.store((start + batch_size) % tracked_chunks.len(), Ordering::Relaxed);
+        (0..batch_size)
+            .filter_map(|i| tracked_chunks.get_index((start + i) % tracked_chunks.len()).cloned())
+            .collect()
+    }
+
+    pub fn stats(&self) -> CacheIntegrityStats {
+        CacheIntegrityStats {
+            verified: self.counters.verified.load(Ordering::Relaxed),
+            corrupted: self.counters.corrupted.load(Ordering::Relaxed),
+            scrubbed: self.counters.scrubbed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for CacheIntegrity {
+    fn is_active(&self) -> bool {
+        self.scrub_batch_size > 0
+    }
+
+    fn schedule(&self) -> Option<Schedule> {
+        Some("1/10 * * * * *".parse().unwrap())
+    }
+
+    async fn handle(&mut self) {
+        self.scrub().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foyer::{BlockEngineConfig, DeviceBuilder, FsDeviceBuilder, HybridCacheBuilder, HybridCachePolicy, PsyncIoEngineConfig};
+    use tempfile::TempDir;
+
+    async fn create_cache_integrity(directory: &TempDir, scrub_batch_size: usize) -> CacheIntegrity {
+        let device = FsDeviceBuilder::new(directory.path()).with_capacity(16 * 1024 * 1024).build().unwrap();
+        let hybrid_cache = HybridCacheBuilder::new()
+            .with_policy(HybridCachePolicy::WriteOnInsertion)
+            .memory(1024)
+            .storage()
+            .with_io_engine_config(PsyncIoEngineConfig::new())
+            .with_engine_config(BlockEngineConfig::new(device))
+            .build().await.unwrap();
+        CacheIntegrity::new(Data::new(hybrid_cache), scrub_batch_size)
+    }
+
+    async fn insert_chunk(cache_integrity: &CacheIntegrity, content: &str, cached_content: &str) -> XorName {
+        let address = DataChunk::from_content(Bytes::from(content.to_string())).address;
+        cache_integrity.hybrid_cache.insert(hex::encode(address), cached_content.as_bytes().to_vec());
+        cache_integrity.hybrid_cache.storage().wait().await;
+        cache_integrity.track(&address);
+        address
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_integrity = create_cache_integrity(&directory, 1).await;
+        let address = DataChunk::from_content(Bytes::from("content")).address;
+
+        assert!(cache_integrity.verify(&address, "content".as_bytes()));
+        assert!(!cache_integrity.verify(&address, "corrupted".as_bytes()));
+
+        let stats = cache_integrity.stats();
+        assert_eq!(stats.verified, 2);
+        assert_eq!(stats.corrupted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_scrub_evicts_corrupted_chunks() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_integrity = create_cache_integrity(&directory, 10).await;
+        insert_chunk(&cache_integrity, "valid", "valid").await;
+        let corrupted_address = insert_chunk(&cache_integrity, "valid too", "corrupted").await;
+
+        assert_eq!(cache_integrity.scrub().await, 1);
+        assert!(cache_integrity.hybrid_cache.get(&hex::encode(corrupted_address)).await.unwrap().is_none());
+        assert_eq!(cache_integrity.tracked_chunks.lock().unwrap().len(), 1);
+        assert_eq!(cache_integrity.stats().scrubbed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_scrub_is_throttled_to_batch_size() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_integrity = create_cache_integrity(&directory, 1).await;
+        insert_chunk(&cache_integrity, "first", "first").await;
+        insert_chunk(&cache_integrity, "second", "second").await;
+
+        cache_integrity.scrub().await;
+        assert_eq!(cache_integrity.stats().scrubbed, 1);
+        cache_integrity.scrub().await;
+        assert_eq!(cache_integrity.stats().scrubbed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_scrub_untracks_evicted_chunks() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_integrity = create_cache_integrity(&directory, 1).await;
+        cache_integrity.track(&DataChunk::from_content(Bytes::from("missing")).address);
+
+        assert_eq!(cache_integrity.scrub().await, 0);
+        assert!(cache_integrity.tracked_chunks.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_scrub_reads_disk_without_populating_memory() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache_integrity = create_cache_integrity(&directory, 10).await;
+        let address = insert_chunk(&cache_integrity, "on disk", "on disk").await;
+        cache_integrity.hybrid_cache.memory().remove(&hex::encode(address));
+
+        assert_eq!(cache_integrity.scrub().await, 0);
+        assert_eq!(cache_integrity.stats().scrubbed, 1);
+        assert!(!cache_integrity.hybrid_cache.memory().contains(&hex::encode(address)));
+    }
+}
@@ -1,4 +1,5 @@
 use std::fs;
+use std::time::Duration;
 use actix_web::web::Data;
 use async_job::{Job, Schedule};
 use async_trait::async_trait;
@@ -10,21 +11,37 @@ use crate::error::{CheckError, CreateError, GetError, UpdateError};
 use crate::error::chunk_error::ChunkError;
 use mockall::mock;
 use crate::client::client_harness::ClientHarness;
+use crate::client::negative_cache::NegativeCache;
+use crate::client::cache_integrity::CacheIntegrity;
+use crate::client::chunk_fetch_policy::ChunkFetchPolicy;
+use crate::client::chunk_source::ChunkSource;
+use crate::client::upstream_chunk_source::UpstreamChunkSource;
 use crate::client::command::Command;
+use crate::client::command::command_journal::CommandJournal;
+use crate::client::command::command_metadata::CommandMetadata;
 use ant_core::data::XorName;
+use log::warn;
+use crate::client::network_backend::{self, ChunksQuote};
+use crate::client::wallet_backend;
 
 #[derive(Clone)]
 pub struct CachingClient {
-    pub client_harness: Data<tokio::sync::Mutex<ClientHarness>>,
+    pub client_harness: Data<ClientHarness>,
     pub ant_tp_config: AntTpConfig,
     pub hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
     pub command_executor: Data<Sender<Box<dyn Command>>>,
+    pub command_journal: CommandJournal,
+    pub negative_cache: NegativeCache,
+    pub cache_integrity: CacheIntegrity,
+    pub chunk_fetch_policy: ChunkFetchPolicy,
+    pub chunk_sources: Data<Vec<Box<dyn ChunkSource>>>,
 }
 
 mock! {
     pub CachingClient {
-        pub fn new(client_harness: Data<tokio::sync::Mutex<ClientHarness>>, ant_tp_config: AntTpConfig,
-                   hybrid_cache: Data<HybridCache<String, Vec<u8>>>, command_executor: Data<Sender<Box<dyn Command>>>) -> Self;
+        pub fn new(client_harness: Data<ClientHarness>, ant_tp_config: AntTpConfig,
+                   hybrid_cache: Data<HybridCache<String, Vec<u8>>>, command_executor: Data<Sender<Box<dyn Command>>>,
+                   command_journal: CommandJournal) -> Self;
         pub async fn download_stream(
             &self,
             addr: &XorName,
@@ -32,13 +49,20 @@ mock! {
             range_to: i64,
         ) -> Result<Bytes, ChunkError>;
         pub fn get_derived_ranges(&self, range_from: i64, range_to: i64, length: Option<u64>) -> (u64, u64);
+        pub async fn ensure_funds(&self, contents: Vec<Bytes>) -> Result<(), CreateError>;
+        pub async fn quote_chunks(&self, contents: Vec<Bytes>) -> Result<ChunksQuote, CreateError>;
         pub async fn send_create_command(&self, command: Box<dyn Command>) -> Result<(), CreateError>;
         pub async fn send_update_command(&self, command: Box<dyn Command>) -> Result<(), UpdateError>;
         pub async fn send_get_command(&self, command: Box<dyn Command>) -> Result<(), GetError>;
         pub async fn send_check_command(&self, command: Box<dyn Command>) -> Result<(), CheckError>;
         pub fn get_hybrid_cache(&self) -> &Data<HybridCache<String, Vec<u8>>>;
-        pub fn get_client_harness(&self) -> &Data<tokio::sync::Mutex<ClientHarness>>;
+        pub fn get_client_harness(&self) -> &Data<ClientHarness>;
         pub fn get_ant_tp_config(&self) -> &AntTpConfig;
+        pub fn get_command_journal(&self) -> &CommandJournal;
+        pub fn get_negative_cache(&self) -> &NegativeCache;
+        pub fn get_cache_integrity(&self) -> &CacheIntegrity;
+        pub fn get_chunk_fetch_policy(&self) -> &ChunkFetchPolicy;
+        pub fn get_chunk_sources(&self) -> &Data<Vec<Box<dyn ChunkSource>>>;
     }
     impl Clone for CachingClient {
         fn clone(&self) -> Self;
@@ -59,20 +83,25 @@ impl Job for CachingClient {
         Some("1/10 * * * * *".parse().unwrap())
     }
     async fn handle(&mut self) {
-        let mut harness = self.client_harness.get_ref().lock().await;
-        harness.try_sleep();
+        self.client_harness.try_sleep();
     }
 }
 
 impl CachingClient {
 
-    pub fn new(client_harness: Data<tokio::sync::Mutex<ClientHarness>>, ant_tp_config: AntTpConfig,
-               hybrid_cache: Data<HybridCache<String, Vec<u8>>>, command_executor: Data<Sender<Box<dyn Command>>>) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, ant_tp_config: AntTpConfig,
+               hybrid_cache: Data<HybridCache<String, Vec<u8>>>, command_executor: Data<Sender<Box<dyn Command>>>,
+               command_journal: CommandJournal) -> Self {
         let cache_dir = ant_tp_config.clone().map_cache_directory;
         CachingClient::create_tmp_dir(cache_dir.clone());
+        let negative_cache = NegativeCache::new(hybrid_cache.clone(), ant_tp_config.negative_cache_ttl);
+        let cache_integrity = CacheIntegrity::new(hybrid_cache.clone(), ant_tp_config.scrub_batch_size);
+        let chunk_fetch_policy = CachingClient::create_chunk_fetch_policy(&ant_tp_config);
+        let chunk_sources = Data::new(CachingClient::create_chunk_sources(&ant_tp_config));
 
         Self {
-            client_harness, ant_tp_config, hybrid_cache, command_executor
+            client_harness, ant_tp_config, hybrid_cache, command_executor, command_journal, negative_cache, cache_integrity,
+            chunk_fetch_policy, chunk_sources
         }
     }
 
@@ -80,7 +109,7 @@ impl CachingClient {
         &self.hybrid_cache
     }
 
-    pub fn get_client_harness(&self) -> &Data<tokio::sync::Mutex<ClientHarness>> {
+    pub fn get_client_harness(&self) -> &Data<ClientHarness> {
         &self.client_harness
     }
 
@@ -88,25 +117,100 @@ impl CachingClient {
         &self.ant_tp_config
     }
 
+    pub fn get_command_journal(&self) -> &CommandJournal {
+        &self.command_journal
+    }
+
+    pub fn get_negative_cache(&self) -> &NegativeCache {
+        &self.negative_cache
+    }
+
+    pub fn get_cache_integrity(&self) -> &CacheIntegrity {
+        &self.cache_integrity
+    }
+
+    pub fn get_chunk_fetch_policy(&self) -> &ChunkFetchPolicy {
+        &self.chunk_fetch_policy
+    }
+
+    pub fn get_chunk_sources(&self) -> &Data<Vec<Box<dyn ChunkSource>>> {
+        &self.chunk_sources
+    }
+
+    fn create_chunk_fetch_policy(ant_tp_config: &AntTpConfig) -> ChunkFetchPolicy {
+        let hedge_delay = match ant_tp_config.chunk_fetch_hedge_ms {
+            0 => None,
+            hedge_ms => Some(Duration::from_millis(hedge_ms)),
+        };
+        ChunkFetchPolicy::new(
+            Duration::from_secs(ant_tp_config.chunk_fetch_timeout),
+            ant_tp_config.chunk_fetch_retries,
+            Duration::from_millis(ant_tp_config.chunk_fetch_backoff_ms),
+            hedge_delay,
+        )
+    }
+
+    fn create_chunk_sources(ant_tp_config: &AntTpConfig) -> Vec<Box<dyn ChunkSource>> {
+        let mut chunk_sources: Vec<Box<dyn ChunkSource>> = Vec::new();
+        if !ant_tp_config.upstream_urls.is_empty() {
+            chunk_sources.push(Box::new(
+                UpstreamChunkSource::new(ant_tp_config.upstream_urls.clone(), Duration::from_secs(ant_tp_config.upstream_timeout))
+            ));
+        }
+        chunk_sources
+    }
+
     fn create_tmp_dir(cache_dir: String) {
         if !fs::exists(cache_dir.clone()).unwrap() {
             fs::create_dir_all(cache_dir.clone()).unwrap_or_default()
         }
     }
 
+    /// Refuses to upload chunks which the wallet cannot pay for, unless the funding check is disabled.
+    pub async fn ensure_funds(&self, contents: Vec<Bytes>) -> Result<(), CreateError> {
+        if self.ant_tp_config.funding_check_disabled {
+            return Ok(());
+        }
+        let wallet_backend = self.client_harness.get_wallet_backend().map_err(|e| CreateError::InvalidData(e.to_string()))?;
+        let network_backend = match self.client_harness.get_network_backend().await {
+            Ok(network_backend) => network_backend,
+            Err(e) => {
+                warn!("skipping funding check, as the network is offline: [{}]", e);
+                return Ok(());
+            }
+        };
+        wallet_backend::ensure_funds(network_backend.as_ref(), wallet_backend.as_ref(), contents, self.ant_tp_config.download_threads).await
+    }
+
+    /// Quotes uploading the chunks to the network, without paying for them.
+    pub async fn quote_chunks(&self, contents: Vec<Bytes>) -> Result<ChunksQuote, CreateError> {
+        let network_backend = self.client_harness.get_ref().get_network_backend().await
+            .map_err(|e| CreateError::NetworkOffline(e.to_string()))?;
+        network_backend::quote_chunks(network_backend.as_ref(), contents, self.ant_tp_config.download_threads).await
+            .map_err(|e| CreateError::NetworkOffline(e.to_string()))
+    }
+
     pub async fn send_create_command(&self, command: Box<dyn Command>) -> Result<(), CreateError> {
+        let command = CommandMetadata::current().apply(command);
+        self.command_journal.append(command.as_ref()).await?;
         Ok(self.command_executor.send(command).await?)
     }
 
     pub async fn send_update_command(&self, command: Box<dyn Command>) -> Result<(), UpdateError> {
+        let command = CommandMetadata::current().apply(command);
+        self.command_journal.append(command.as_ref()).await?;
         Ok(self.command_executor.send(command).await?)
     }
 
     pub async fn send_get_command(&self, command: Box<dyn Command>) -> Result<(), GetError> {
+        let command = CommandMetadata::current().apply(command);
+        self.command_journal.append(command.as_ref()).await?;
         Ok(self.command_executor.send(command).await?)
     }
 
     pub async fn send_check_command(&self, command: Box<dyn Command>) -> Result<(), CheckError> {
+        let command = CommandMetadata::current().apply(command);
+        self.command_journal.append(command.as_ref()).await?;
         Ok(self.command_executor.send(command).await?)
     }
 }
@@ -199,17 +303,18 @@ mod tests {
 
         let ctx = MockCachingClient::new_context();
         ctx.expect()
-            .returning(|_client_harness, config, _hybrid_cache, _command_executor| {
+            .returning(|_client_harness, config, _hybrid_cache, _command_executor, _command_journal| {
                 let cache_dir = config.clone().map_cache_directory;
                 CachingClient::create_tmp_dir(cache_dir.clone());
                 MockCachingClient::default()
             });
 
         let _client = MockCachingClient::new(
-            Data::new(tokio::sync::Mutex::new(client_harness)),
+            Data::new(client_harness),
             ant_tp_config,
             Data::new(hybrid_cache),
             Data::new(tx),
+            CommandJournal::new(cache_path.join("command_journal")).unwrap(),
         );
 
         assert!(cache_path.exists());
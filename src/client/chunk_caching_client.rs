@@ -1,14 +1,20 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use ant_core::data::{DataChunk, XorName};
 use async_trait::async_trait;
 use bytes::Bytes;
 use chunk_streamer::chunk_streamer::ChunkGetter;
-use log::{debug, error, info};
+use foyer::Source;
+use log::{debug, error, info, warn};
 use mockall::mock;
 use mockall_double::double;
 #[double]
 use crate::client::CachingClient;
+use crate::client::CHUNK_NEGATIVE_CACHE_KEY;
+use crate::client::network_backend::ChunksQuote;
 use crate::client::command::chunk::create_chunk_command::CreateChunkCommand;
 use crate::error::chunk_error::ChunkError;
+use crate::error::GetError;
 use crate::controller::StoreType;
 
 #[derive(Clone)]
@@ -25,6 +31,8 @@ mock! {
             store_type: StoreType
         ) -> Result<XorName, ChunkError>;
         pub async fn chunk_get_internal(&self, address: &XorName) -> Result<DataChunk, ChunkError>;
+        pub fn chunk_negative_cache_bypass(&self, address: &XorName);
+        pub async fn chunk_quote(&self, chunk: &DataChunk) -> Result<ChunksQuote, ChunkError>;
     }
     impl Clone for ChunkCachingClient {
         fn clone(&self) -> Self;
@@ -45,7 +53,12 @@ impl ChunkCachingClient {
         chunk: &DataChunk,
         store_type: StoreType
     ) -> Result<XorName, ChunkError> {
+        // check before caching, so that refused uploads leave nothing behind
+        if store_type == StoreType::Network {
+            self.caching_client.ensure_funds(vec![chunk.content.clone()]).await?;
+        }
         self.caching_client.get_hybrid_cache().insert(hex::encode(chunk.address), Vec::from(chunk.content.clone()));
+        self.caching_client.get_cache_integrity().track(&chunk.address);
         debug!("creating chunk with address [{}] in cache", hex::encode(chunk.address));
         if store_type == StoreType::Network {
             let command = Box::new(
@@ -56,30 +69,90 @@ impl ChunkCachingClient {
         Ok(chunk.address)
     }
 
+    /// Quotes uploading the chunk to the network, without caching or uploading it.
+    pub async fn chunk_quote(&self, chunk: &DataChunk) -> Result<ChunksQuote, ChunkError> {
+        Ok(self.caching_client.quote_chunks(vec![chunk.content.clone()]).await?)
+    }
+
     pub async fn chunk_get_internal(&self, address: &XorName) -> Result<DataChunk, ChunkError> {
+        self.chunk_get_verified(address, true).await
+    }
+
+    async fn chunk_get_verified(&self, address: &XorName, is_refetch_allowed: bool) -> Result<DataChunk, ChunkError> {
+        let negative_cache_key = format!("{}{}", CHUNK_NEGATIVE_CACHE_KEY, hex::encode(address));
+        if let Some(reason) = self.caching_client.get_negative_cache().get(&negative_cache_key) {
+            info!("negative cache for chunk [{}] from hybrid cache", hex::encode(address));
+            return Err(ChunkError::GetError(GetError::RecordNotFound(reason)));
+        }
         let local_address = address.clone();
-        let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&hex::encode(local_address), {
-            let client = self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?;
-            || async move {
-                match client.chunk_get(&local_address).await {
-                    Ok(Some(chunk)) => {
-                        info!("retrieved chunk for [{}] from network - storing in hybrid cache", hex::encode(local_address));
-                        Ok(Vec::from(chunk.content))
-                    }
-                    Ok(None) => {
-                        error!("Failed to retrieve chunk for [{}] from network", hex::encode(local_address));
-                        Err(anyhow::anyhow!(format!("Failed to retrieve chunk for [{}] from network", hex::encode(local_address))))
-                    }
-                    Err(err) => {
-                        error!("Failed to retrieve chunk for [{}] from network {:?}", hex::encode(local_address), err);
-                        Err(anyhow::anyhow!(format!("Failed to retrieve chunk for [{}] from network {:?}", hex::encode(local_address), err)))
+        let chunk_sources = self.caching_client.get_chunk_sources().clone();
+        let client_harness = self.caching_client.get_client_harness().clone();
+        let chunk_fetch_policy = self.caching_client.get_chunk_fetch_policy().clone();
+        let is_not_found = Arc::new(AtomicBool::new(false));
+        let local_is_not_found = is_not_found.clone();
+        let cache_entry = match self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&hex::encode(local_address), || async move {
+            for chunk_source in chunk_sources.iter() {
+                match chunk_source.chunk_get(&local_address).await {
+                    Ok(chunk) => {
+                        info!("retrieved chunk for [{}] from [{}] - storing in hybrid cache", hex::encode(local_address), chunk_source.name());
+                        return Ok(Vec::from(chunk.content));
                     }
+                    Err(err) => debug!("failed to retrieve chunk for [{}] from [{}]: {:?}", hex::encode(local_address), chunk_source.name(), err),
+                }
+            }
+            // only connect to the network on a cache miss, once any other chunk sources have failed
+            let network_backend = match client_harness.get_ref().get_network_backend().await {
+                Ok(network_backend) => network_backend,
+                Err(err) => {
+                    error!("Failed to connect to network to retrieve chunk for [{}] {:?}", hex::encode(local_address), err);
+                    return Err(anyhow::anyhow!(format!("Failed to connect to network to retrieve chunk for [{}] {:?}", hex::encode(local_address), err)));
+                }
+            };
+            match chunk_fetch_policy.chunk_get(&network_backend, &local_address).await {
+                Ok(Some(chunk)) => {
+                    info!("retrieved chunk for [{}] from network - storing in hybrid cache", hex::encode(local_address));
+                    Ok(Vec::from(chunk.content))
+                }
+                Ok(None) => {
+                    local_is_not_found.store(true, Ordering::Relaxed);
+                    error!("Failed to retrieve chunk for [{}] from network", hex::encode(local_address));
+                    Err(anyhow::anyhow!(format!("Failed to retrieve chunk for [{}] from network", hex::encode(local_address))))
+                }
+                Err(err) => {
+                    error!("Failed to retrieve chunk for [{}] from network {:?}", hex::encode(local_address), err);
+                    Err(anyhow::anyhow!(format!("Failed to retrieve chunk for [{}] from network {:?}", hex::encode(local_address), err)))
+                }
+            }
+        }).await {
+            Ok(cache_entry) => cache_entry,
+            Err(e) => {
+                // store negative cache to avoid repeated lookups, but only when the network reported the
+                // chunk as not stored, as timeouts and an unreachable network may be transient
+                if is_not_found.load(Ordering::Relaxed) {
+                    self.caching_client.get_negative_cache().insert(negative_cache_key, e.to_string());
                 }
+                return Err(e.into());
+            }
+        };
+        // disk cache files may be corrupted, so verify the content still matches the address
+        let cache_integrity = self.caching_client.get_cache_integrity();
+        if cache_entry.source() == Source::Disk && !cache_integrity.verify(address, cache_entry.value()) {
+            cache_integrity.evict(address);
+            if is_refetch_allowed {
+                warn!("evicted corrupted chunk for [{}] from hybrid cache - refetching", hex::encode(address));
+                return Box::pin(self.chunk_get_verified(address, false)).await;
+            } else {
+                return Err(ChunkError::GetError(GetError::Decode(format!("Corrupted chunk for [{}] in hybrid cache", hex::encode(address)))));
             }
-        }).await?;
+        }
+        cache_integrity.track(address);
         info!("retrieved chunk for [{}] from hybrid cache", hex::encode(address));
         Ok(DataChunk::new(address.clone(), Bytes::from(cache_entry.value().to_vec())))
     }
+
+    pub fn chunk_negative_cache_bypass(&self, address: &XorName) {
+        self.caching_client.get_negative_cache().bypass(&format!("{}{}", CHUNK_NEGATIVE_CACHE_KEY, hex::encode(address)));
+    }
 }
 
 #[async_trait]
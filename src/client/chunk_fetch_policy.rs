@@ -0,0 +1,250 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use actix_web::web::Data;
+use ant_core::data::{DataChunk, Error, XorName};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::client::network_backend::NetworkBackend;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default, PartialEq)]
+pub struct ChunkFetchStats {
+    #[schema(read_only)]
+    pub fetches: u64,
+    #[schema(read_only)]
+    pub timeouts: u64,
+    #[schema(read_only)]
+    pub retries: u64,
+    #[schema(read_only)]
+    pub hedges: u64,
+    #[schema(read_only)]
+    pub hedge_wins: u64,
+    #[schema(read_only)]
+    pub failures: u64,
+}
+
+#[derive(Debug, Default)]
+struct ChunkFetchCounters {
+    fetches: AtomicU64,
+    timeouts: AtomicU64,
+    retries: AtomicU64,
+    hedges: AtomicU64,
+    hedge_wins: AtomicU64,
+    failures: AtomicU64,
+}
+
+/// Retrieves chunks from the network with a timeout per attempt, a bounded number of retries
+/// and, optionally, a hedged duplicate request when an attempt is slow.
+///
+/// Retries back off exponentially with full jitter, so that concurrent requests for a range do
+/// not retry in lock step. Chunks which the network reports as not stored are not retried. A
+/// timeout of 0 disables the timeout and a hedge delay of `None` disables hedging.
+#[derive(Clone)]
+pub struct ChunkFetchPolicy {
+    timeout: Duration,
+    retries: u32,
+    retry_backoff: Duration,
+    hedge_delay: Option<Duration>,
+    counters: Data<ChunkFetchCounters>,
+}
+
+impl ChunkFetchPolicy {
+    pub fn new(timeout: Duration, retries: u32, retry_backoff: Duration, hedge_delay: Option<Duration>) -> Self {
+        Self { timeout, retries, retry_backoff, hedge_delay, counters: Data::new(ChunkFetchCounters::default()) }
+    }
+
+    pub async fn chunk_get(&self, network_backend: &Arc<dyn NetworkBackend>, address: &XorName) -> Result<Option<DataChunk>, Error> {
+        self.counters.fetches.fetch_add(1, Ordering::Relaxed);
+        let mut retry = 0;
+        loop {
+            match self.attempt(network_backend, address).await {
+                Err(e) if retry < self.retries => {
+                    retry += 1;
+                    let backoff = self.get_backoff(retry);
+                    self.counters.retries.fetch_add(1, Ordering::Relaxed);
+                    warn!("failed to retrieve chunk [{}] from network: {:?} - retry [{}] of [{}] in [{:?}]",
+                        hex::encode(address), e, retry, self.retries, backoff);
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(e) => {
+                    self.counters.failures.fetch_add(1, Ordering::Relaxed);
+                    return Err(e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    pub fn stats(&self) -> ChunkFetchStats {
+        ChunkFetchStats {
+            fetches: self.counters.fetches.load(Ordering::Relaxed),
+            timeouts: self.counters.timeouts.load(Ordering::Relaxed),
+            retries: self.counters.retries.load(Ordering::Relaxed),
+            hedges: self.counters.hedges.load(Ordering::Relaxed),
+            hedge_wins: self.counters.hedge_wins.load(Ordering::Relaxed),
+            failures: self.counters.failures.load(Ordering::Relaxed),
+        }
+    }
+
+    async fn attempt(&self, network_backend: &Arc<dyn NetworkBackend>, address: &XorName) -> Result<Option<DataChunk>, Error> {
+        if self.timeout.is_zero() {
+            return self.hedged_chunk_get(network_backend, address).await;
+        }
+        match tokio::time::timeout(self.timeout, self.hedged_chunk_get(network_backend, address)).await {
+            Ok(result) => result,
+            Err(_) => {
+                self.counters.timeouts.fetch_add(1, Ordering::Relaxed);
+                Err(Error::Timeout(format!("timed out after [{:?}] retrieving chunk [{}]", self.timeout, hex::encode(address))))
+            }
+        }
+    }
+
+    /// Sends a duplicate request if the first has not completed within the hedge delay, taking
+    /// whichever succeeds first. If the first request fails, the attempt fails and is retried.
+    async fn hedged_chunk_get(&self, network_backend: &Arc<dyn NetworkBackend>, address: &XorName) -> Result<Option<DataChunk>, Error> {
+        let hedge_delay = match self.hedge_delay {
+            Some(hedge_delay) => hedge_delay,
+            None => return network_backend.chunk_get(address).await,
+        };
+        let primary = network_backend.chunk_get(address);
+        let hedge = async {
+            tokio::time::sleep(hedge_delay).await;
+            debug!("chunk [{}] not retrieved within [{:?}] - sending hedged request", hex::encode(address), hedge_delay);
+            self.counters.hedges.fetch_add(1, Ordering::Relaxed);
+            network_backend.chunk_get(address).await
+        };
+        tokio::pin!(primary, hedge);
+        tokio::select! {
+            result = &mut primary => result,
+            result = &mut hedge => match result {
+                Ok(chunk) => {
+                    info!("hedged request for chunk [{}] completed first", hex::encode(address));
+                    self.counters.hedge_wins.fetch_add(1, Ordering::Relaxed);
+                    Ok(chunk)
+                }
+                Err(e) => {
+                    debug!("hedged request for chunk [{}] failed: {:?} - waiting for first request", hex::encode(address), e);
+                    primary.await
+                }
+            },
+        }
+    }
+
+    fn get_backoff(&self, retry: u32) -> Duration {
+        let ceiling = self.retry_backoff.saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)));
+        ceiling.mul_f64(rand::random::<f64>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use ant_core::data::{compute_address, UploadEvent, U256};
+    use tokio::sync::mpsc;
+    use crate::client::network_backend::ChunksUpload;
+
+    /// Returns the chunk after the latency of each call in turn, or fails if the latency is `None`.
+    struct ScriptedNetworkBackend {
+        content: Bytes,
+        latencies: Vec<Option<u64>>,
+        calls: AtomicU64,
+    }
+
+    impl ScriptedNetworkBackend {
+        fn create(content: &'static str, latencies: Vec<Option<u64>>) -> Arc<dyn NetworkBackend> {
+            Arc::new(Self { content: Bytes::from(content), latencies, calls: AtomicU64::new(0) })
+        }
+    }
+
+    #[async_trait]
+    impl NetworkBackend for ScriptedNetworkBackend {
+        fn name(&self) -> String {
+            "scripted".to_string()
+        }
+
+        async fn chunk_get(&self, address: &XorName) -> Result<Option<DataChunk>, Error> {
+            let call = self.calls.fetch_add(1, Ordering::Relaxed) as usize;
+            match self.latencies.get(call).cloned().flatten() {
+                Some(latency_ms) => {
+                    tokio::time::sleep(Duration::from_millis(latency_ms)).await;
+                    Ok(Some(DataChunk::new(*address, self.content.clone())))
+                }
+                None => Err(Error::Network(format!("scripted failure [{}]", call))),
+            }
+        }
+
+        async fn chunk_put(&self, content: Bytes) -> Result<XorName, Error> {
+            Ok(compute_address(&content))
+        }
+
+        async fn chunks_upload(&self, _chunks: Vec<Bytes>, _progress: Option<&mpsc::Sender<UploadEvent>>) -> Result<ChunksUpload, Error> {
+            Ok(ChunksUpload::default())
+        }
+
+        async fn chunk_quote(&self, _address: &XorName, _size: u64) -> Result<U256, Error> {
+            Ok(U256::ZERO)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chunk_get_times_out_and_retries() {
+        let address = compute_address(&Bytes::from("slow"));
+        let network_backend = ScriptedNetworkBackend::create("slow", vec![Some(5_000), None, Some(10)]);
+        let chunk_fetch_policy = ChunkFetchPolicy::new(Duration::from_millis(100), 2, Duration::from_millis(10), None);
+
+        let chunk = chunk_fetch_policy.chunk_get(&network_backend, &address).await.unwrap().unwrap();
+        assert_eq!(chunk.content, Bytes::from("slow"));
+        assert_eq!(chunk_fetch_policy.stats(), ChunkFetchStats {
+            fetches: 1, timeouts: 1, retries: 2, hedges: 0, hedge_wins: 0, failures: 0
+        });
+    }
+
+    #[tokio::test]
+    async fn test_chunk_get_fails_after_retries() {
+        let address = compute_address(&Bytes::from("stalled"));
+        let network_backend = ScriptedNetworkBackend::create("stalled", vec![Some(5_000), Some(5_000)]);
+        let chunk_fetch_policy = ChunkFetchPolicy::new(Duration::from_millis(100), 1, Duration::from_millis(10), None);
+
+        assert!(matches!(chunk_fetch_policy.chunk_get(&network_backend, &address).await, Err(Error::Timeout(_))));
+        let stats = chunk_fetch_policy.stats();
+        assert_eq!(stats.timeouts, 2);
+        assert_eq!(stats.retries, 1);
+        assert_eq!(stats.failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_chunk_get_hedges_slow_requests() {
+        let address = compute_address(&Bytes::from("hedged"));
+        let network_backend = ScriptedNetworkBackend::create("hedged", vec![Some(5_000), Some(10)]);
+        let chunk_fetch_policy = ChunkFetchPolicy::new(Duration::from_secs(10), 0, Duration::from_millis(10), Some(Duration::from_millis(50)));
+
+        let started = std::time::Instant::now();
+        assert!(chunk_fetch_policy.chunk_get(&network_backend, &address).await.unwrap().is_some());
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(chunk_fetch_policy.stats(), ChunkFetchStats {
+            fetches: 1, timeouts: 0, retries: 0, hedges: 1, hedge_wins: 1, failures: 0
+        });
+    }
+
+    #[tokio::test]
+    async fn test_chunk_get_does_not_hedge_fast_requests() {
+        let address = compute_address(&Bytes::from("fast"));
+        let network_backend = ScriptedNetworkBackend::create("fast", vec![Some(10)]);
+        let chunk_fetch_policy = ChunkFetchPolicy::new(Duration::from_secs(10), 0, Duration::from_millis(10), Some(Duration::from_millis(500)));
+
+        assert!(chunk_fetch_policy.chunk_get(&network_backend, &address).await.unwrap().is_some());
+        assert_eq!(chunk_fetch_policy.stats().hedges, 0);
+    }
+
+    #[test]
+    fn test_backoff_is_jittered_within_ceiling() {
+        let chunk_fetch_policy = ChunkFetchPolicy::new(Duration::from_secs(1), 3, Duration::from_millis(100), None);
+        for _ in 0..100 {
+            assert!(chunk_fetch_policy.get_backoff(1) <= Duration::from_millis(100));
+            assert!(chunk_fetch_policy.get_backoff(3) <= Duration::from_millis(400));
+        }
+    }
+}
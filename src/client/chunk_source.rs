@@ -0,0 +1,10 @@
+use ant_core::data::{DataChunk, XorName};
+use async_trait::async_trait;
+use crate::error::chunk_error::ChunkError;
+
+/// Alternative source of chunks, consulted on a cache miss before falling back to the Autonomi network.
+#[async_trait]
+pub trait ChunkSource: Send + Sync {
+    fn name(&self) -> String;
+    async fn chunk_get(&self, address: &XorName) -> Result<DataChunk, ChunkError>;
+}
@@ -1,57 +1,255 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use actix_web::web::Data;
-use ant_core::data::{Client, ClientConfig, Error};
+use ant_core::data::{Client, ClientConfig, Error, EvmAddress, U256, Wallet};
 use evmlib::Network;
-use log::{debug, info};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::client::fake_network_backend::FakeNetworkBackend;
+use crate::client::network_backend::{ClientNetworkBackend, NetworkBackend};
+use crate::client::wallet_backend::{EvmWalletBackend, FakeWalletBackend, WalletBackend};
 use crate::config::anttp_config::AntTpConfig;
 
+const MAX_CONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Connection state of the network, as reported by the status endpoints.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default, PartialEq)]
+pub struct NetworkStatus {
+    #[schema(read_only)]
+    pub backend: String,
+    #[schema(read_only)]
+    pub connected: bool,
+    /// True while connecting to the network.
+    #[schema(read_only)]
+    pub connecting: bool,
+    #[schema(read_only)]
+    pub last_connect_error: Option<String>,
+    #[schema(read_only)]
+    pub idle_seconds: u64,
+    #[schema(read_only)]
+    pub idle_disconnect: u64,
+}
+
+/// Wallets and clients of the harness, which are locked briefly and never across a connection attempt.
+struct ClientHarnessState {
+    wallet: Wallet,
+    /// Keystore wallets which requests may select to pay for uploads, by name.
+    wallets: HashMap<String, Wallet>,
+    maybe_client: Option<Data<Client>>,
+    /// Clients paying with keystore wallets, sharing the network connection of the client.
+    wallet_clients: HashMap<EvmAddress, Data<Client>>,
+    last_connect_error: Option<String>,
+    connect_failures: u32,
+    /// Connection attempts fail fast until then, after a failure.
+    next_connect_time: Option<Instant>,
+}
+
+/// Provides the network backend and wallets, connecting to the network when first needed.
+///
+/// The harness is shared without an outer lock. Connecting is single flight: one caller connects
+/// while the others wait for it and share the client. After a failure, callers fail fast until
+/// the connect backoff has passed, which doubles with each failure up to `MAX_CONNECT_BACKOFF`.
 pub struct ClientHarness {
-    evm_network: Network,
     ant_tp_config: AntTpConfig,
-    maybe_client: Option<Data<Client>>,
-    last_accessed_time: u64,
+    maybe_fake_network_backend: Option<Arc<dyn NetworkBackend>>,
+    state: Mutex<ClientHarnessState>,
+    /// Held while connecting, by the one caller which connects.
+    connect_lock: tokio::sync::Mutex<()>,
+    last_accessed_time: AtomicU64,
 }
 
 impl ClientHarness {
     pub fn new(evm_network: Network, ant_tp_config: AntTpConfig) -> Self {
-        let last_accessed_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        ClientHarness { evm_network, ant_tp_config, maybe_client: None, last_accessed_time }
+        let maybe_fake_network_backend: Option<Arc<dyn NetworkBackend>> = if ant_tp_config.fake_network {
+            let directory = if ant_tp_config.fake_network_directory.is_empty() {
+                None
+            } else {
+                Some(PathBuf::from(&ant_tp_config.fake_network_directory))
+            };
+            Some(Arc::new(FakeNetworkBackend::new(directory, ant_tp_config.fake_network_faults.clone())))
+        } else {
+            None
+        };
+        // a random wallet, until one which can pay is set
+        let wallet = Wallet::new_with_random_wallet(evm_network);
+        let state = ClientHarnessState {
+            wallet, wallets: HashMap::new(), maybe_client: None, wallet_clients: HashMap::new(),
+            last_connect_error: None, connect_failures: 0, next_connect_time: None
+        };
+        ClientHarness {
+            ant_tp_config, maybe_fake_network_backend, state: Mutex::new(state), connect_lock: tokio::sync::Mutex::new(()),
+            last_accessed_time: AtomicU64::new(Self::get_current_time())
+        }
+    }
+
+    /// Returns the fake network when enabled, otherwise the Autonomi client, connecting if needed.
+    ///
+    /// The client pays with the wallet of the current request or command.
+    pub async fn get_network_backend(&self) -> Result<Arc<dyn NetworkBackend>, Error> {
+        let paying_wallet = self.get_paying_wallet()?;
+        if let Some(fake_network_backend) = self.maybe_fake_network_backend.clone() {
+            self.touch();
+            return Ok(fake_network_backend);
+        }
+        let client = self.get_client().await?;
+        let mut state = self.state.lock().unwrap();
+        if paying_wallet.address() == state.wallet.address() {
+            Ok(Arc::new(ClientNetworkBackend::new(client)))
+        } else {
+            let wallet_client = state.wallet_clients.entry(paying_wallet.address())
+                .or_insert_with(|| {
+                    debug!("creating client for wallet [{}]", paying_wallet.address());
+                    Data::new(Client::from_node(client.network().node().clone(), client.config().clone()).with_wallet(paying_wallet))
+                })
+                .clone();
+            Ok(Arc::new(ClientNetworkBackend::new(wallet_client)))
+        }
+    }
+
+    pub fn get_wallet(&self) -> Wallet {
+        self.state.lock().unwrap().wallet.clone()
+    }
+
+    /// Adds a keystore wallet, which requests may select by name to pay for uploads.
+    pub fn add_wallet(&self, name: String, wallet: Wallet) {
+        info!("wallet [{}] with address [{}] may pay for uploads", name, wallet.address());
+        self.state.lock().unwrap().wallets.insert(name, wallet);
     }
 
-    pub async fn get_client(&mut self) -> Result<Data<Client>, Error> {
-        self.last_accessed_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        if self.maybe_client.is_none() {
-            self.maybe_client = Some(Data::new(self.init_client().await?));
+    /// Returns the wallet selected by the current request or command, otherwise the default wallet.
+    pub fn get_paying_wallet(&self) -> Result<Wallet, Error> {
+        let state = self.state.lock().unwrap();
+        match CommandWallet::current() {
+            Some(command_wallet) => state.wallets.get(&command_wallet.name).cloned()
+                .ok_or_else(|| Error::Payment(format!("wallet [{}] is not loaded", command_wallet.name))),
+            None => Ok(state.wallet.clone()),
         }
-        match self.maybe_client.clone() {
-            Some(client) => Ok(client),
-            None => Err(Error::Network("Client failure".to_string()))
+    }
+
+    /// Sets the wallet which pays for uploads, disconnecting any client paying with the previous wallet.
+    pub fn set_wallet(&self, wallet: Wallet) {
+        info!("paying for uploads with wallet [{}]", wallet.address());
+        let mut state = self.state.lock().unwrap();
+        state.wallet = wallet;
+        if state.maybe_client.is_some() {
+            info!("disconnecting from network to reconnect with the new wallet");
+            state.maybe_client = None;
+            state.wallet_clients.clear();
+        }
+    }
+
+    /// Returns balances of the paying wallet, or of a fake wallet when the fake network is enabled.
+    pub fn get_wallet_backend(&self) -> Result<Arc<dyn WalletBackend>, Error> {
+        let paying_wallet = self.get_paying_wallet()?;
+        if self.maybe_fake_network_backend.is_some() {
+            Ok(Arc::new(FakeWalletBackend::new(paying_wallet.address(), U256::from(self.ant_tp_config.fake_wallet_balance))))
+        } else {
+            Ok(Arc::new(EvmWalletBackend::new(paying_wallet)))
+        }
+    }
+
+    pub async fn get_client(&self) -> Result<Data<Client>, Error> {
+        self.touch();
+        if let Some(client) = self.state.lock().unwrap().maybe_client.clone() {
+            return Ok(client);
+        }
+        // only one caller connects, while the others wait to share its client
+        let _connect_guard = self.connect_lock.lock().await;
+        {
+            let state = self.state.lock().unwrap();
+            if let Some(client) = state.maybe_client.clone() {
+                return Ok(client);
+            }
+            if let Some(next_connect_time) = state.next_connect_time.filter(|next_connect_time| Instant::now() < *next_connect_time) {
+                return Err(Error::Network(format!("not connected to network, retrying in [{:?}]: {}",
+                    next_connect_time.saturating_duration_since(Instant::now()), state.last_connect_error.clone().unwrap_or_default())));
+            }
+        }
+        let result = self.init_client().await;
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Ok(client) => {
+                // the wallet may have been set while connecting
+                let client = Data::new(client.with_wallet(state.wallet.clone()));
+                state.maybe_client = Some(client.clone());
+                state.last_connect_error = None;
+                state.connect_failures = 0;
+                state.next_connect_time = None;
+                Ok(client)
+            }
+            Err(e) => {
+                state.connect_failures = state.connect_failures.saturating_add(1);
+                let connect_backoff = self.get_connect_backoff(state.connect_failures);
+                warn!("failed to connect to network: [{}] - retrying in [{:?}]", e, connect_backoff);
+                state.last_connect_error = Some(e.to_string());
+                state.next_connect_time = Some(Instant::now() + connect_backoff);
+                Err(e)
+            }
+        }
+    }
+
+    pub fn status(&self) -> NetworkStatus {
+        let state = self.state.lock().unwrap();
+        let is_fake_network = self.maybe_fake_network_backend.is_some();
+        NetworkStatus {
+            backend: if is_fake_network { "fake".to_string() } else { "autonomi".to_string() },
+            connected: is_fake_network || state.maybe_client.is_some(),
+            connecting: self.connect_lock.try_lock().is_err(),
+            last_connect_error: state.last_connect_error.clone(),
+            idle_seconds: Self::get_current_time().saturating_sub(self.last_accessed_time.load(Ordering::Relaxed)),
+            idle_disconnect: self.ant_tp_config.idle_disconnect,
         }
     }
 
-    pub fn try_sleep(&mut self) {
+    pub fn try_sleep(&self) {
         // if idle for a period, deallocate the client to save resources (CPU/memory)
-        let current_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-        if self.maybe_client.is_some() {
-            if current_time - self.last_accessed_time > self.ant_tp_config.idle_disconnect {
-                info!("idle timeout exceeded... disconnecting from network");
-                self.maybe_client = None;
+        let current_time = Self::get_current_time();
+        let last_accessed_time = self.last_accessed_time.load(Ordering::Relaxed);
+        let mut state = self.state.lock().unwrap();
+        if let Some(client) = state.maybe_client.as_ref() {
+            if current_time.saturating_sub(last_accessed_time) <= self.ant_tp_config.idle_disconnect {
+                debug!("staying awake... current_time: {}, last_accessed_time: {}", current_time, last_accessed_time);
+            } else if Self::is_in_use(client) || state.wallet_clients.values().any(Self::is_in_use) {
+                // requests hold the client until they complete, so do not disconnect under them
+                debug!("staying awake... client is in use by requests");
             } else {
-                debug!("staying awake... current_time: {}, last_accessed_time: {}", current_time, self.last_accessed_time);
+                info!("idle timeout exceeded... disconnecting from network");
+                state.maybe_client = None;
+                state.wallet_clients.clear();
             }
         }
     }
 
+    fn is_in_use(client: &Data<Client>) -> bool {
+        Arc::strong_count(client) > 1
+    }
+
+    fn touch(&self) {
+        self.last_accessed_time.store(Self::get_current_time(), Ordering::Relaxed);
+    }
+
+    fn get_current_time() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn get_connect_backoff(&self, connect_failures: u32) -> Duration {
+        Duration::from_millis(self.ant_tp_config.connect_backoff_ms)
+            .saturating_mul(2u32.saturating_pow(connect_failures.saturating_sub(1)))
+            .min(MAX_CONNECT_BACKOFF)
+    }
+
+    /// Connects to the network, without a wallet, as the wallet may change while connecting.
     async fn init_client(&self) -> Result<Client, Error> {
-        // todo: fix unwraps
-        Ok(
-            Client::connect(
-                ant_core::config::load_bootstrap_peers().unwrap().unwrap().as_slice(),
-                ClientConfig::default(),
-            ).await?
-                //.with_wallet(Wallet::new_from_private_key(EvmNetwork::ArbitrumOne, self.ant_tp_config.wallet_private_key.as_str()).unwrap())
-                .with_evm_network(self.evm_network.clone())
-        )
+        let bootstrap_peers = self.ant_tp_config.get_bootstrap_peers()?;
+        // local devnets listen on loopback addresses, which are otherwise ignored
+        let client_config = ClientConfig { allow_loopback: self.ant_tp_config.is_local_network(), ..ClientConfig::default() };
+        info!("connecting to network with [{}] bootstrap peers", bootstrap_peers.len());
+        Client::connect(bootstrap_peers.as_slice(), client_config).await
         /*Ok(Client {
             config: ClientConfig::default(),
             network: Network::new(self.ant_tp_config.peers.as_slice(), true).await?,
@@ -61,4 +259,112 @@ impl ClientHarness {
             next_request_id: AtomicU64::new(0),
         })*/
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use evmlib::Network::ArbitrumOne;
+
+    #[tokio::test]
+    async fn test_status_reports_connect_error() {
+        // local networks require bootstrap peers, so connecting fails without reaching the network
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--evm-network", "local"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        assert_eq!(client_harness.status().last_connect_error, None);
+
+        assert!(client_harness.get_client().await.is_err());
+        let network_status = client_harness.status();
+        assert_eq!(network_status.backend, "autonomi");
+        assert!(!network_status.connected);
+        assert!(network_status.last_connect_error.is_some());
+        assert!(!network_status.connecting);
+    }
+
+    #[tokio::test]
+    async fn test_connect_backs_off_after_failure() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--evm-network", "local"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        assert!(!client_harness.get_client().await.err().unwrap().to_string().contains("retrying in"));
+        // fails fast until the backoff has passed
+        assert!(client_harness.get_client().await.err().unwrap().to_string().contains("retrying in"));
+
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--evm-network", "local", "--connect-backoff-ms", "0"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        assert!(client_harness.get_client().await.is_err());
+        assert!(!client_harness.get_client().await.err().unwrap().to_string().contains("retrying in"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_connects_are_single_flight() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--evm-network", "local"]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config));
+        let handles: Vec<_> = (0..8).map(|_| {
+            let client_harness = client_harness.clone();
+            tokio::spawn(async move { client_harness.get_client().await.err().unwrap().to_string() })
+        }).collect();
+        let mut connect_attempts = 0;
+        for handle in handles {
+            if !handle.await.unwrap().contains("retrying in") {
+                connect_attempts += 1;
+            }
+        }
+        // waiters share the outcome of the one attempt, rather than each connecting in turn
+        assert_eq!(connect_attempts, 1);
+    }
+
+    #[test]
+    fn test_connect_backoff() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--connect-backoff-ms", "1000"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        assert_eq!(client_harness.get_connect_backoff(1), Duration::from_secs(1));
+        assert_eq!(client_harness.get_connect_backoff(3), Duration::from_secs(4));
+        assert_eq!(client_harness.get_connect_backoff(20), MAX_CONNECT_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_status_with_fake_network() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--fake-network"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        assert_eq!(client_harness.get_network_backend().await.unwrap().name(), "fake");
+
+        let network_status = client_harness.status();
+        assert_eq!(network_status.backend, "fake");
+        assert!(network_status.connected);
+        assert_eq!(network_status.idle_disconnect, 30);
+    }
+
+    #[tokio::test]
+    async fn test_set_wallet() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--fake-network", "--fake-wallet-balance", "42"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        let wallet = Wallet::new_with_random_wallet(ArbitrumOne);
+        assert_ne!(client_harness.get_wallet().address(), wallet.address());
+
+        client_harness.set_wallet(wallet.clone());
+        assert_eq!(client_harness.get_wallet().address(), wallet.address());
+        let wallet_backend = client_harness.get_wallet_backend().unwrap();
+        assert_eq!(wallet_backend.address(), wallet.address());
+        assert_eq!(wallet_backend.token_balance().await.unwrap(), U256::from(42));
+    }
+
+    #[tokio::test]
+    async fn test_paying_wallet() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--fake-network"]).unwrap();
+        let client_harness = ClientHarness::new(ArbitrumOne, ant_tp_config);
+        let team_wallet = Wallet::new_with_random_wallet(ArbitrumOne);
+        client_harness.add_wallet("team-a".to_string(), team_wallet.clone());
+        assert_eq!(client_harness.get_paying_wallet().unwrap().address(), client_harness.get_wallet().address());
+
+        CommandWallet::new("team-a".to_string()).scope(async {
+            assert_eq!(client_harness.get_paying_wallet().unwrap().address(), team_wallet.address());
+            assert_eq!(client_harness.get_wallet_backend().unwrap().address(), team_wallet.address());
+        }).await;
+
+        CommandWallet::new("team-b".to_string()).scope(async {
+            assert!(client_harness.get_paying_wallet().is_err());
+            assert!(client_harness.get_network_backend().await.is_err());
+        }).await;
+    }
 }
\ No newline at end of file
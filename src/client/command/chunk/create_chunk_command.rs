@@ -1,37 +1,42 @@
 use actix_web::web::Data;
 use ant_core::data::DataChunk;
 use async_trait::async_trait;
+use bytes::Bytes;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::Command;
+use crate::client::command::command_details::CommandReceipt;
 use crate::client::command::error::CommandError;
 
 pub struct CreateChunkCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     chunk: DataChunk,
 }
 
 impl CreateChunkCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, chunk: DataChunk) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, chunk: DataChunk) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, chunk }
     }
+
+    pub fn restore(id: u128, client_harness: Data<ClientHarness>, payload: Bytes) -> Self {
+        Self { id, client_harness, chunk: DataChunk::from_content(payload) }
+    }
 }
 
-const STRUCT_NAME: &'static str = "CreateChunkCommand";
+pub const STRUCT_NAME: &str = "CreateChunkCommand";
 
 #[async_trait]
 impl Command for CreateChunkCommand {    
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let network_backend = self.client_harness.get_ref().get_network_backend().await?;
         let chunk = &self.chunk.clone();
         let chunk_address_hex = hex::encode(&chunk.address);
         debug!("creating chunk with address [{}] on network", chunk_address_hex);
-        client.chunk_put(chunk.content.clone()).await?;
+        network_backend.chunk_put(chunk.content.clone()).await?;
         info!("chunk at address [{}] created successfully", chunk_address_hex);
         Ok(())
     }
@@ -56,4 +61,21 @@ impl Command for CreateChunkCommand {
         properties.insert("chunk_address".to_string(), hex::encode(self.chunk.address));
         properties
     }
+
+    fn receipt(&self) -> CommandReceipt {
+        // chunk_put pays internally, so the cost is not known
+        CommandReceipt {
+            result_address: Some(hex::encode(self.chunk.address)),
+            bytes_uploaded: Some(self.chunk.content.len() as u64),
+            ..Default::default()
+        }
+    }
+
+    fn target_address(&self) -> Option<String> {
+        Some(hex::encode(self.chunk.address))
+    }
+
+    fn payload(&self) -> Option<Bytes> {
+        Some(self.chunk.content.clone())
+    }
 }
\ No newline at end of file
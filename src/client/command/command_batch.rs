@@ -0,0 +1,83 @@
+use std::future::Future;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::UpdateError;
+
+pub const BATCH_HEADER: &str = "x-command-batch";
+const MAX_BATCH_ID_LENGTH: usize = 64;
+
+tokio::task_local! {
+    static CURRENT_COMMAND_BATCH: CommandBatch;
+}
+
+/// Groups related commands, such as those queued by a single archive upload.
+///
+/// Requests join a batch for all commands they queue by running within `scope`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommandBatch {
+    pub id: String,
+}
+
+impl CommandBatch {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+
+    pub fn generate() -> Self {
+        Self::new(Uuid::new_v4().to_string())
+    }
+
+    /// Parses a batch id of up to 64 letters, digits, '-', '_' or '.', which may be absent.
+    pub fn parse(id: Option<&str>) -> Result<Option<Self>, UpdateError> {
+        match id.map(|id| id.trim()).filter(|id| !id.is_empty()) {
+            Some(id) if id.len() <= MAX_BATCH_ID_LENGTH
+                && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.') =>
+                Ok(Some(Self::new(id.to_string()))),
+            Some(id) => Err(UpdateError::InvalidData(format!("invalid command batch [{}]", id))),
+            None => Ok(None),
+        }
+    }
+
+    /// Runs the future with this batch applied to any commands it queues.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_COMMAND_BATCH.scope(self, future).await
+    }
+
+    /// Runs the future within the current batch, or a new one if there is none.
+    pub async fn join_or_start<F: Future>(future: F) -> (Self, F::Output) {
+        let command_batch = Self::current().unwrap_or_else(Self::generate);
+        let output = command_batch.clone().scope(future).await;
+        (command_batch, output)
+    }
+
+    pub fn current() -> Option<Self> {
+        CURRENT_COMMAND_BATCH.try_with(|command_batch| command_batch.clone()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(CommandBatch::parse(None).unwrap(), None);
+        assert_eq!(CommandBatch::parse(Some(" ")).unwrap(), None);
+        assert_eq!(CommandBatch::parse(Some("site-v1.2_a")).unwrap(), Some(CommandBatch::new("site-v1.2_a".to_string())));
+        assert!(CommandBatch::parse(Some("no spaces")).is_err());
+        assert!(CommandBatch::parse(Some(&"a".repeat(65))).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_join_or_start() {
+        assert_eq!(CommandBatch::current(), None);
+
+        let (command_batch, current_command_batch) = CommandBatch::join_or_start(async {
+            CommandBatch::current()
+        }).await;
+        assert_eq!(current_command_batch, Some(command_batch));
+
+        let (command_batch, _) = CommandBatch::new("outer".to_string()).scope(CommandBatch::join_or_start(async {})).await;
+        assert_eq!(command_batch.id, "outer");
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use actix_web::web::Data;
+use indexmap::IndexMap;
+use tokio::sync::{watch, Notify};
+use crate::client::command::Command;
+
+#[derive(Default)]
+struct Cancellations {
+    notifiers: HashMap<u128, Arc<Notify>>,
+    cancelled: HashSet<u128>,
+}
+
+/// Signals cancellation to queued or running commands and keeps failed commands for retry.
+///
+/// Also signals the executor to stop starting commands on shutdown, and signals back once all
+/// running commands have finished.
+#[derive(Clone)]
+pub struct CommandControl {
+    cancellations: Data<Mutex<Cancellations>>,
+    failed_commands: Data<Mutex<IndexMap<u128, Box<dyn Command>>>>,
+    failed_capacity: usize,
+    waiting_cancelled: Arc<Notify>,
+    stopping: Arc<watch::Sender<bool>>,
+    stopped: Arc<Notify>,
+}
+
+impl CommandControl {
+    pub fn new(failed_capacity: usize) -> Self {
+        Self {
+            cancellations: Data::new(Mutex::new(Cancellations::default())),
+            failed_commands: Data::new(Mutex::new(IndexMap::new())),
+            failed_capacity: failed_capacity.max(1),
+            waiting_cancelled: Arc::new(Notify::new()),
+            stopping: Arc::new(watch::Sender::new(false)),
+            stopped: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Cancels a running command or marks a queued command to be aborted when it is scheduled.
+    pub fn cancel(&self, id: u128) {
+        let mut cancellations = self.cancellations.lock().unwrap();
+        match cancellations.notifiers.get(&id) {
+            Some(notify) => notify.notify_one(),
+            None => {
+                cancellations.cancelled.insert(id);
+                self.waiting_cancelled.notify_one();
+            },
+        }
+    }
+
+    /// Waits until a queued command is cancelled.
+    pub async fn waiting_cancelled(&self) {
+        self.waiting_cancelled.notified().await
+    }
+
+    /// Ids of queued commands which have been cancelled, but not yet dropped by the executor.
+    pub fn cancelled(&self) -> Vec<u128> {
+        self.cancellations.lock().unwrap().cancelled.iter().copied().collect()
+    }
+
+    /// Forgets the cancellation of a command which the executor has dropped.
+    pub fn forget_cancelled(&self, id: u128) {
+        self.cancellations.lock().unwrap().cancelled.remove(&id);
+    }
+
+    /// Registers a command which is about to run. Returns `None` if it has already been cancelled.
+    pub fn register(&self, id: u128) -> Option<Arc<Notify>> {
+        let mut cancellations = self.cancellations.lock().unwrap();
+        if cancellations.cancelled.remove(&id) {
+            return None;
+        }
+        let notify = Arc::new(Notify::new());
+        cancellations.notifiers.insert(id, notify.clone());
+        Some(notify)
+    }
+
+    pub fn unregister(&self, id: u128) {
+        self.cancellations.lock().unwrap().notifiers.remove(&id);
+    }
+
+    pub fn insert_failed(&self, command: Box<dyn Command>) {
+        let mut failed_commands = self.failed_commands.lock().unwrap();
+        failed_commands.insert(command.id(), command);
+        if failed_commands.len() > self.failed_capacity {
+            failed_commands.shift_remove_index(0);
+        }
+    }
+
+    pub fn take_failed(&self, id: u128) -> Option<Box<dyn Command>> {
+        self.failed_commands.lock().unwrap().shift_remove(&id)
+    }
+
+    pub fn remove_failed(&self, ids: &[u128]) {
+        let mut failed_commands = self.failed_commands.lock().unwrap();
+        ids.iter().for_each(|id| { failed_commands.shift_remove(id); });
+    }
+
+    /// Stops new commands from starting. Waiting commands stay journaled for the next start.
+    pub fn stop(&self) {
+        self.stopping.send_replace(true);
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        *self.stopping.borrow()
+    }
+
+    pub fn subscribe_stopping(&self) -> watch::Receiver<bool> {
+        self.stopping.subscribe()
+    }
+
+    pub fn notify_stopped(&self) {
+        self.stopped.notify_one();
+    }
+
+    /// Waits until no commands are running after `stop`.
+    pub async fn stopped(&self) {
+        self.stopped.notified().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::command::test_command::IdCommand;
+
+    #[test]
+    fn test_cancel_before_register() {
+        let command_control = CommandControl::new(4);
+        command_control.cancel(1);
+
+        assert!(command_control.register(1).is_none());
+        assert!(command_control.register(1).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_waiting_notifies_executor() {
+        let command_control = CommandControl::new(4);
+        command_control.cancel(1);
+
+        command_control.waiting_cancelled().await;
+        assert_eq!(command_control.cancelled(), vec![1]);
+        command_control.forget_cancelled(1);
+        assert!(command_control.cancelled().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_after_register_notifies() {
+        let command_control = CommandControl::new(4);
+        let notify = command_control.register(1).unwrap();
+        command_control.cancel(1);
+
+        notify.notified().await;
+        command_control.unregister(1);
+    }
+
+    #[test]
+    fn test_failed_commands_are_capped() {
+        let command_control = CommandControl::new(2);
+        (1..=3).for_each(|id| command_control.insert_failed(IdCommand::boxed(id)));
+
+        assert!(command_control.take_failed(1).is_none());
+        assert_eq!(command_control.take_failed(2).unwrap().id(), 2);
+        command_control.remove_failed(&[3]);
+        assert!(command_control.take_failed(3).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stop() {
+        let command_control = CommandControl::new(4);
+        let mut stopping = command_control.subscribe_stopping();
+        assert!(!command_control.is_stopping());
+
+        command_control.clone().stop();
+        stopping.changed().await.unwrap();
+        assert!(command_control.is_stopping());
+
+        // stopped before waiting is remembered
+        command_control.notify_stopped();
+        command_control.stopped().await;
+    }
+}
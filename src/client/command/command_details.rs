@@ -2,11 +2,14 @@ use std::fmt::{Debug, Display, Formatter};
 use std::time::{SystemTime, UNIX_EPOCH};
 use indexmap::IndexMap;
 use log::warn;
+use serde::{Deserialize, Serialize};
 use crate::client::command::Command;
+use crate::client::command::command_metadata::CommandMetadata;
+use crate::client::command::command_schedule::CommandSchedule;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum CommandState {
-    WAITING, RUNNING, COMPLETED, ABORTED,
+    WAITING, RUNNING, COMPLETED, ABORTED, FAILED,
 }
 
 impl Display for CommandState {
@@ -15,6 +18,22 @@ impl Display for CommandState {
     }
 }
 
+/// What a command produced, as reported by the client.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandReceipt {
+    pub result_address: Option<String>,
+    pub bytes_uploaded: Option<u64>,
+    pub storage_cost: Option<String>,
+    pub gas_cost: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommandAttempt {
+    pub started_at: u128,
+    pub error: Option<String>,
+    pub backoff_seconds: Option<u64>,
+}
+
 #[derive(Clone, Debug)]
 pub struct CommandDetails {
     id: u128,
@@ -24,6 +43,16 @@ pub struct CommandDetails {
     waiting_at: u128,
     running_at: Option<u128>,
     terminated_at: Option<u128>,
+    attempts: Vec<CommandAttempt>,
+    error: Option<String>,
+    receipt: CommandReceipt,
+    duplicate_of: Option<u128>,
+    priority: i32,
+    not_before: Option<u128>,
+    queue_position: Option<usize>,
+    eta: Option<u128>,
+    batch_id: Option<String>,
+    wallet: Option<String>,
 }
 
 impl CommandDetails {
@@ -35,20 +64,60 @@ impl CommandDetails {
         let waiting_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
         let running_at = None;
         let terminated_at = None;
-        Self { id, name, properties, state, waiting_at, running_at, terminated_at }
+        let attempts = Vec::new();
+        let error = None;
+        let receipt = CommandReceipt::default();
+        let duplicate_of = None;
+        let CommandMetadata { schedule, batch, wallet } = command.metadata();
+        let CommandSchedule { priority, not_before } = schedule;
+        let queue_position = None;
+        let eta = None;
+        let batch_id = batch.map(|command_batch| command_batch.id);
+        let wallet = wallet.map(|command_wallet| command_wallet.name);
+        Self {
+            id, name, properties, state, waiting_at, running_at, terminated_at, attempts, error, receipt, duplicate_of,
+            priority, not_before, queue_position, eta, batch_id, wallet
+        }
     }
 
     pub fn set_state(&mut self, state: CommandState) {
         self.state = state;
+        self.queue_position = None;
+        self.eta = None;
         match self.state {
             CommandState::RUNNING =>
                 self.running_at = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()),
-            CommandState::COMPLETED | CommandState::ABORTED =>
+            CommandState::COMPLETED | CommandState::ABORTED | CommandState::FAILED =>
                 self.terminated_at = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()),
-            _ => warn!("can only change command state to running, completed or aborted"),
+            _ => warn!("can only change command state to running, completed, aborted or failed"),
         }
     }
 
+    pub fn set_properties(&mut self, properties: IndexMap<String, String>) {
+        self.properties = properties;
+    }
+
+    pub fn add_attempt(&mut self, attempt: CommandAttempt) {
+        self.attempts.push(attempt);
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn set_receipt(&mut self, receipt: CommandReceipt) {
+        self.receipt = receipt;
+    }
+
+    pub fn set_duplicate_of(&mut self, duplicate_of: u128) {
+        self.duplicate_of = Some(duplicate_of);
+    }
+
+    pub fn set_queue_estimate(&mut self, queue_position: usize, eta: Option<u128>) {
+        self.queue_position = Some(queue_position);
+        self.eta = eta;
+    }
+
     pub fn id(&self) -> u128 {
         self.id
     }
@@ -76,6 +145,49 @@ impl CommandDetails {
     pub fn terminated_at(&self) -> Option<u128> {
         self.terminated_at
     }
+
+    pub fn attempts(&self) -> &Vec<CommandAttempt> {
+        &self.attempts
+    }
+
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    pub fn receipt(&self) -> &CommandReceipt {
+        &self.receipt
+    }
+
+    pub fn duplicate_of(&self) -> Option<u128> {
+        self.duplicate_of
+    }
+
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn not_before(&self) -> Option<u128> {
+        self.not_before
+    }
+
+    /// Position in the queue, starting from 0, while the command is waiting.
+    pub fn queue_position(&self) -> Option<usize> {
+        self.queue_position
+    }
+
+    /// Estimated time the command will start running, while it is waiting.
+    pub fn eta(&self) -> Option<u128> {
+        self.eta
+    }
+
+    pub fn batch_id(&self) -> Option<&String> {
+        self.batch_id.as_ref()
+    }
+
+    /// Name of the keystore wallet which pays for the command, or `None` for the default wallet.
+    pub fn wallet(&self) -> Option<&String> {
+        self.wallet.as_ref()
+    }
 }
 
 impl Display for CommandDetails {
@@ -91,9 +203,12 @@ impl Display for CommandDetails {
             None => "".to_string(),
         };
 
+        let error = self.error.clone().unwrap_or_default();
+        let result_address = self.receipt.result_address.clone().unwrap_or_default();
+
         write!(f,
-               "id: [{}], name: [{}], properties: [{}], state: [{}], waiting_at: [{}], running_at: [{}], terminated_at: [{}]",
-               self.id, self.name, properties, self.state, self.waiting_at, running_at, terminated_at
+               "id: [{}], name: [{}], properties: [{}], state: [{}], waiting_at: [{}], running_at: [{}], terminated_at: [{}], attempts: [{}], error: [{}], result_address: [{}]",
+               self.id, self.name, properties, self.state, self.waiting_at, running_at, terminated_at, self.attempts.len(), error, result_address
         )
     }
 }
@@ -0,0 +1,41 @@
+use tokio::sync::broadcast::{channel, Receiver, Sender};
+use crate::client::command::command_details::CommandDetails;
+
+/// Broadcasts command details whenever a command changes state or reports new progress.
+#[derive(Clone)]
+pub struct CommandEvents {
+    sender: Sender<CommandDetails>,
+}
+
+impl CommandEvents {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = channel(capacity.max(1));
+        Self { sender }
+    }
+
+    pub fn publish(&self, command_details: CommandDetails) {
+        // no subscribers is not an error
+        let _ = self.sender.send(command_details);
+    }
+
+    pub fn subscribe(&self) -> Receiver<CommandDetails> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::command::test_command::IdCommand;
+
+    #[tokio::test]
+    async fn test_publish_to_subscribers() {
+        let command_events = CommandEvents::new(10);
+        command_events.publish(CommandDetails::new(&IdCommand::boxed(1)));
+
+        let mut receiver = command_events.subscribe();
+        command_events.publish(CommandDetails::new(&IdCommand::boxed(2)));
+
+        assert_eq!(receiver.recv().await.unwrap().id(), 2);
+    }
+}
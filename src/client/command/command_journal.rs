@@ -0,0 +1,403 @@
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use actix_web::web::Data;
+use bytes::Bytes;
+use indexmap::IndexMap;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use crate::client::command::Command;
+use crate::client::command::command_details::CommandState;
+use crate::client::command::command_metadata::CommandMetadata;
+
+const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+const COMPACTED_JOURNAL_FILE_NAME: &str = "journal.jsonl.compact";
+const PAYLOAD_DIRECTORY_NAME: &str = "payloads";
+const COMPACTION_THRESHOLD: usize = 1024;
+
+/// Recreates a journaled command from its id and payload, or `None` if the payload is unusable.
+pub type CommandRestorer = Box<dyn Fn(u128, Bytes) -> Option<Box<dyn Command>> + Send + Sync>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    pub id: u128,
+    pub name: String,
+    pub payload: String,
+    pub state: CommandState,
+    #[serde(default)]
+    pub metadata: CommandMetadata,
+}
+
+struct JournalFile {
+    file: File,
+    pending_entries: IndexMap<u128, JournalEntry>,
+    appended_entries: usize,
+}
+
+/// Write-ahead journal of queued commands, so that they survive a restart.
+///
+/// Each command payload is written to its own file, then a line recording the command state is
+/// appended to the journal. On startup, the last state of each command wins and any which were
+/// still waiting or running are replayed. Completed commands are compacted out of the journal.
+#[derive(Clone)]
+pub struct CommandJournal {
+    journal_directory: PathBuf,
+    journal_file: Data<Mutex<JournalFile>>,
+}
+
+impl CommandJournal {
+    pub fn new(journal_directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let journal_directory = journal_directory.into();
+        fs::create_dir_all(journal_directory.join(PAYLOAD_DIRECTORY_NAME))?;
+
+        let pending_entries = Self::read_pending_entries(&journal_directory.join(JOURNAL_FILE_NAME))?;
+        Self::remove_orphaned_payloads(&journal_directory, &pending_entries);
+        let file = Self::write_compacted(&journal_directory, &pending_entries)?;
+        if !pending_entries.is_empty() {
+            info!("found [{}] pending commands in journal at [{:?}]", pending_entries.len(), journal_directory);
+        }
+        let journal_file = Data::new(Mutex::new(JournalFile { file, pending_entries, appended_entries: 0 }));
+        Ok(Self { journal_directory, journal_file })
+    }
+
+    /// Journals the command as waiting. Commands without a payload cannot be replayed, so are skipped.
+    pub async fn append(&self, command: &dyn Command) -> io::Result<()> {
+        let payload = match command.payload() {
+            Some(payload) => payload,
+            None => return Ok(()),
+        };
+        let payload_size = payload.len();
+        let payload_file_name = format!("{:032x}", command.id());
+        let payload_path = self.journal_directory.join(PAYLOAD_DIRECTORY_NAME).join(&payload_file_name);
+        // payloads may be large, so are written without holding the journal lock
+        Self::run_blocking(move || {
+            let mut payload_file = File::create(&payload_path)?;
+            payload_file.write_all(&payload)?;
+            payload_file.sync_all()
+        }).await?;
+
+        let journal_entry = JournalEntry {
+            id: command.id(),
+            name: command.name(),
+            payload: payload_file_name,
+            state: CommandState::WAITING,
+            metadata: command.metadata(),
+        };
+        let journal_file = self.journal_file.clone();
+        Self::run_blocking(move || {
+            let mut journal_file = journal_file.lock().unwrap();
+            Self::write_entry(&mut journal_file.file, &journal_entry)?;
+            journal_file.appended_entries += 1;
+            journal_file.pending_entries.insert(journal_entry.id, journal_entry);
+            Ok(())
+        }).await?;
+        debug!("journaled command [{}] with [{}] byte payload", command.id(), payload_size);
+        Ok(())
+    }
+
+    /// Journals the new state of a command, removing its payload once it has terminated.
+    pub async fn update(&self, command_id: u128, command_state: &CommandState) {
+        let command_journal = self.clone();
+        let command_state = command_state.clone();
+        if let Err(e) = Self::run_blocking(move || {
+            command_journal.write_update(command_id, &command_state);
+            Ok(())
+        }).await {
+            error!("failed to journal state of command [{}]: [{:?}]", command_id, e);
+        }
+    }
+
+    fn write_update(&self, command_id: u128, command_state: &CommandState) {
+        let mut journal_file = self.journal_file.lock().unwrap();
+        let mut journal_entry = match journal_file.pending_entries.get(&command_id) {
+            Some(journal_entry) => journal_entry.clone(),
+            None => return, // not journaled
+        };
+        journal_entry.state = command_state.clone();
+        if let Err(e) = Self::write_entry(&mut journal_file.file, &journal_entry) {
+            error!("failed to journal state [{}] of command [{}]: [{:?}]", command_state, command_id, e);
+            return;
+        }
+        journal_file.appended_entries += 1;
+        if Self::is_pending(command_state) {
+            journal_file.pending_entries.insert(command_id, journal_entry);
+        } else {
+            journal_file.pending_entries.shift_remove(&command_id);
+            if let Err(e) = fs::remove_file(self.journal_directory.join(PAYLOAD_DIRECTORY_NAME).join(&journal_entry.payload)) {
+                warn!("failed to remove payload of command [{}] from journal: [{:?}]", command_id, e);
+            }
+        }
+
+        if journal_file.appended_entries >= COMPACTION_THRESHOLD {
+            match Self::write_compacted(&self.journal_directory, &journal_file.pending_entries) {
+                Ok(file) => {
+                    journal_file.file = file;
+                    journal_file.appended_entries = 0;
+                }
+                Err(e) => error!("failed to compact command journal: [{:?}]", e),
+            }
+        }
+    }
+
+    pub fn pending(&self) -> Vec<JournalEntry> {
+        self.journal_file.lock().unwrap().pending_entries.values().cloned().collect()
+    }
+
+    /// Restores pending commands, in the order they were originally queued.
+    pub async fn replay(&self, command_restorers: &IndexMap<String, CommandRestorer>) -> Vec<Box<dyn Command>> {
+        let mut commands = Vec::new();
+        for journal_entry in self.pending() {
+            let maybe_command = match command_restorers.get(&journal_entry.name) {
+                Some(command_restorer) => match fs::read(self.journal_directory.join(PAYLOAD_DIRECTORY_NAME).join(&journal_entry.payload)) {
+                    Ok(payload) => command_restorer(journal_entry.id, Bytes::from(payload)),
+                    Err(e) => {
+                        warn!("failed to read payload of command [{}] from journal: [{:?}]", journal_entry.id, e);
+                        None
+                    }
+                }
+                None => {
+                    warn!("unable to restore [{}] command [{}] from journal", journal_entry.name, journal_entry.id);
+                    None
+                }
+            };
+            match maybe_command {
+                Some(command) => {
+                    info!("replaying [{}] command [{}] from journal", journal_entry.name, journal_entry.id);
+                    commands.push(journal_entry.metadata.clone().apply(command));
+                }
+                None => self.update(journal_entry.id, &CommandState::ABORTED).await,
+            }
+        }
+        commands
+    }
+
+    async fn run_blocking<T: Send + 'static>(f: impl FnOnce() -> io::Result<T> + Send + 'static) -> io::Result<T> {
+        tokio::task::spawn_blocking(f).await.map_err(io::Error::other)?
+    }
+
+    fn is_pending(command_state: &CommandState) -> bool {
+        *command_state == CommandState::WAITING || *command_state == CommandState::RUNNING
+    }
+
+    fn write_entry(file: &mut File, journal_entry: &JournalEntry) -> io::Result<()> {
+        let mut line = serde_json::to_vec(journal_entry)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        file.sync_data()
+    }
+
+    fn read_pending_entries(journal_path: &Path) -> io::Result<IndexMap<u128, JournalEntry>> {
+        let mut pending_entries = IndexMap::new();
+        if !journal_path.exists() {
+            return Ok(pending_entries);
+        }
+        for line in BufReader::new(File::open(journal_path)?).lines() {
+            // a crash may leave a partially written final line
+            match serde_json::from_str::<JournalEntry>(&line?) {
+                Ok(journal_entry) => {
+                    if Self::is_pending(&journal_entry.state) {
+                        pending_entries.insert(journal_entry.id, journal_entry);
+                    } else {
+                        pending_entries.shift_remove(&journal_entry.id);
+                    }
+                }
+                Err(e) => warn!("skipping unreadable command journal entry: [{:?}]", e),
+            }
+        }
+        Ok(pending_entries)
+    }
+
+    fn write_compacted(journal_directory: &Path, pending_entries: &IndexMap<u128, JournalEntry>) -> io::Result<File> {
+        let compacted_path = journal_directory.join(COMPACTED_JOURNAL_FILE_NAME);
+        let mut compacted_file = File::create(&compacted_path)?;
+        for journal_entry in pending_entries.values() {
+            let mut line = serde_json::to_vec(journal_entry)?;
+            line.push(b'\n');
+            compacted_file.write_all(&line)?;
+        }
+        compacted_file.sync_all()?;
+        fs::rename(&compacted_path, journal_directory.join(JOURNAL_FILE_NAME))?;
+        OpenOptions::new().append(true).open(journal_directory.join(JOURNAL_FILE_NAME))
+    }
+
+    /// Removes payloads orphaned by a crash between writing a payload and journaling it.
+    ///
+    /// Payloads are written before they are journaled, so this is only safe before any are appended.
+    fn remove_orphaned_payloads(journal_directory: &Path, pending_entries: &IndexMap<u128, JournalEntry>) {
+        let dir_entries = match fs::read_dir(journal_directory.join(PAYLOAD_DIRECTORY_NAME)) {
+            Ok(dir_entries) => dir_entries,
+            Err(e) => {
+                warn!("failed to read command journal payloads: [{:?}]", e);
+                return;
+            }
+        };
+        for dir_entry in dir_entries.flatten() {
+            let file_name = dir_entry.file_name().to_string_lossy().to_string();
+            if !pending_entries.values().any(|journal_entry| journal_entry.payload == file_name)
+                && let Err(e) = fs::remove_file(dir_entry.path()) {
+                warn!("failed to remove orphaned command journal payload [{}]: [{:?}]", file_name, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use tempfile::tempdir;
+    use crate::client::command::command_batch::CommandBatch;
+    use crate::client::command::command_schedule::CommandSchedule;
+    use crate::client::command::command_wallet::CommandWallet;
+    use crate::client::command::error::CommandError;
+
+    struct PayloadCommand {
+        id: u128,
+        payload: Option<Bytes>,
+    }
+
+    #[async_trait]
+    impl Command for PayloadCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            vec![]
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+
+        fn name(&self) -> String {
+            "PayloadCommand".to_string()
+        }
+
+        fn payload(&self) -> Option<Bytes> {
+            self.payload.clone()
+        }
+    }
+
+    fn create_command(id: u128, payload: Option<&'static str>) -> Box<dyn Command> {
+        Box::new(PayloadCommand { id, payload: payload.map(Bytes::from) })
+    }
+
+    fn create_restorers() -> IndexMap<String, CommandRestorer> {
+        let mut command_restorers: IndexMap<String, CommandRestorer> = IndexMap::new();
+        command_restorers.insert("PayloadCommand".to_string(), Box::new(|id, payload| {
+            Some(Box::new(PayloadCommand { id, payload: Some(payload) }) as Box<dyn Command>)
+        }));
+        command_restorers
+    }
+
+    #[tokio::test]
+    async fn test_replay_pending_commands_after_restart() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        command_journal.append(&*create_command(1, Some("waiting"))).await.unwrap();
+        command_journal.append(&*create_command(2, Some("running"))).await.unwrap();
+        command_journal.append(&*create_command(3, Some("completed"))).await.unwrap();
+        command_journal.update(2, &CommandState::RUNNING).await;
+        command_journal.update(3, &CommandState::RUNNING).await;
+        command_journal.update(3, &CommandState::COMPLETED).await;
+        drop(command_journal);
+
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        let commands = command_journal.replay(&create_restorers()).await;
+
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].id(), 1);
+        assert_eq!(commands[0].payload().unwrap(), Bytes::from("waiting"));
+        assert_eq!(commands[1].id(), 2);
+        assert_eq!(commands[1].payload().unwrap(), Bytes::from("running"));
+    }
+
+    #[tokio::test]
+    async fn test_replay_keeps_metadata() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        let command_metadata = CommandMetadata {
+            schedule: CommandSchedule::new(10, Some(1000)),
+            batch: Some(CommandBatch::new("batch".to_string())),
+            wallet: Some(CommandWallet::new("team-a".to_string())),
+        };
+        command_journal.append(&*command_metadata.clone().apply(create_command(1, Some("scheduled")))).await.unwrap();
+        command_journal.append(&*create_command(2, Some("unscheduled"))).await.unwrap();
+        drop(command_journal);
+
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        let commands = command_journal.replay(&create_restorers()).await;
+
+        assert_eq!(commands[0].metadata(), command_metadata);
+        assert!(commands[1].metadata().is_default());
+    }
+
+    #[tokio::test]
+    async fn test_append_skips_commands_without_payload() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        command_journal.append(&*create_command(1, None)).await.unwrap();
+        command_journal.update(1, &CommandState::RUNNING).await;
+
+        assert!(command_journal.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compaction_removes_terminated_commands() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        command_journal.append(&*create_command(1, Some("pending"))).await.unwrap();
+        for id in 2..(COMPACTION_THRESHOLD as u128) {
+            command_journal.append(&*create_command(id, Some("aborted"))).await.unwrap();
+            command_journal.update(id, &CommandState::ABORTED).await;
+        }
+
+        let journal = fs::read_to_string(journal_directory.path().join(JOURNAL_FILE_NAME)).unwrap();
+        assert!(journal.lines().count() < COMPACTION_THRESHOLD);
+        assert_eq!(fs::read_dir(journal_directory.path().join(PAYLOAD_DIRECTORY_NAME)).unwrap().count(), 1);
+        assert_eq!(command_journal.pending().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_startup_removes_orphaned_payloads() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        command_journal.append(&*create_command(1, Some("waiting"))).await.unwrap();
+        drop(command_journal);
+        fs::write(journal_directory.path().join(PAYLOAD_DIRECTORY_NAME).join(format!("{:032x}", 2)), "orphaned").unwrap();
+
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+
+        assert_eq!(fs::read_dir(journal_directory.path().join(PAYLOAD_DIRECTORY_NAME)).unwrap().count(), 1);
+        assert_eq!(command_journal.replay(&create_restorers()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_skips_partially_written_entries() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        command_journal.append(&*create_command(1, Some("waiting"))).await.unwrap();
+        drop(command_journal);
+        let mut journal_file = OpenOptions::new().append(true).open(journal_directory.path().join(JOURNAL_FILE_NAME)).unwrap();
+        journal_file.write_all("{\"id\":2,\"na".as_bytes()).unwrap();
+
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+
+        assert_eq!(command_journal.pending().len(), 1);
+        assert_eq!(command_journal.replay(&create_restorers()).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_aborts_unknown_commands() {
+        let journal_directory = tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        command_journal.append(&*create_command(1, Some("waiting"))).await.unwrap();
+
+        assert!(command_journal.replay(&IndexMap::new()).await.is_empty());
+        assert!(command_journal.pending().is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use crate::client::command::Command;
+use crate::client::command::command_batch::CommandBatch;
+use crate::client::command::command_details::CommandReceipt;
+use crate::client::command::command_schedule::CommandSchedule;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::client::command::error::CommandError;
+
+/// What a request decided about the commands it queues, beyond the commands themselves.
+///
+/// Requests set each part within a scope (see `CommandSchedule::scope` and friends), which
+/// `current` collects as commands are queued. The metadata is applied to a command once and
+/// journaled with it, so that a replayed command keeps it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandMetadata {
+    #[serde(default)]
+    pub schedule: CommandSchedule,
+    #[serde(default)]
+    pub batch: Option<CommandBatch>,
+    #[serde(default)]
+    pub wallet: Option<CommandWallet>,
+}
+
+impl CommandMetadata {
+    pub fn current() -> Self {
+        Self {
+            schedule: CommandSchedule::current(),
+            batch: CommandBatch::current(),
+            wallet: CommandWallet::current(),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Applies the metadata to the command, unless it is the default.
+    pub fn apply(self, command: Box<dyn Command>) -> Box<dyn Command> {
+        if self.is_default() {
+            command
+        } else {
+            Box::new(CommandWithMetadata { command, command_metadata: self })
+        }
+    }
+}
+
+/// Wraps a command with its metadata, delegating everything else to it.
+struct CommandWithMetadata {
+    command: Box<dyn Command>,
+    command_metadata: CommandMetadata,
+}
+
+#[async_trait]
+impl Command for CommandWithMetadata {
+    async fn execute(&self) -> Result<(), CommandError> {
+        // commands run within the scope of their wallet, so that the network is paid from it
+        match &self.command_metadata.wallet {
+            Some(command_wallet) => command_wallet.clone().scope(self.command.execute()).await,
+            None => self.command.execute().await,
+        }
+    }
+
+    fn action_hash(&self) -> Vec<u8> {
+        self.command.action_hash()
+    }
+
+    fn id(&self) -> u128 {
+        self.command.id()
+    }
+
+    fn name(&self) -> String {
+        self.command.name()
+    }
+
+    fn properties(&self) -> IndexMap<String, String> {
+        self.command.properties()
+    }
+
+    fn target_address(&self) -> Option<String> {
+        self.command.target_address()
+    }
+
+    fn receipt(&self) -> CommandReceipt {
+        self.command.receipt()
+    }
+
+    fn metadata(&self) -> CommandMetadata {
+        self.command_metadata.clone()
+    }
+
+    fn payload(&self) -> Option<Bytes> {
+        self.command.payload()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use crate::client::command::test_command::IdCommand;
+
+    struct WalletCommand {
+        id: u128,
+        executed_with: Arc<Mutex<Option<CommandWallet>>>,
+    }
+
+    #[async_trait]
+    impl Command for WalletCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            *self.executed_with.lock().unwrap() = CommandWallet::current();
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            vec![]
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+    }
+
+    #[tokio::test]
+    async fn test_current_applies_scoped_metadata() {
+        assert!(CommandMetadata::current().is_default());
+
+        let command_schedule = CommandSchedule::new(5, Some(1000));
+        let command_batch = CommandBatch::new("batch".to_string());
+        let command_wallet = CommandWallet::new("team-a".to_string());
+        let command = command_schedule.clone().scope(command_batch.clone().scope(command_wallet.clone().scope(async {
+            CommandMetadata::current().apply(IdCommand::boxed(1))
+        }))).await;
+
+        assert_eq!(command.metadata(), CommandMetadata {
+            schedule: command_schedule,
+            batch: Some(command_batch),
+            wallet: Some(command_wallet),
+        });
+        assert_eq!(command.id(), 1);
+        assert_eq!(command.name(), "IdCommand");
+    }
+
+    #[tokio::test]
+    async fn test_command_executes_with_wallet() {
+        let command_wallet = CommandWallet::new("team-a".to_string());
+        let executed_with = Arc::new(Mutex::new(None));
+        let command = command_wallet.clone().scope(async {
+            CommandMetadata::current().apply(Box::new(WalletCommand { id: 1, executed_with: executed_with.clone() }))
+        }).await;
+
+        // executed outside of the request, as by the command executor
+        command.execute().await.unwrap();
+        assert_eq!(*executed_with.lock().unwrap(), Some(command_wallet));
+        assert_eq!(CommandWallet::current(), None);
+    }
+}
@@ -0,0 +1,91 @@
+use std::future::Future;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use crate::error::UpdateError;
+
+pub const PRIORITY_HEADER: &str = "x-command-priority";
+pub const NOT_BEFORE_HEADER: &str = "x-command-not-before";
+
+tokio::task_local! {
+    static CURRENT_COMMAND_SCHEDULE: CommandSchedule;
+}
+
+/// When a command may run, relative to other queued commands.
+///
+/// Commands with a higher priority run first. Commands with a `not_before` time (in milliseconds
+/// since the epoch) wait until then. Requests set the schedule for all commands they queue, by
+/// running within `scope`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommandSchedule {
+    pub priority: i32,
+    pub not_before: Option<u128>,
+}
+
+impl CommandSchedule {
+    pub fn new(priority: i32, not_before: Option<u128>) -> Self {
+        Self { priority, not_before }
+    }
+
+    /// Parses an integer priority and an RFC 3339 `not_before` time, either of which may be absent.
+    pub fn parse(priority: Option<&str>, not_before: Option<&str>) -> Result<Self, UpdateError> {
+        let priority = match priority.map(|priority| priority.trim()).filter(|priority| !priority.is_empty()) {
+            Some(priority) => priority.parse::<i32>()
+                .map_err(|e| UpdateError::InvalidData(format!("invalid command priority [{}]: {}", priority, e)))?,
+            None => 0,
+        };
+        let not_before = match not_before.map(|not_before| not_before.trim()).filter(|not_before| !not_before.is_empty()) {
+            Some(not_before) => Some(DateTime::parse_from_rfc3339(not_before)
+                .map_err(|e| UpdateError::InvalidData(format!("invalid command not before [{}]: {}", not_before, e)))?
+                .timestamp_millis().max(0) as u128),
+            None => None,
+        };
+        Ok(Self::new(priority, not_before))
+    }
+
+    /// As `parse`, for requests which already have an integer priority.
+    pub fn with_priority(priority: Option<i32>, not_before: Option<&str>) -> Result<Self, UpdateError> {
+        Ok(Self::new(priority.unwrap_or_default(), Self::parse(None, not_before)?.not_before))
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Runs the future with this schedule applied to any commands it queues.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_COMMAND_SCHEDULE.scope(self, future).await
+    }
+
+    pub fn current() -> Self {
+        CURRENT_COMMAND_SCHEDULE.try_with(|command_schedule| command_schedule.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(CommandSchedule::parse(None, None).unwrap(), CommandSchedule::default());
+        assert_eq!(
+            CommandSchedule::parse(Some("10"), Some("1970-01-01T00:00:01Z")).unwrap(),
+            CommandSchedule::new(10, Some(1000))
+        );
+        assert_eq!(CommandSchedule::parse(Some("-1"), Some("")).unwrap(), CommandSchedule::new(-1, None));
+        assert!(CommandSchedule::parse(Some("high"), None).is_err());
+        assert!(CommandSchedule::parse(None, Some("tomorrow")).is_err());
+        assert_eq!(CommandSchedule::with_priority(Some(3), None).unwrap(), CommandSchedule::new(3, None));
+    }
+
+    #[tokio::test]
+    async fn test_scope_applies_schedule() {
+        assert!(CommandSchedule::current().is_default());
+
+        let command_schedule = CommandSchedule::new(5, Some(1000)).scope(async {
+            CommandSchedule::current()
+        }).await;
+
+        assert_eq!(command_schedule, CommandSchedule::new(5, Some(1000)));
+    }
+}
@@ -0,0 +1,79 @@
+use std::future::Future;
+use serde::{Deserialize, Serialize};
+use crate::error::UpdateError;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+tokio::task_local! {
+    static CURRENT_COMMAND_WALLET: CommandWallet;
+}
+
+/// Keystore wallet which pays for commands, instead of the default wallet.
+///
+/// Requests select a wallet with an API key and pay for all commands they queue with it, by
+/// running within `scope`. Commands run within the scope of their wallet too, so that the
+/// network is paid from it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommandWallet {
+    pub name: String,
+}
+
+impl CommandWallet {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    /// Finds the wallet for an API key, which may be absent. Unknown API keys are refused.
+    pub fn from_api_key(api_key: Option<&str>, wallet_api_keys: &[(String, String)]) -> Result<Option<Self>, UpdateError> {
+        match api_key.map(|api_key| api_key.trim()).filter(|api_key| !api_key.is_empty()) {
+            Some(api_key) => match wallet_api_keys.iter().find(|(wallet_api_key, _)| wallet_api_key == api_key) {
+                Some((_, name)) => Ok(Some(Self::new(name.clone()))),
+                None => Err(UpdateError::InvalidData("unknown API key".to_string())),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Runs the future with this wallet paying for any commands it queues.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_COMMAND_WALLET.scope(self, future).await
+    }
+
+    /// Runs the future with the wallet, if any, paying for any commands it queues.
+    pub async fn scope_optional<F: Future>(command_wallet: Option<Self>, future: F) -> F::Output {
+        match command_wallet {
+            Some(command_wallet) => command_wallet.scope(future).await,
+            None => future.await,
+        }
+    }
+
+    pub fn current() -> Option<Self> {
+        CURRENT_COMMAND_WALLET.try_with(|command_wallet| command_wallet.clone()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_api_key() {
+        let wallet_api_keys = vec![("key-a".to_string(), "team-a".to_string()), ("key-b".to_string(), "team-b".to_string())];
+        assert_eq!(CommandWallet::from_api_key(None, &wallet_api_keys).unwrap(), None);
+        assert_eq!(CommandWallet::from_api_key(Some(" "), &wallet_api_keys).unwrap(), None);
+        assert_eq!(CommandWallet::from_api_key(Some("key-b"), &wallet_api_keys).unwrap(), Some(CommandWallet::new("team-b".to_string())));
+        assert!(CommandWallet::from_api_key(Some("key-c"), &wallet_api_keys).is_err());
+        assert!(CommandWallet::from_api_key(Some("key-a"), &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scope() {
+        assert_eq!(CommandWallet::current(), None);
+
+        let command_wallet = CommandWallet::new("team-a".to_string()).scope(async {
+            CommandWallet::current()
+        }).await;
+
+        assert_eq!(command_wallet, Some(CommandWallet::new("team-a".to_string())));
+    }
+}
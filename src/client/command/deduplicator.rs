@@ -0,0 +1,99 @@
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+struct DeduplicatorEntry {
+    id: u128,
+    completed_at: Option<Instant>,
+}
+
+/// Tracks action hashes of running and recently completed commands.
+///
+/// A command is a duplicate if a command with the same action hash is running, or completed
+/// within the window. Failed or aborted commands are forgotten, so that they can be retried.
+pub struct CommandDeduplicator {
+    window: Duration,
+    entries: HashMap<Vec<u8>, DeduplicatorEntry>,
+    expiries: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl CommandDeduplicator {
+    pub fn new(window: Duration) -> Self {
+        Self { window, entries: HashMap::new(), expiries: VecDeque::new() }
+    }
+
+    /// Returns the id of the original command, if the action hash is a duplicate.
+    pub fn original(&mut self, action_hash: &[u8]) -> Option<u128> {
+        self.expire(Instant::now());
+        self.entries.get(action_hash).map(|entry| entry.id)
+    }
+
+    pub fn start(&mut self, action_hash: Vec<u8>, id: u128) {
+        self.entries.insert(action_hash, DeduplicatorEntry { id, completed_at: None });
+    }
+
+    pub fn complete(&mut self, action_hash: &[u8], is_success: bool) {
+        if !is_success {
+            self.entries.remove(action_hash);
+            return;
+        }
+        if let Some(entry) = self.entries.get_mut(action_hash) {
+            let completed_at = Instant::now();
+            entry.completed_at = Some(completed_at);
+            self.expiries.push_back((completed_at, action_hash.to_vec()));
+        }
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((completed_at, _)) = self.expiries.front() {
+            if now.duration_since(*completed_at) < self.window {
+                break;
+            }
+            let (completed_at, action_hash) = self.expiries.pop_front().unwrap();
+            // the hash may have been started again since, so only remove the matching completion
+            if self.entries.get(&action_hash).and_then(|entry| entry.completed_at) == Some(completed_at) {
+                self.entries.remove(&action_hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_and_completed_commands_are_duplicates() {
+        let mut command_deduplicator = CommandDeduplicator::new(Duration::from_secs(60));
+        command_deduplicator.start(vec![1], 10);
+        assert_eq!(command_deduplicator.original(&[1]), Some(10));
+
+        command_deduplicator.complete(&[1], true);
+        assert_eq!(command_deduplicator.original(&[1]), Some(10));
+        assert_eq!(command_deduplicator.original(&[2]), None);
+    }
+
+    #[test]
+    fn test_failed_commands_are_forgotten() {
+        let mut command_deduplicator = CommandDeduplicator::new(Duration::from_secs(60));
+        command_deduplicator.start(vec![1], 10);
+        command_deduplicator.complete(&[1], false);
+
+        assert_eq!(command_deduplicator.original(&[1]), None);
+        assert_eq!(command_deduplicator.len(), 0);
+    }
+
+    #[test]
+    fn test_completed_commands_expire_after_window() {
+        let mut command_deduplicator = CommandDeduplicator::new(Duration::ZERO);
+        command_deduplicator.start(vec![1], 10);
+        command_deduplicator.complete(&[1], true);
+
+        assert_eq!(command_deduplicator.original(&[1]), None);
+        assert_eq!(command_deduplicator.len(), 0);
+    }
+}
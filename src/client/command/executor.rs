@@ -1,98 +1,236 @@
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use actix_web::web::Data;
 use indexmap::IndexMap;
 use log::{debug, error, warn};
-use tokio::sync::mpsc::{channel, Sender};
+use tokio::sync::mpsc::{channel, unbounded_channel, Sender};
 use tokio::sync::Mutex;
-use tokio::time::sleep;
+use tokio::time::{interval, sleep};
 use crate::client::command::Command;
-use crate::client::command::command_details::{CommandDetails, CommandState};
-use crate::client::command::command_details::CommandState::{ABORTED, COMPLETED, RUNNING};
+use crate::client::command::command_events::CommandEvents;
+use crate::client::command::command_details::{CommandAttempt, CommandDetails, CommandState};
+use crate::client::command::command_control::CommandControl;
+use crate::client::command::command_details::CommandState::{ABORTED, COMPLETED, FAILED, RUNNING, WAITING};
+use crate::client::command::command_journal::CommandJournal;
+use crate::client::command::deduplicator::CommandDeduplicator;
+use crate::client::command::scheduler::{CommandConcurrency, CommandScheduler, ScheduledCommand};
 use crate::client::command::error::CommandError;
 
+const PROPERTIES_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+// weight of the latest completed command in the average duration used for queue estimates
+const DURATION_SMOOTHING_PERCENT: u128 = 20;
+
 pub struct Executor {}
 
 impl Executor {
-    pub async fn start(buffer_size: usize, executor_map: Data<Mutex<IndexMap<u128, CommandDetails>>>) -> Sender<Box<dyn Command>> {
+    pub async fn start(buffer_size: usize, command_concurrency: CommandConcurrency, dedup_window: Duration, executor_map: Data<Mutex<IndexMap<u128, CommandDetails>>>, command_journal: CommandJournal, command_control: CommandControl, command_events: CommandEvents) -> Sender<Box<dyn Command>> {
         let (command_queue_sender, mut command_queue_receiver) = channel::<Box<dyn Command>>(buffer_size);
         let (command_executor_sender, mut command_executor_receiver) = channel::<Box<dyn Command>>(buffer_size);
 
         let pre_executor_map = executor_map.clone();
+        let pre_command_events = command_events.clone();
 
         // read the queue and insert command details into the executor map
         tokio::spawn(async move {
             while let Some(command) = command_queue_receiver.recv().await {
                 let command_details = CommandDetails::new(&command);
                 debug!("command buffered: [{:?}]", command_details);
-                pre_executor_map.get_ref().lock().await.insert(command.id(), command_details);
+                pre_executor_map.get_ref().lock().await.insert(command.id(), command_details.clone());
+                pre_command_events.publish(command_details);
 
                 command_executor_sender.send(command).await.unwrap();
             }
         });
 
-        // execute commands and update command details in the executor map
+        // schedule commands and execute them concurrently, updating command details in the executor map
         tokio::spawn(async move {
-            let mut last_hash = vec![];
-            while let Some(command) = command_executor_receiver.recv().await {
-                let command_action_hash = command.action_hash();
-                if last_hash == command_action_hash {
-                    Self::update_executor_map(&executor_map, buffer_size, command.id(), ABORTED).await;
-                } else {
-                    Self::update_executor_map(&executor_map, buffer_size, command.id(), RUNNING).await;
-
-                    let mut attempt = 1;
-                    loop {
-                        match command.execute().await {
-                            Ok(_) => break,
-                            Err(error) => {
-                                match error {
-                                    CommandError::Unrecoverable(_) => {
-                                        error!("failed to execute command [{}] with single attempt (skipping): [{:?}]", command.id(), error);
-                                        break;
-                                    },
-                                    CommandError::Recoverable(_) => {
-                                        if attempt <= 5 {
-                                            warn!("failed to execute command [{}] on attempt [{}] (retrying): [{:?}]", command.id(), attempt, error);
-                                            let backoff =  attempt * attempt;
-                                            sleep(Duration::from_secs(backoff)).await;
-                                            attempt += 1;
-                                        } else {
-                                            error!("failed to execute command [{}] after attempt [{}] (skipping): [{:?}]", command.id(), attempt, error);
-                                            break;
-                                        }
-                                    }
-                                }
+            let mut command_scheduler = CommandScheduler::new(command_concurrency);
+            let (completion_sender, mut completion_receiver) = unbounded_channel::<(String, Vec<Vec<u8>>, CommandState, u128)>();
+            let mut command_deduplicator = CommandDeduplicator::new(dedup_window);
+            let mut average_duration: Option<u128> = None;
+            let mut is_receiver_open = true;
+            let mut stopping_receiver = command_control.subscribe_stopping();
+            while is_receiver_open || command_scheduler.queued() > 0 || command_scheduler.running() > 0 {
+                while !command_control.is_stopping() {
+                    let Some(ScheduledCommand { command, name, keys, .. }) = command_scheduler.pop() else {
+                        break;
+                    };
+                    let command_action_hash = command.action_hash();
+                    if let Some(original_id) = command_deduplicator.original(&command_action_hash) {
+                        debug!("command [{}] is a duplicate of command [{}]", command.id(), original_id);
+                        Self::update_executor_outcome(&executor_map, command.id(), |command_details| command_details.set_duplicate_of(original_id)).await;
+                        Self::update_executor_map(&executor_map, &command_journal, &command_events, buffer_size, command.id(), ABORTED).await;
+                        // it may also have been cancelled while waiting for the executor map
+                        command_control.forget_cancelled(command.id());
+                        command_scheduler.complete(&name, &keys);
+                        continue;
+                    }
+                    let Some(notify) = command_control.register(command.id()) else {
+                        Self::update_executor_map(&executor_map, &command_journal, &command_events, buffer_size, command.id(), ABORTED).await;
+                        command_scheduler.complete(&name, &keys);
+                        continue;
+                    };
+                    command_deduplicator.start(command_action_hash, command.id());
+                    Self::update_executor_map(&executor_map, &command_journal, &command_events, buffer_size, command.id(), RUNNING).await;
+
+                    let local_executor_map = executor_map.clone();
+                    let local_command_journal = command_journal.clone();
+                    let local_command_control = command_control.clone();
+                    let local_command_events = command_events.clone();
+                    let local_completion_sender = completion_sender.clone();
+                    tokio::spawn(async move {
+                        let started_at = Instant::now();
+                        let command_state = tokio::select! {
+                            result = Self::execute_with_retries(&local_executor_map, &local_command_events, command.as_ref()) => match result {
+                                Ok(_) => {
+                                    Self::update_executor_outcome(&local_executor_map, command.id(), |command_details| command_details.set_receipt(command.receipt())).await;
+                                    COMPLETED
+                                },
+                                Err(error) => {
+                                    Self::update_executor_outcome(&local_executor_map, command.id(), |command_details| command_details.set_error(error.to_string())).await;
+                                    FAILED
+                                },
+                            },
+                            _ = notify.notified() => {
+                                warn!("cancelled command [{}]", command.id());
+                                ABORTED
+                            },
+                        };
+                        local_command_control.unregister(command.id());
+                        Self::update_executor_map(&local_executor_map, &local_command_journal, &local_command_events, buffer_size, command.id(), command_state.clone()).await;
+                        if command_state == FAILED {
+                            local_command_control.insert_failed(command);
+                        }
+                        local_completion_sender.send((name, keys, command_state, started_at.elapsed().as_millis())).unwrap();
+                    });
+                }
+                // on shutdown, leave waiting commands in the journal and wait for running commands
+                if command_control.is_stopping() && command_scheduler.running() == 0 {
+                    debug!("command executor stopped with [{}] commands waiting", command_scheduler.queued());
+                    break;
+                }
+                Self::update_queue_estimates(&executor_map, &command_scheduler, average_duration).await;
+
+                // wake when the next deferred command becomes ready
+                let ready_in = command_scheduler.next_ready_at()
+                    .map(|ready_at| Duration::from_millis(ready_at.saturating_sub(Self::now()) as u64));
+                tokio::select! {
+                    maybe_command = command_executor_receiver.recv(), if is_receiver_open && !command_control.is_stopping() && command_scheduler.queued() < buffer_size => {
+                        match maybe_command {
+                            Some(command) => command_scheduler.push(command),
+                            None => is_receiver_open = false,
+                        }
+                    }
+                    _ = command_control.waiting_cancelled() => {
+                        // drop cancelled commands now, so they do not hold their name or key slots
+                        // commands still buffered stay cancelled until they are scheduled
+                        for command_id in command_control.cancelled() {
+                            if command_scheduler.remove(command_id).is_some() {
+                                command_control.forget_cancelled(command_id);
+                                Self::update_executor_map(&executor_map, &command_journal, &command_events, buffer_size, command_id, ABORTED).await;
                             }
                         }
                     }
-
-                    Self::update_executor_map(&executor_map, buffer_size, command.id(), COMPLETED).await;
-                    last_hash = command_action_hash;
+                    Some((name, keys, command_state, duration)) = completion_receiver.recv() => {
+                        command_deduplicator.complete(&keys[0], command_state == COMPLETED);
+                        command_scheduler.complete(&name, &keys);
+                        if command_state == COMPLETED {
+                            average_duration = Some(match average_duration {
+                                Some(average) => (average * (100 - DURATION_SMOOTHING_PERCENT) + duration * DURATION_SMOOTHING_PERCENT) / 100,
+                                None => duration,
+                            });
+                        }
+                    }
+                    _ = sleep(ready_in.unwrap_or_default()), if ready_in.is_some() => {}
+                    _ = stopping_receiver.changed(), if !command_control.is_stopping() => {}
                 }
             }
+            command_control.notify_stopped();
         });
 
         command_queue_sender
     }
 
+    async fn execute_with_retries(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_events: &CommandEvents, command: &dyn Command) -> Result<(), CommandError> {
+        let mut attempt = 1;
+        loop {
+            let started_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+            match Self::execute_command(executor_map, command_events, command).await {
+                Ok(_) => {
+                    Self::update_executor_attempt(executor_map, command.id(), CommandAttempt { started_at, error: None, backoff_seconds: None }).await;
+                    return Ok(())
+                },
+                Err(error) => {
+                    match error {
+                        CommandError::Unrecoverable(_) => {
+                            error!("failed to execute command [{}] with single attempt (skipping): [{:?}]", command.id(), error);
+                            Self::update_executor_attempt(executor_map, command.id(), CommandAttempt { started_at, error: Some(error.to_string()), backoff_seconds: None }).await;
+                            return Err(error);
+                        },
+                        CommandError::Recoverable(_) => {
+                            if attempt <= 5 {
+                                warn!("failed to execute command [{}] on attempt [{}] (retrying): [{:?}]", command.id(), attempt, error);
+                                let backoff =  attempt * attempt;
+                                Self::update_executor_attempt(executor_map, command.id(), CommandAttempt { started_at, error: Some(error.to_string()), backoff_seconds: Some(backoff) }).await;
+                                sleep(Duration::from_secs(backoff)).await;
+                                attempt += 1;
+                            } else {
+                                error!("failed to execute command [{}] after attempt [{}] (skipping): [{:?}]", command.id(), attempt, error);
+                                Self::update_executor_attempt(executor_map, command.id(), CommandAttempt { started_at, error: Some(error.to_string()), backoff_seconds: None }).await;
+                                return Err(error);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn execute_command(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_events: &CommandEvents, command: &dyn Command) -> Result<(), CommandError> {
+        // refresh properties while running, so that long-running commands can report progress
+        // changes are published too, while the final properties are published with the new state
+        let execution = command.execute();
+        tokio::pin!(execution);
+        let mut properties_interval = interval(PROPERTIES_REFRESH_INTERVAL);
+        properties_interval.tick().await;
+        loop {
+            tokio::select! {
+                result = &mut execution => {
+                    Self::update_executor_properties(executor_map, command.id(), command.properties()).await;
+                    return result;
+                }
+                _ = properties_interval.tick() => {
+                    if let Some(command_details) = Self::update_executor_properties(executor_map, command.id(), command.properties()).await {
+                        command_events.publish(command_details);
+                    }
+                }
+            }
+        }
+    }
+
     /*async fn log_executor_map(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>) {
         let mut executor_map_string = String::new();
         executor_map.lock().await.iter().for_each(|(_, v)| executor_map_string += &format!("{:?},", v).as_str());
         debug!("command queue {:?}", executor_map_string);
     }*/
 
-    async fn update_executor_map(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, buffer_size: usize, command_id: u128, command_state: CommandState) {
+    async fn update_executor_map(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_journal: &CommandJournal, command_events: &CommandEvents, buffer_size: usize, command_id: u128, command_state: CommandState) {
+        command_journal.update(command_id, &command_state).await;
         let maybe_command_details = match executor_map.get_ref().lock().await.get(&command_id) {
             Some(command_details) => {
+                let is_transition = command_details.state() != &command_state;
                 let mut new_command_details = command_details.clone();
                 new_command_details.set_state(command_state);
-                Some(new_command_details)
+                Some((new_command_details, is_transition))
             },
             None => None, // should never happen
         };
-        if let Some(command_detail) = maybe_command_details {
-            executor_map.get_ref().lock().await.insert(command_id.clone(), command_detail.clone());
+        if let Some((command_detail, is_transition)) = maybe_command_details {
+            executor_map.get_ref().lock().await.insert(command_id, command_detail.clone());
             debug!("command status: [{:?}]", command_detail);
+            // commands cancelled while waiting have already been reported as aborted
+            if is_transition {
+                command_events.publish(command_detail);
+            }
         }
         if executor_map.get_ref().lock().await.len() > (buffer_size * 128) {
             // todo: tune size/content to prevent useful records scrolling out of the map
@@ -100,14 +238,71 @@ impl Executor {
             executor_map.get_ref().lock().await.shift_remove_index(0);
         }
     }
+
+    /// Sets the queue position and estimated start time of waiting commands.
+    ///
+    /// Commands are assumed to take the average duration of completed commands and to run in
+    /// waves of the concurrency limit, after those already running.
+    async fn update_queue_estimates(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_scheduler: &CommandScheduler, average_duration: Option<u128>) {
+        let queue_order = command_scheduler.queue_order();
+        if queue_order.is_empty() {
+            return;
+        }
+        let now = Self::now();
+        let limit = command_scheduler.limit().max(1);
+        let running = command_scheduler.running();
+        let mut executor_map = executor_map.get_ref().lock().await;
+        for (queue_position, (command_id, not_before)) in queue_order.into_iter().enumerate() {
+            let eta = match average_duration {
+                Some(average_duration) => {
+                    let eta = now + average_duration * ((running + queue_position) / limit) as u128;
+                    Some(eta.max(not_before.unwrap_or(0)))
+                },
+                None => not_before,
+            };
+            if let Some(command_details) = executor_map.get_mut(&command_id).filter(|command_details| command_details.state() == &WAITING) {
+                command_details.set_queue_estimate(queue_position, eta);
+            }
+        }
+    }
+
+    fn now() -> u128 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()
+    }
+
+    async fn update_executor_attempt(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_id: u128, attempt: CommandAttempt) {
+        Self::update_executor_outcome(executor_map, command_id, |command_details| command_details.add_attempt(attempt)).await;
+    }
+
+    async fn update_executor_outcome<F: FnOnce(&mut CommandDetails)>(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_id: u128, update: F) {
+        if let Some(command_details) = executor_map.get_ref().lock().await.get_mut(&command_id) {
+            update(command_details);
+        }
+    }
+
+    /// Sets the properties of the command, returning its details if they changed.
+    async fn update_executor_properties(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_id: u128, properties: IndexMap<String, String>) -> Option<CommandDetails> {
+        match executor_map.get_ref().lock().await.get_mut(&command_id) {
+            Some(command_details) if command_details.properties() != &properties => {
+                command_details.set_properties(properties);
+                Some(command_details.clone())
+            },
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
     use std::sync::Arc;
     use async_trait::async_trait;
+    use tempfile::TempDir;
     use crate::client::command::Command;
+    use crate::client::command::command_details::CommandReceipt;
+    use crate::client::command::command_metadata::CommandMetadata;
+    use crate::client::command::command_schedule::CommandSchedule;
     use crate::client::command::error::CommandError;
 
     #[derive(Clone)]
@@ -147,6 +342,15 @@ mod tests {
         }
     }
 
+    const DEDUP_WINDOW: Duration = Duration::from_secs(60);
+
+    // the journal directory is removed when the returned guard is dropped
+    fn create_command_journal() -> (TempDir, CommandJournal) {
+        let journal_directory = tempfile::tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        (journal_directory, command_journal)
+    }
+
     async fn wait_for_state(executor_map: &Data<Mutex<IndexMap<u128, CommandDetails>>>, command_id: u128, expected_state: CommandState) {
         for _ in 0..100 { // Wait up to 10 seconds (100 * 100ms)
             let map = executor_map.lock().await;
@@ -164,7 +368,8 @@ mod tests {
     #[tokio::test]
     async fn test_execute_success() {
         let executor_map = Data::new(Mutex::new(IndexMap::new()));
-        let sender = Executor::start(10, executor_map.clone()).await;
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
 
         let command = MockCommand::new(1, vec![1], vec![Ok(())]);
         sender.send(Box::new(command)).await.unwrap();
@@ -175,7 +380,8 @@ mod tests {
     #[tokio::test]
     async fn test_execute_failure_recoverable() {
         let executor_map = Data::new(Mutex::new(IndexMap::new()));
-        let sender = Executor::start(10, executor_map.clone()).await;
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
 
         // Fail twice with recoverable error, then succeed
         // Note: results are popped, so push in reverse order: Ok, Err, Err
@@ -188,12 +394,19 @@ mod tests {
         sender.send(Box::new(command)).await.unwrap();
 
         wait_for_state(&executor_map, 2, COMPLETED).await;
+        let map = executor_map.lock().await;
+        let attempts = map.get(&2).unwrap().attempts();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].backoff_seconds, Some(1));
+        assert_eq!(attempts[1].backoff_seconds, Some(4));
+        assert_eq!(attempts[2].error, None);
     }
 
     #[tokio::test]
     async fn test_execute_failure_unrecoverable() {
         let executor_map = Data::new(Mutex::new(IndexMap::new()));
-        let sender = Executor::start(10, executor_map.clone()).await;
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
 
         let results = vec![
             Err(CommandError::Unrecoverable("fail".to_string())),
@@ -201,14 +414,17 @@ mod tests {
         let command = MockCommand::new(3, vec![3], results);
         sender.send(Box::new(command)).await.unwrap();
 
-        // Even unrecoverable errors result in COMPLETED state in current implementation
-        wait_for_state(&executor_map, 3, COMPLETED).await;
+        wait_for_state(&executor_map, 3, FAILED).await;
+        let map = executor_map.lock().await;
+        assert_eq!(map.get(&3).unwrap().error().unwrap(), "unrecoverable error: fail");
+        assert_eq!(map.get(&3).unwrap().attempts().len(), 1);
     }
 
     #[tokio::test]
     async fn test_execute_duplicate() {
         let executor_map = Data::new(Mutex::new(IndexMap::new()));
-        let sender = Executor::start(10, executor_map.clone()).await;
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
 
         let command1 = MockCommand::new(4, vec![4], vec![Ok(())]);
         let command2 = MockCommand::new(5, vec![4], vec![Ok(())]); // Same hash as command1
@@ -218,7 +434,349 @@ mod tests {
         wait_for_state(&executor_map, 4, COMPLETED).await;
 
         sender.send(Box::new(command2)).await.unwrap();
-        // Second should be aborted because hash matches last executed
+        // Second should be aborted because hash matches a recently completed command
         wait_for_state(&executor_map, 5, ABORTED).await;
+        assert_eq!(executor_map.lock().await.get(&5).unwrap().duplicate_of(), Some(4));
+    }
+
+    struct ProgressCommand {
+        id: u128,
+        progress: Arc<Mutex<u32>>,
     }
-}
\ No newline at end of file
+
+    #[async_trait]
+    impl Command for ProgressCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            *self.progress.lock().await = 100;
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            vec![6]
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+
+        fn properties(&self) -> IndexMap<String, String> {
+            let mut properties = IndexMap::new();
+            let progress = self.progress.try_lock().map(|progress| *progress).unwrap_or_default();
+            properties.insert("progress".to_string(), progress.to_string());
+            properties
+        }
+
+        fn receipt(&self) -> CommandReceipt {
+            CommandReceipt { result_address: Some("address".to_string()), bytes_uploaded: Some(100), ..Default::default() }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_refreshes_properties() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
+
+        let command = ProgressCommand { id: 6, progress: Arc::new(Mutex::new(0)) };
+        sender.send(Box::new(command)).await.unwrap();
+
+        wait_for_state(&executor_map, 6, COMPLETED).await;
+        let map = executor_map.lock().await;
+        assert_eq!(map.get(&6).unwrap().properties().get("progress").unwrap(), "100");
+        assert_eq!(map.get(&6).unwrap().receipt().result_address, Some("address".to_string()));
+        assert_eq!(map.get(&6).unwrap().receipt().bytes_uploaded, Some(100));
+    }
+
+    struct JournaledCommand {
+        id: u128,
+    }
+
+    #[async_trait]
+    impl Command for JournaledCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            vec![7]
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+
+        fn payload(&self) -> Option<bytes::Bytes> {
+            Some(bytes::Bytes::from("payload"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_removes_completed_command_from_journal() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal.clone(), CommandControl::new(10), CommandEvents::new(10)).await;
+
+        let command: Box<dyn Command> = Box::new(JournaledCommand { id: 7 });
+        command_journal.append(command.as_ref()).await.unwrap();
+        assert_eq!(command_journal.pending().len(), 1);
+        sender.send(command).await.unwrap();
+
+        wait_for_state(&executor_map, 7, COMPLETED).await;
+        assert!(command_journal.pending().is_empty());
+    }
+
+    struct BlockingCommand {
+        id: u128,
+        release: Arc<tokio::sync::Semaphore>,
+    }
+
+    #[async_trait]
+    impl Command for BlockingCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            self.release.acquire().await.unwrap().forget();
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            vec![8]
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+
+        fn name(&self) -> String {
+            "BlockingCommand".to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_concurrently() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::new(2, HashMap::new()), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
+
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        sender.send(Box::new(BlockingCommand { id: 8, release: release.clone() })).await.unwrap();
+        sender.send(Box::new(MockCommand::new(9, vec![9], vec![Ok(())]))).await.unwrap();
+
+        // the second command completes while the first is still blocked
+        wait_for_state(&executor_map, 8, RUNNING).await;
+        wait_for_state(&executor_map, 9, COMPLETED).await;
+
+        release.add_permits(1);
+        wait_for_state(&executor_map, 8, COMPLETED).await;
+    }
+
+    #[tokio::test]
+    async fn test_execute_same_action_hash_sequentially() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::new(2, HashMap::new()), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
+
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        sender.send(Box::new(BlockingCommand { id: 10, release: release.clone() })).await.unwrap();
+        sender.send(Box::new(MockCommand::new(11, vec![11], vec![Ok(())]))).await.unwrap();
+        sender.send(Box::new(BlockingCommand { id: 12, release: release.clone() })).await.unwrap();
+
+        wait_for_state(&executor_map, 11, COMPLETED).await;
+        wait_for_state(&executor_map, 12, CommandState::WAITING).await;
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(executor_map.lock().await.get(&12).unwrap().state(), &CommandState::WAITING);
+
+        // the same action hash within the de-duplication window is not executed again
+        release.add_permits(1);
+        wait_for_state(&executor_map, 10, COMPLETED).await;
+        wait_for_state(&executor_map, 12, ABORTED).await;
+        assert_eq!(executor_map.lock().await.get(&12).unwrap().duplicate_of(), Some(10));
+    }
+
+    #[tokio::test]
+    async fn test_execute_highest_priority_first_with_queue_positions() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::new(1, HashMap::new()), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
+
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        sender.send(Box::new(BlockingCommand { id: 18, release: release.clone() })).await.unwrap();
+        sender.send(Box::new(MockCommand::new(19, vec![19], vec![Ok(())]))).await.unwrap();
+        let command_metadata = CommandMetadata { schedule: CommandSchedule::new(10, None), ..Default::default() };
+        sender.send(command_metadata.apply(Box::new(MockCommand::new(20, vec![20], vec![Ok(())])))).await.unwrap();
+        wait_for_state(&executor_map, 18, RUNNING).await;
+
+        let mut queue_positions = (None, None);
+        for _ in 0..100 {
+            let map = executor_map.lock().await;
+            queue_positions = (map.get(&19).and_then(|c| c.queue_position()), map.get(&20).and_then(|c| c.queue_position()));
+            if queue_positions == (Some(1), Some(0)) {
+                break;
+            }
+            drop(map);
+            sleep(Duration::from_millis(100)).await;
+        }
+        assert_eq!(queue_positions, (Some(1), Some(0)));
+        assert_eq!(executor_map.lock().await.get(&20).unwrap().priority(), 10);
+
+        release.add_permits(1);
+        wait_for_state(&executor_map, 19, COMPLETED).await;
+        wait_for_state(&executor_map, 20, COMPLETED).await;
+        let map = executor_map.lock().await;
+        assert!(map.get(&20).unwrap().running_at().unwrap() <= map.get(&19).unwrap().running_at().unwrap());
+        assert_eq!(map.get(&20).unwrap().queue_position(), None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_failed_command_is_not_a_duplicate() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), CommandEvents::new(10)).await;
+
+        sender.send(Box::new(MockCommand::new(16, vec![16], vec![Err(CommandError::Unrecoverable("fail".to_string()))]))).await.unwrap();
+        wait_for_state(&executor_map, 16, FAILED).await;
+
+        sender.send(Box::new(MockCommand::new(17, vec![16], vec![Ok(())]))).await.unwrap();
+        wait_for_state(&executor_map, 17, COMPLETED).await;
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_and_waiting_commands() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let command_control = CommandControl::new(10);
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, command_control.clone(), CommandEvents::new(10)).await;
+
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        sender.send(Box::new(BlockingCommand { id: 13, release: release.clone() })).await.unwrap();
+        sender.send(Box::new(MockCommand::new(14, vec![14], vec![Ok(())]))).await.unwrap();
+        wait_for_state(&executor_map, 13, RUNNING).await;
+        wait_for_state(&executor_map, 14, CommandState::WAITING).await;
+
+        command_control.cancel(14);
+        command_control.cancel(13);
+
+        wait_for_state(&executor_map, 13, ABORTED).await;
+        wait_for_state(&executor_map, 14, ABORTED).await;
+    }
+
+    #[tokio::test]
+    async fn test_cancel_waiting_command_releases_its_key() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let command_control = CommandControl::new(10);
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::new(2, HashMap::new()), DEDUP_WINDOW, executor_map.clone(), command_journal, command_control.clone(), CommandEvents::new(10)).await;
+
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        sender.send(Box::new(BlockingCommand { id: 22, release: release.clone() })).await.unwrap();
+        sender.send(Box::new(MockCommand::new(23, vec![8], vec![Ok(())]))).await.unwrap();
+        sender.send(Box::new(MockCommand::new(24, vec![24], vec![Ok(())]))).await.unwrap();
+        wait_for_state(&executor_map, 22, RUNNING).await;
+        wait_for_state(&executor_map, 24, COMPLETED).await;
+
+        command_control.cancel(23);
+
+        wait_for_state(&executor_map, 23, ABORTED).await;
+        assert_eq!(executor_map.lock().await.get(&22).unwrap().state(), &RUNNING);
+        assert!(command_control.cancelled().is_empty());
+        release.add_permits(1);
+        wait_for_state(&executor_map, 22, COMPLETED).await;
+    }
+
+    #[tokio::test]
+    async fn test_stop_waits_for_running_commands_only() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let command_control = CommandControl::new(10);
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, command_control.clone(), CommandEvents::new(10)).await;
+
+        let release = Arc::new(tokio::sync::Semaphore::new(0));
+        sender.send(Box::new(BlockingCommand { id: 20, release: release.clone() })).await.unwrap();
+        sender.send(Box::new(MockCommand::new(21, vec![21], vec![Ok(())]))).await.unwrap();
+        wait_for_state(&executor_map, 20, RUNNING).await;
+        wait_for_state(&executor_map, 21, CommandState::WAITING).await;
+
+        command_control.stop();
+        release.add_permits(1);
+        tokio::time::timeout(Duration::from_secs(10), command_control.stopped()).await.unwrap();
+
+        let map = executor_map.lock().await;
+        assert_eq!(map.get(&20).unwrap().state(), &COMPLETED);
+        assert_eq!(map.get(&21).unwrap().state(), &CommandState::WAITING);
+    }
+
+    #[tokio::test]
+    async fn test_failed_command_is_kept_for_retry() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let command_control = CommandControl::new(10);
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, command_control.clone(), CommandEvents::new(10)).await;
+
+        let command = MockCommand::new(15, vec![15], vec![Err(CommandError::Unrecoverable("fail".to_string()))]);
+        sender.send(Box::new(command)).await.unwrap();
+
+        wait_for_state(&executor_map, 15, FAILED).await;
+        assert_eq!(command_control.take_failed(15).unwrap().id(), 15);
+    }
+
+    #[tokio::test]
+    async fn test_execute_publishes_state_transitions() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let command_events = CommandEvents::new(10);
+        let mut receiver = command_events.subscribe();
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), command_events).await;
+
+        sender.send(Box::new(MockCommand::new(18, vec![18], vec![Ok(())]))).await.unwrap();
+
+        for expected_state in [CommandState::WAITING, RUNNING, COMPLETED] {
+            let command_details = receiver.recv().await.unwrap();
+            assert_eq!(command_details.id(), 18);
+            assert_eq!(command_details.state(), &expected_state);
+        }
+    }
+
+    struct SlowProgressCommand {
+        id: u128,
+        progress: Arc<std::sync::Mutex<u32>>,
+    }
+
+    #[async_trait]
+    impl Command for SlowProgressCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            *self.progress.lock().unwrap() = 50;
+            sleep(Duration::from_millis(1500)).await;
+            *self.progress.lock().unwrap() = 100;
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            vec![19]
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+
+        fn properties(&self) -> IndexMap<String, String> {
+            let mut properties = IndexMap::new();
+            properties.insert("progress".to_string(), self.progress.lock().unwrap().to_string());
+            properties
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_publishes_progress() {
+        let executor_map = Data::new(Mutex::new(IndexMap::new()));
+        let command_events = CommandEvents::new(10);
+        let mut receiver = command_events.subscribe();
+        let (_journal_directory, command_journal) = create_command_journal();
+        let sender = Executor::start(10, CommandConcurrency::default(), DEDUP_WINDOW, executor_map.clone(), command_journal, CommandControl::new(10), command_events).await;
+
+        sender.send(Box::new(SlowProgressCommand { id: 19, progress: Arc::new(std::sync::Mutex::new(0)) })).await.unwrap();
+
+        for (expected_state, expected_progress) in [(CommandState::WAITING, "0"), (RUNNING, "0"), (RUNNING, "50"), (COMPLETED, "100")] {
+            let command_details = receiver.recv().await.unwrap();
+            assert_eq!(command_details.state(), &expected_state);
+            assert_eq!(command_details.properties().get("progress").unwrap(), expected_progress);
+        }
+    }
+}
@@ -5,20 +5,19 @@ use autonomi::client::payment::PaymentOption;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
 
 pub struct CreateGraphEntryCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     graph_entry: GraphEntry,
     payment_option: PaymentOption,
 }
 
 impl CreateGraphEntryCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, graph_entry: GraphEntry, payment_option: PaymentOption) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, graph_entry: GraphEntry, payment_option: PaymentOption) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, graph_entry, payment_option }
     }
@@ -29,7 +28,7 @@ const STRUCT_NAME: &'static str = "CreateGraphEntryCommand";
 #[async_trait]
 impl Command for CreateGraphEntryCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let graph_entry_hex = self.graph_entry.address().to_string();
         debug!("creating graph entry at [{}] async", graph_entry_hex);
         client.graph_entry_put(self.graph_entry.clone(), self.payment_option.clone()).await?;
@@ -1,10 +1,13 @@
 #![cfg_attr(test, warn(unused_imports, unused_variables, dead_code))]
 use async_trait::async_trait;
+use bytes::Bytes;
 use indexmap::IndexMap;
+use crate::client::command::command_details::CommandReceipt;
+use crate::client::command::command_metadata::CommandMetadata;
 use crate::client::command::error::CommandError;
 
 #[async_trait]
-pub trait Command: Send {
+pub trait Command: Send + Sync {
     async fn execute(&self) -> Result<(), CommandError>;
 
     fn action_hash(&self) -> Vec<u8>;
@@ -18,6 +21,26 @@ pub trait Command: Send {
     fn properties(&self) -> IndexMap<String, String> {
         IndexMap::new()
     }
+
+    /// Address this command reads or writes. Commands sharing a target address run sequentially.
+    fn target_address(&self) -> Option<String> {
+        None
+    }
+
+    /// Outcome of the last successful execution, e.g. the address created and what it cost.
+    fn receipt(&self) -> CommandReceipt {
+        CommandReceipt::default()
+    }
+
+    /// Schedule, batch and paying wallet of the command.
+    fn metadata(&self) -> CommandMetadata {
+        CommandMetadata::default()
+    }
+
+    /// Data needed to restore the command after a restart. Commands without a payload are not journaled.
+    fn payload(&self) -> Option<Bytes> {
+        None
+    }
 }
 
 //pub mod pointer;
@@ -25,9 +48,22 @@ pub trait Command: Send {
 pub mod executor;
 pub mod chunk;
 pub mod public_data;
+pub mod prefetch;
 pub mod error;
 //pub mod graph;
 //pub mod scratchpad;
+pub mod command_batch;
+pub mod command_control;
 pub mod command_details;
+pub mod command_events;
+pub mod command_journal;
+pub mod command_metadata;
+pub mod command_schedule;
+pub mod command_wallet;
+pub mod deduplicator;
+pub mod scheduler;
+#[cfg(test)]
+pub mod test_command;
+pub mod upload_progress;
 //pub mod access_checker;
 //pub mod bookmark_resolver;
\ No newline at end of file
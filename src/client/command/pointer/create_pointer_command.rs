@@ -6,14 +6,13 @@ use autonomi::pointer::PointerTarget;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
 
 pub struct CreatePointerCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     owner: SecretKey,
     target: PointerTarget,
     counter: Option<u64>,
@@ -21,7 +20,7 @@ pub struct CreatePointerCommand {
 }
 
 impl CreatePointerCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, owner: SecretKey, target: PointerTarget,
+    pub fn new(client_harness: Data<ClientHarness>, owner: SecretKey, target: PointerTarget,
                counter: Option<u64>, payment_option: PaymentOption,) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, owner, target, counter, payment_option }
@@ -33,7 +32,7 @@ const STRUCT_NAME: &'static str = "CreatePointerCommand";
 #[async_trait]
 impl Command for CreatePointerCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let pointer_address_hex = PointerAddress::new(self.owner.public_key()).to_hex();
         debug!("creating pointer at [{}] async", pointer_address_hex);
         client.pointer_create(&self.owner, self.target.clone(), self.payment_option.clone()).await?;
@@ -96,7 +95,7 @@ mod tests {
         let payment_option = PaymentOption::Wallet(autonomi::Wallet::new_with_random_wallet(autonomi::Network::ArbitrumOne));
 
         CreatePointerCommand::new(
-            Data::new(Mutex::new(client_harness)),
+            Data::new(client_harness),
             owner,
             target,
             counter,
@@ -5,7 +5,6 @@ use foyer::HybridCache;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::cache_item::CacheItem;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
@@ -14,14 +13,14 @@ use crate::client::POINTER_CACHE_KEY;
 
 pub struct GetPointerCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
     pointer_address: PointerAddress,
     ttl: u64,
 }
 
 impl GetPointerCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, hybrid_cache: Data<HybridCache<String, Vec<u8>>>, pointer_address: PointerAddress, ttl: u64) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, hybrid_cache: Data<HybridCache<String, Vec<u8>>>, pointer_address: PointerAddress, ttl: u64) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, hybrid_cache, pointer_address, ttl }
     }
@@ -32,7 +31,7 @@ const STRUCT_NAME: &'static str = "GetPointerCommand";
 #[async_trait]
 impl Command for GetPointerCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let pointer_address_hex = self.pointer_address.to_hex();
         debug!("refreshing hybrid cache with pointer for [{}] from network", pointer_address_hex);
         let pointer = client.pointer_get(&self.pointer_address).await?;
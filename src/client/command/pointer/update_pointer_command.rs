@@ -5,21 +5,20 @@ use autonomi::pointer::PointerTarget;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
 
 pub struct UpdatePointerCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     owner: SecretKey,
     target: PointerTarget,
     counter: Option<u64>,
 }
 
 impl UpdatePointerCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, owner: SecretKey, target: PointerTarget, counter: Option<u64>) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, owner: SecretKey, target: PointerTarget, counter: Option<u64>) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, owner, target, counter }
     }
@@ -30,7 +29,7 @@ const STRUCT_NAME: &'static str = "UpdatePointerCommand";
 #[async_trait]
 impl Command for UpdatePointerCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let pointer_address_hex = PointerAddress::new(self.owner.public_key()).to_hex();
         debug!("updating pointer at [{}] async", pointer_address_hex);
         match self.counter {
@@ -91,7 +90,7 @@ mod tests {
         let target = PointerTarget::ChunkAddress(ChunkAddress::from_hex("a40e045a6fbed33b27039aa8383c9dbf286e19a7265141c2da3085e0c8571527").unwrap());
 
         UpdatePointerCommand::new(
-            Data::new(Mutex::new(client_harness)),
+            Data::new(client_harness),
             owner,
             target,
             counter,
@@ -0,0 +1,2 @@
+#![cfg_attr(test, warn(unused_imports, unused_variables, dead_code))]
+pub mod prefetch_command;
\ No newline at end of file
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use ant_core::data::{DataChunk, DataMap, XorName};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use indexmap::{IndexMap, IndexSet};
+use log::{debug, info, warn};
+use mockall_double::double;
+use sha2::Digest;
+#[double]
+use crate::client::ArchiveCachingClient;
+#[double]
+use crate::client::ChunkCachingClient;
+use crate::client::command::Command;
+use crate::client::command::error::CommandError;
+
+pub struct PrefetchCommand {
+    id: u128,
+    chunk_caching_client: ChunkCachingClient,
+    archive_caching_client: ArchiveCachingClient,
+    address: XorName,
+    concurrency: usize,
+    chunks_total: AtomicUsize,
+    chunks_done: AtomicUsize,
+    chunks_failed: AtomicUsize,
+    bytes_done: AtomicU64,
+}
+
+impl PrefetchCommand {
+    pub fn new(chunk_caching_client: ChunkCachingClient, archive_caching_client: ArchiveCachingClient, address: XorName, concurrency: usize) -> Self {
+        let id = rand::random::<u128>();
+        let concurrency = if concurrency > 0 { concurrency } else { 1 };
+        Self {
+            id, chunk_caching_client, archive_caching_client, address, concurrency,
+            chunks_total: AtomicUsize::new(0),
+            chunks_done: AtomicUsize::new(0),
+            chunks_failed: AtomicUsize::new(0),
+            bytes_done: AtomicU64::new(0),
+        }
+    }
+
+    pub fn restore(id: u128, chunk_caching_client: ChunkCachingClient, archive_caching_client: ArchiveCachingClient, payload: Bytes, concurrency: usize) -> Option<Self> {
+        let address = XorName::try_from(payload.as_ref()).ok()?;
+        Some(Self { id, ..Self::new(chunk_caching_client, archive_caching_client, address, concurrency) })
+    }
+
+    async fn get_data_addresses(&self) -> IndexSet<XorName> {
+        let mut data_addresses = IndexSet::new();
+        data_addresses.insert(self.address);
+        match self.archive_caching_client.archive_get(self.address).await {
+            Ok(archive) => {
+                debug!("prefetching [{}] files from archive at [{}]", archive.vec().len(), hex::encode(self.address));
+                archive.vec().iter().for_each(|data_address_offset| { data_addresses.insert(data_address_offset.data_address); });
+            }
+            Err(_) => debug!("no archive found at [{}] - prefetching as data map", hex::encode(self.address)),
+        }
+        data_addresses
+    }
+
+    async fn fetch_chunks(&self, addresses: Vec<XorName>) -> Vec<DataChunk> {
+        self.chunks_total.fetch_add(addresses.len(), Ordering::Relaxed);
+        stream::iter(addresses)
+            .map(|address| async move {
+                match self.chunk_caching_client.chunk_get_internal(&address).await {
+                    Ok(chunk) => {
+                        self.chunks_done.fetch_add(1, Ordering::Relaxed);
+                        self.bytes_done.fetch_add(chunk.content.len() as u64, Ordering::Relaxed);
+                        Some(chunk)
+                    }
+                    Err(e) => {
+                        warn!("failed to prefetch chunk at [{}]: [{:?}]", hex::encode(address), e);
+                        self.chunks_failed.fetch_add(1, Ordering::Relaxed);
+                        None
+                    }
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .filter_map(|maybe_chunk| async move { maybe_chunk })
+            .collect()
+            .await
+    }
+
+    /// Resolves child data maps, which reference the chunks of a serialised data map rather than
+    /// the content, down to the root data map, fetching the chunks of each level as `ChunkStreamer`
+    /// would when decrypting.
+    async fn get_root_data_map(&self, data_map: DataMap) -> Option<DataMap> {
+        let mut level_chunks: HashMap<XorName, Bytes> = HashMap::new();
+        loop {
+            let missing_addresses = Mutex::new(Vec::new());
+            let get_chunk_parallel = |addresses: &[(usize, self_encryption::XorName)]| {
+                let mut chunks = Vec::with_capacity(addresses.len());
+                for (index, address) in addresses {
+                    match level_chunks.get(&address.0) {
+                        Some(content) => chunks.push((*index, content.clone())),
+                        None => missing_addresses.lock().unwrap().push(address.0),
+                    }
+                }
+                if missing_addresses.lock().unwrap().is_empty() {
+                    Ok(chunks)
+                } else {
+                    Err(self_encryption::Error::Generic("child data map chunks not fetched yet".to_string()))
+                }
+            };
+            let result = self_encryption::get_root_data_map_parallel(data_map.clone(), &get_chunk_parallel);
+            let missing_addresses = missing_addresses.into_inner().unwrap();
+            match result {
+                Ok(root_data_map) => return Some(root_data_map),
+                Err(e) if missing_addresses.is_empty() => {
+                    warn!("failed to resolve child data map for [{}]: [{:?}]", hex::encode(self.address), e);
+                    return None;
+                }
+                Err(_) => {
+                    let missing_count = missing_addresses.len();
+                    let chunks = self.fetch_chunks(missing_addresses).await;
+                    if chunks.len() < missing_count {
+                        return None;
+                    }
+                    chunks.into_iter().for_each(|chunk| { level_chunks.insert(chunk.address, chunk.content); });
+                }
+            }
+        }
+    }
+
+    fn reset_progress(&self) {
+        self.chunks_total.store(0, Ordering::Relaxed);
+        self.chunks_done.store(0, Ordering::Relaxed);
+        self.chunks_failed.store(0, Ordering::Relaxed);
+        self.bytes_done.store(0, Ordering::Relaxed);
+    }
+}
+
+pub const STRUCT_NAME: &str = "PrefetchCommand";
+
+#[async_trait]
+impl Command for PrefetchCommand {
+    async fn execute(&self) -> Result<(), CommandError> {
+        self.reset_progress();
+        let data_addresses = self.get_data_addresses().await;
+
+        // data maps must be retrieved before the chunks they reference can be counted
+        let data_map_chunks = self.fetch_chunks(data_addresses.into_iter().collect()).await;
+        let mut chunk_addresses = IndexSet::new();
+        for data_map_chunk in data_map_chunks {
+            match rmp_serde::from_slice::<DataMap>(&data_map_chunk.content) {
+                Ok(data_map) => match self.get_root_data_map(data_map).await {
+                    Some(root_data_map) => root_data_map.infos().iter().for_each(|info| { chunk_addresses.insert(info.dst_hash.0); }),
+                    None => debug!("failed to resolve data map at [{}] - skipping its chunks", hex::encode(data_map_chunk.address)),
+                },
+                Err(_) => debug!("chunk at [{}] is not a data map - nothing further to prefetch", hex::encode(data_map_chunk.address)),
+            }
+        }
+        self.fetch_chunks(chunk_addresses.into_iter().collect()).await;
+
+        let chunks_failed = self.chunks_failed.load(Ordering::Relaxed);
+        if chunks_failed > 0 {
+            Err(CommandError::Recoverable(format!("failed to prefetch [{}] of [{}] chunks for [{}]",
+                chunks_failed, self.chunks_total.load(Ordering::Relaxed), hex::encode(self.address))))
+        } else {
+            info!("prefetched [{}] chunks with [{}] bytes for [{}]",
+                self.chunks_done.load(Ordering::Relaxed), self.bytes_done.load(Ordering::Relaxed), hex::encode(self.address));
+            Ok(())
+        }
+    }
+
+    fn action_hash(&self) -> Vec<u8> {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(STRUCT_NAME);
+        hasher.update(hex::encode(self.address));
+        hasher.finalize().to_ascii_lowercase()
+    }
+
+    fn id(&self) -> u128 {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        STRUCT_NAME.to_string()
+    }
+
+    fn properties(&self) -> IndexMap<String, String> {
+        let mut properties = IndexMap::new();
+        properties.insert("address".to_string(), hex::encode(self.address));
+        properties.insert("chunks_done".to_string(), self.chunks_done.load(Ordering::Relaxed).to_string());
+        properties.insert("chunks_total".to_string(), self.chunks_total.load(Ordering::Relaxed).to_string());
+        properties.insert("chunks_failed".to_string(), self.chunks_failed.load(Ordering::Relaxed).to_string());
+        properties.insert("bytes_done".to_string(), self.bytes_done.load(Ordering::Relaxed).to_string());
+        properties
+    }
+
+    fn target_address(&self) -> Option<String> {
+        Some(hex::encode(self.address))
+    }
+
+    fn payload(&self) -> Option<Bytes> {
+        Some(Bytes::copy_from_slice(&self.address))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chunk_streamer::chunk_encrypter::ChunkEncrypter;
+    use crate::client::{MockArchiveCachingClient, MockChunkCachingClient};
+    use crate::error::archive_error::ArchiveError;
+    use crate::error::GetError;
+    use crate::error::chunk_error::ChunkError;
+
+    fn create_archive_client_without_archive() -> MockArchiveCachingClient {
+        let mut mock_archive_client = MockArchiveCachingClient::default();
+        mock_archive_client
+            .expect_archive_get()
+            .returning(|_| Err(ArchiveError::GetError(GetError::RecordNotFound("not an archive".to_string()))));
+        mock_archive_client
+    }
+
+    #[tokio::test]
+    async fn test_execute_prefetches_data_map_chunks() {
+        let (chunks, data_map) = ChunkEncrypter::new().encrypt(true, Bytes::from((0..4096).map(|i| (i % 251) as u8).collect::<Vec<u8>>())).await.unwrap();
+        let data_map_chunk = DataChunk::from_content(Bytes::from(rmp_serde::to_vec(&data_map).unwrap()));
+        let data_map_address = data_map_chunk.address;
+        let chunk_count = chunks.len();
+
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_get_internal()
+            .times(chunk_count + 1)
+            .returning(move |address| {
+                if *address == data_map_address {
+                    Ok(data_map_chunk.clone())
+                } else {
+                    Ok(DataChunk::new(*address, Bytes::from("chunk")))
+                }
+            });
+
+        let command = PrefetchCommand::new(mock_chunk_client, create_archive_client_without_archive(), data_map_address, 2);
+        assert!(command.execute().await.is_ok());
+
+        let properties = command.properties();
+        assert_eq!(properties.get("chunks_total").unwrap(), &(chunk_count + 1).to_string());
+        assert_eq!(properties.get("chunks_done").unwrap(), &(chunk_count + 1).to_string());
+        assert_eq!(properties.get("chunks_failed").unwrap(), "0");
+    }
+
+    #[tokio::test]
+    async fn test_execute_prefetches_child_data_map_chunks() {
+        let root_data_map = DataMap::new((0..10u8).map(|index| self_encryption::ChunkInfo {
+            index: index as usize,
+            dst_hash: self_encryption::XorName([index; 32]),
+            src_hash: self_encryption::XorName([index; 32]),
+            src_size: 1024,
+        }).collect());
+        let mut child_chunks = HashMap::new();
+        let (child_data_map, _) = self_encryption::shrink_data_map(root_data_map, |address, content| {
+            child_chunks.insert(address.0, content);
+            Ok(())
+        }).unwrap();
+        assert!(child_data_map.is_child());
+        let data_map_chunk = DataChunk::from_content(Bytes::from(rmp_serde::to_vec(&child_data_map).unwrap()));
+        let data_map_address = data_map_chunk.address;
+        let chunk_count = 1 + child_chunks.len() + 10;
+
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_get_internal()
+            .times(chunk_count)
+            .returning(move |address| {
+                if *address == data_map_address {
+                    Ok(data_map_chunk.clone())
+                } else if let Some(content) = child_chunks.get(address) {
+                    Ok(DataChunk::new(*address, content.clone()))
+                } else {
+                    assert!(address.iter().all(|byte| *byte == address[0] && *byte < 10), "unexpected chunk");
+                    Ok(DataChunk::new(*address, Bytes::from("chunk")))
+                }
+            });
+
+        let command = PrefetchCommand::new(mock_chunk_client, create_archive_client_without_archive(), data_map_address, 2);
+        assert!(command.execute().await.is_ok());
+
+        let properties = command.properties();
+        assert_eq!(properties.get("chunks_total").unwrap(), &chunk_count.to_string());
+        assert_eq!(properties.get("chunks_done").unwrap(), &chunk_count.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_execute_raw_chunk() {
+        let address = DataChunk::from_content(Bytes::from("raw")).address;
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_get_internal()
+            .times(1)
+            .returning(|address| Ok(DataChunk::new(*address, Bytes::from("raw"))));
+
+        let command = PrefetchCommand::new(mock_chunk_client, create_archive_client_without_archive(), address, 2);
+        assert!(command.execute().await.is_ok());
+        assert_eq!(command.properties().get("bytes_done").unwrap(), "3");
+    }
+
+    #[tokio::test]
+    async fn test_execute_failure_is_recoverable() {
+        let address = DataChunk::from_content(Bytes::from("missing")).address;
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_get_internal()
+            .returning(|_| Err(ChunkError::GetError(GetError::RecordNotFound("missing".to_string()))));
+
+        let command = PrefetchCommand::new(mock_chunk_client, create_archive_client_without_archive(), address, 2);
+        match command.execute().await {
+            Err(CommandError::Recoverable(_)) => (),
+            _ => panic!("Expected recoverable error"),
+        }
+        assert_eq!(command.properties().get("chunks_failed").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_restore_from_payload() {
+        let address = DataChunk::from_content(Bytes::from("raw")).address;
+        let command = PrefetchCommand::new(MockChunkCachingClient::default(), MockArchiveCachingClient::default(), address, 2);
+
+        let restored_command = PrefetchCommand::restore(
+            command.id(), MockChunkCachingClient::default(), MockArchiveCachingClient::default(), command.payload().unwrap(), 2).unwrap();
+        assert_eq!(restored_command.id(), command.id());
+        assert_eq!(restored_command.address, address);
+        assert!(PrefetchCommand::restore(1, MockChunkCachingClient::default(), MockArchiveCachingClient::default(), Bytes::from("bad"), 2).is_none());
+    }
+}
@@ -1,37 +1,78 @@
 use actix_web::web::Data;
 use async_trait::async_trait;
 use bytes::Bytes;
+use chunk_streamer::chunk_encrypter::ChunkEncrypter;
 use hex::ToHex;
 use indexmap::IndexMap;
 use log::info;
 use sha2::Digest;
-use tokio::sync::Mutex;
+use tokio::sync::mpsc;
 use crate::client::client_harness::ClientHarness;
+use crate::client::command::command_details::CommandReceipt;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
+use crate::client::command::upload_progress::UploadProgress;
+
+const PROGRESS_CHANNEL_CAPACITY: usize = 1024;
 
 pub struct CreatePublicDataCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     data: Bytes,
+    receipt: std::sync::Mutex<CommandReceipt>,
+    upload_progress: std::sync::Mutex<UploadProgress>,
 }
 
 impl CreatePublicDataCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, data: Bytes) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, data: Bytes) -> Self {
         let id = rand::random::<u128>();
-        Self { id, client_harness, data }
+        Self::restore(id, client_harness, data)
+    }
+
+    pub fn restore(id: u128, client_harness: Data<ClientHarness>, payload: Bytes) -> Self {
+        Self {
+            id,
+            client_harness,
+            data: payload,
+            receipt: std::sync::Mutex::new(CommandReceipt::default()),
+            upload_progress: std::sync::Mutex::new(UploadProgress::default()),
+        }
     }
 }
 
-const STRUCT_NAME: &'static str = "CreatePublicDataCommand";
+pub const STRUCT_NAME: &str = "CreatePublicDataCommand";
 
 #[async_trait]
 impl Command for CreatePublicDataCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
-        let result = client.data_upload(self.data.clone()).await?;
-        let data_address = result.data_map.infos().get(0).unwrap().dst_hash;
+        let network_backend = self.client_harness.get_ref().get_network_backend().await?;
+        // encrypt and upload the chunks directly, as data_upload does not return the cost
+        let (chunks, data_map) = ChunkEncrypter::new().encrypt(true, self.data.clone()).await?;
+        let bytes_uploaded = chunks.iter().map(|chunk| chunk.content.len() as u64).sum();
+        *self.upload_progress.lock().unwrap() = UploadProgress::new(chunks.len());
+
+        // count chunks as they are quoted and stored, for the executor to report while running
+        let (sender, mut receiver) = mpsc::channel(PROGRESS_CHANNEL_CAPACITY);
+        let upload = async move {
+            let contents = chunks.into_iter().map(|chunk| chunk.content).collect();
+            network_backend.chunks_upload(contents, Some(&sender)).await
+        };
+        let progress = async {
+            while let Some(upload_event) = receiver.recv().await {
+                self.upload_progress.lock().unwrap().update(&upload_event);
+            }
+        };
+        let (upload_result, _) = tokio::join!(upload, progress);
+        let chunks_upload = upload_result?;
+        self.upload_progress.lock().unwrap().complete(chunks_upload.chunks_paid);
+        let data_address = data_map.infos().first().unwrap().dst_hash;
         info!("chunk at address [{}] created successfully", data_address.encode_hex::<String>());
+        *self.receipt.lock().unwrap() = CommandReceipt {
+            result_address: Some(data_address.encode_hex::<String>()),
+            bytes_uploaded: Some(bytes_uploaded),
+            storage_cost: Some(chunks_upload.storage_cost),
+            gas_cost: Some(chunks_upload.gas_cost.to_string()),
+        };
         Ok(())
     }
 
@@ -52,7 +93,16 @@ impl Command for CreatePublicDataCommand {
 
     fn properties(&self) -> IndexMap<String, String> {
         let mut properties = IndexMap::new();
-        properties.insert("data".to_string(), "tbc".to_string()); // todo: improve
+        properties.insert("data_size".to_string(), self.data.len().to_string());
+        self.upload_progress.lock().unwrap().add_properties(&mut properties);
         properties
     }
+
+    fn receipt(&self) -> CommandReceipt {
+        self.receipt.lock().unwrap().clone()
+    }
+
+    fn payload(&self) -> Option<Bytes> {
+        Some(self.data.clone())
+    }
 }
\ No newline at end of file
@@ -6,21 +6,20 @@ use autonomi::register::{RegisterAddress, RegisterValue};
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
 
 pub struct CreateRegisterCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     owner: SecretKey,
     register_value: RegisterValue,
     payment_option: PaymentOption,
 }
 
 impl CreateRegisterCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, owner: SecretKey, register_value: RegisterValue,
+    pub fn new(client_harness: Data<ClientHarness>, owner: SecretKey, register_value: RegisterValue,
                payment_option: PaymentOption,) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, owner, register_value, payment_option }
@@ -32,7 +31,7 @@ const STRUCT_NAME: &'static str = "CreateRegisterCommand";
 #[async_trait]
 impl Command for CreateRegisterCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let register_address_hex = RegisterAddress::new(self.owner.public_key()).to_hex();
         debug!("creating register at [{}] async", register_address_hex);
         client.register_create(&self.owner, self.register_value, self.payment_option.clone()).await?;
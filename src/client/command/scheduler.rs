@@ -0,0 +1,360 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+use indexmap::IndexMap;
+use crate::client::command::Command;
+
+#[derive(Clone, Debug)]
+pub struct CommandConcurrency {
+    limit: usize,
+    name_limits: HashMap<String, usize>,
+}
+
+impl CommandConcurrency {
+    pub fn new(limit: usize, name_limits: HashMap<String, usize>) -> Self {
+        Self { limit: limit.max(1), name_limits }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn name_limit(&self, name: &String) -> usize {
+        self.name_limits.get(name).copied().unwrap_or(self.limit).clamp(1, self.limit)
+    }
+}
+
+impl Default for CommandConcurrency {
+    fn default() -> Self {
+        Self::new(1, HashMap::new())
+    }
+}
+
+pub struct ScheduledCommand {
+    pub command: Box<dyn Command>,
+    pub name: String,
+    pub keys: Vec<Vec<u8>>,
+    sequence: u64,
+    priority: i32,
+    not_before: Option<u128>,
+}
+
+impl ScheduledCommand {
+    fn is_ready(&self, now: u128) -> bool {
+        self.not_before.is_none_or(|not_before| not_before <= now)
+    }
+}
+
+/// Decides which queued commands may run next.
+///
+/// The highest priority command which is ready runs first. Commands with a `not_before` time
+/// are not ready until then. Commands are queued by `name()` and, for equal priorities, the
+/// queues are visited in turn, so a backlog of one type of command cannot starve the others.
+/// Commands sharing an action hash or target address run sequentially, in the order they were
+/// queued.
+pub struct CommandScheduler {
+    command_concurrency: CommandConcurrency,
+    queues: IndexMap<String, VecDeque<ScheduledCommand>>,
+    cursor: usize,
+    queued: usize,
+    next_sequence: u64,
+    running: usize,
+    running_by_name: HashMap<String, usize>,
+    running_keys: HashSet<Vec<u8>>,
+    waiting_keys: HashMap<Vec<u8>, VecDeque<u64>>,
+}
+
+impl CommandScheduler {
+    pub fn new(command_concurrency: CommandConcurrency) -> Self {
+        Self {
+            command_concurrency,
+            queues: IndexMap::new(),
+            cursor: 0,
+            queued: 0,
+            next_sequence: 0,
+            running: 0,
+            running_by_name: HashMap::new(),
+            running_keys: HashSet::new(),
+            waiting_keys: HashMap::new(),
+        }
+    }
+
+    pub fn push(&mut self, command: Box<dyn Command>) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        let name = command.name();
+        let keys = Self::keys(command.as_ref());
+        let command_schedule = command.metadata().schedule;
+        keys.iter().for_each(|key| self.waiting_keys.entry(key.clone()).or_default().push_back(sequence));
+        self.queues.entry(name.clone()).or_default().push_back(ScheduledCommand {
+            command, name, keys, sequence, priority: command_schedule.priority, not_before: command_schedule.not_before
+        });
+        self.queued += 1;
+    }
+
+    /// Takes the next command which may run, if any.
+    pub fn pop(&mut self) -> Option<ScheduledCommand> {
+        if self.running >= self.command_concurrency.limit() || self.queues.is_empty() {
+            return None;
+        }
+        let now = Self::now();
+        let running_keys = &self.running_keys;
+        let waiting_keys = &self.waiting_keys;
+        let is_runnable = |scheduled_command: &ScheduledCommand| scheduled_command.is_ready(now)
+            && scheduled_command.keys.iter().all(|key| {
+                !running_keys.contains(key)
+                    && waiting_keys.get(key).and_then(|sequences| sequences.front()) == Some(&scheduled_command.sequence)
+            });
+        // (queue index, position in queue, priority) of the best runnable command
+        let mut best: Option<(usize, usize, i32)> = None;
+        let queue_count = self.queues.len();
+        for offset in 0..queue_count {
+            let index = (self.cursor + offset) % queue_count;
+            let (name, queue) = self.queues.get_index(index).unwrap();
+            if self.running_by_name.get(name).copied().unwrap_or(0) >= self.command_concurrency.name_limit(name) {
+                continue;
+            }
+            for (position, scheduled_command) in queue.iter().enumerate() {
+                if is_runnable(scheduled_command) && best.is_none_or(|(_, _, priority)| scheduled_command.priority > priority) {
+                    best = Some((index, position, scheduled_command.priority));
+                }
+            }
+        }
+        let (index, position, _) = best?;
+        let scheduled_command = self.queues.get_index_mut(index).unwrap().1.remove(position).unwrap();
+        self.cursor = (index + 1) % queue_count;
+        self.start(&scheduled_command);
+        Some(scheduled_command)
+    }
+
+    /// Removes a queued command, releasing its place in the key sequences.
+    pub fn remove(&mut self, id: u128) -> Option<ScheduledCommand> {
+        let (index, position) = self.queues.values().enumerate().find_map(|(index, queue)| {
+            queue.iter().position(|scheduled_command| scheduled_command.command.id() == id).map(|position| (index, position))
+        })?;
+        let scheduled_command = self.queues.get_index_mut(index).unwrap().1.remove(position).unwrap();
+        self.queued -= 1;
+        for key in scheduled_command.keys.iter() {
+            if let Some(sequences) = self.waiting_keys.get_mut(key) {
+                sequences.retain(|sequence| *sequence != scheduled_command.sequence);
+                if sequences.is_empty() {
+                    self.waiting_keys.remove(key);
+                }
+            }
+        }
+        Some(scheduled_command)
+    }
+
+    /// Earliest time a deferred command becomes ready, if any are waiting.
+    pub fn next_ready_at(&self) -> Option<u128> {
+        let now = Self::now();
+        self.queues.values()
+            .flat_map(|queue| queue.iter())
+            .filter_map(|scheduled_command| scheduled_command.not_before.filter(|not_before| *not_before > now))
+            .min()
+    }
+
+    /// Ids of queued commands in the order they are expected to run, with their `not_before` times.
+    pub fn queue_order(&self) -> Vec<(u128, Option<u128>)> {
+        let now = Self::now();
+        let mut scheduled_commands: Vec<&ScheduledCommand> = self.queues.values().flat_map(|queue| queue.iter()).collect();
+        scheduled_commands.sort_by_key(|scheduled_command| {
+            let ready_at = if scheduled_command.is_ready(now) { 0 } else { scheduled_command.not_before.unwrap_or(0) };
+            (ready_at, -(scheduled_command.priority as i64), scheduled_command.sequence)
+        });
+        scheduled_commands.iter()
+            .map(|scheduled_command| (scheduled_command.command.id(), scheduled_command.not_before))
+            .collect()
+    }
+
+    pub fn limit(&self) -> usize {
+        self.command_concurrency.limit()
+    }
+
+    pub fn complete(&mut self, name: &str, keys: &[Vec<u8>]) {
+        self.running -= 1;
+        if let Some(running) = self.running_by_name.get_mut(name) {
+            *running -= 1;
+        }
+        keys.iter().for_each(|key| { self.running_keys.remove(key); });
+    }
+
+    pub fn queued(&self) -> usize {
+        self.queued
+    }
+
+    pub fn running(&self) -> usize {
+        self.running
+    }
+
+    fn start(&mut self, scheduled_command: &ScheduledCommand) {
+        self.queued -= 1;
+        self.running += 1;
+        *self.running_by_name.entry(scheduled_command.name.clone()).or_default() += 1;
+        for key in scheduled_command.keys.iter() {
+            if let Some(sequences) = self.waiting_keys.get_mut(key) {
+                sequences.pop_front();
+                if sequences.is_empty() {
+                    self.waiting_keys.remove(key);
+                }
+            }
+            self.running_keys.insert(key.clone());
+        }
+    }
+
+    fn now() -> u128 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()
+    }
+
+    fn keys(command: &dyn Command) -> Vec<Vec<u8>> {
+        // action hashes and hex addresses differ in length, so cannot collide
+        let mut keys = vec![command.action_hash()];
+        if let Some(target_address) = command.target_address() {
+            keys.push(target_address.into_bytes());
+        }
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use crate::client::command::command_metadata::CommandMetadata;
+    use crate::client::command::command_schedule::CommandSchedule;
+    use crate::client::command::error::CommandError;
+
+    struct NamedCommand {
+        id: u128,
+        name: String,
+        action_hash: Vec<u8>,
+        target_address: Option<String>,
+    }
+
+    #[async_trait]
+    impl Command for NamedCommand {
+        async fn execute(&self) -> Result<(), CommandError> {
+            Ok(())
+        }
+
+        fn action_hash(&self) -> Vec<u8> {
+            self.action_hash.clone()
+        }
+
+        fn id(&self) -> u128 {
+            self.id
+        }
+
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn target_address(&self) -> Option<String> {
+            self.target_address.clone()
+        }
+    }
+
+    fn create_command(id: u128, name: &str, target_address: Option<&str>) -> Box<dyn Command> {
+        Box::new(NamedCommand {
+            id,
+            name: name.to_string(),
+            action_hash: id.to_be_bytes().to_vec(),
+            target_address: target_address.map(|target_address| target_address.to_string()),
+        })
+    }
+
+    fn with_schedule(command: Box<dyn Command>, priority: i32, not_before: Option<u128>) -> Box<dyn Command> {
+        CommandMetadata { schedule: CommandSchedule::new(priority, not_before), ..Default::default() }.apply(command)
+    }
+
+    fn pop_id(command_scheduler: &mut CommandScheduler) -> Option<u128> {
+        command_scheduler.pop().map(|scheduled_command| scheduled_command.command.id())
+    }
+
+    #[test]
+    fn test_pop_respects_global_limit() {
+        let mut command_scheduler = CommandScheduler::new(CommandConcurrency::new(2, HashMap::new()));
+        (1..=3).for_each(|id| command_scheduler.push(create_command(id, "Command", None)));
+
+        assert_eq!(pop_id(&mut command_scheduler), Some(1));
+        assert_eq!(pop_id(&mut command_scheduler), Some(2));
+        assert_eq!(pop_id(&mut command_scheduler), None);
+        assert_eq!(command_scheduler.running(), 2);
+        assert_eq!(command_scheduler.queued(), 1);
+
+        command_scheduler.complete("Command", &[1u128.to_be_bytes().to_vec()]);
+        assert_eq!(pop_id(&mut command_scheduler), Some(3));
+    }
+
+    #[test]
+    fn test_pop_respects_name_limit_and_is_fair() {
+        let mut name_limits = HashMap::new();
+        name_limits.insert("Slow".to_string(), 1);
+        let mut command_scheduler = CommandScheduler::new(CommandConcurrency::new(4, name_limits));
+        command_scheduler.push(create_command(1, "Slow", None));
+        command_scheduler.push(create_command(2, "Slow", None));
+        command_scheduler.push(create_command(3, "Fast", None));
+        command_scheduler.push(create_command(4, "Fast", None));
+
+        assert_eq!(pop_id(&mut command_scheduler), Some(1));
+        assert_eq!(pop_id(&mut command_scheduler), Some(3));
+        assert_eq!(pop_id(&mut command_scheduler), Some(4));
+        assert_eq!(pop_id(&mut command_scheduler), None);
+    }
+
+    #[test]
+    fn test_pop_runs_shared_target_address_sequentially() {
+        let mut command_scheduler = CommandScheduler::new(CommandConcurrency::new(4, HashMap::new()));
+        command_scheduler.push(create_command(1, "Upload", Some("address")));
+        command_scheduler.push(create_command(2, "Prefetch", Some("address")));
+        command_scheduler.push(create_command(3, "Prefetch", Some("other")));
+
+        let first = command_scheduler.pop().unwrap();
+        assert_eq!(first.command.id(), 1);
+        assert_eq!(pop_id(&mut command_scheduler), Some(3));
+        assert_eq!(pop_id(&mut command_scheduler), None);
+
+        command_scheduler.complete(&first.name, &first.keys);
+        assert_eq!(pop_id(&mut command_scheduler), Some(2));
+    }
+
+    #[test]
+    fn test_pop_highest_priority_first() {
+        let mut command_scheduler = CommandScheduler::new(CommandConcurrency::new(1, HashMap::new()));
+        command_scheduler.push(create_command(1, "Bulk", None));
+        command_scheduler.push(with_schedule(create_command(2, "Interactive", None), 10, None));
+        command_scheduler.push(with_schedule(create_command(3, "Bulk", None), -10, None));
+        command_scheduler.push(create_command(4, "Bulk", None));
+        assert_eq!(command_scheduler.queue_order().iter().map(|(id, _)| *id).collect::<Vec<u128>>(), vec![2, 1, 4, 3]);
+
+        let mut ids = vec![];
+        while let Some(scheduled_command) = command_scheduler.pop() {
+            ids.push(scheduled_command.command.id());
+            command_scheduler.complete(&scheduled_command.name, &scheduled_command.keys);
+        }
+        assert_eq!(ids, vec![2, 1, 4, 3]);
+    }
+
+    #[test]
+    fn test_pop_defers_until_not_before() {
+        let mut command_scheduler = CommandScheduler::new(CommandConcurrency::new(4, HashMap::new()));
+        let not_before = CommandScheduler::now() + 60_000;
+        command_scheduler.push(with_schedule(create_command(1, "Command", None), 10, Some(not_before)));
+        command_scheduler.push(with_schedule(create_command(2, "Command", None), 0, Some(1)));
+
+        assert_eq!(pop_id(&mut command_scheduler), Some(2));
+        assert_eq!(pop_id(&mut command_scheduler), None);
+        assert_eq!(command_scheduler.next_ready_at(), Some(not_before));
+        assert_eq!(command_scheduler.queue_order(), vec![(1, Some(not_before))]);
+    }
+
+    #[test]
+    fn test_name_limit_is_capped_by_global_limit() {
+        let mut name_limits = HashMap::new();
+        name_limits.insert("Command".to_string(), 8);
+        let command_concurrency = CommandConcurrency::new(2, name_limits);
+
+        assert_eq!(command_concurrency.name_limit(&"Command".to_string()), 2);
+        assert_eq!(command_concurrency.name_limit(&"Other".to_string()), 2);
+        assert_eq!(CommandConcurrency::new(0, HashMap::new()).limit(), 1);
+    }
+}
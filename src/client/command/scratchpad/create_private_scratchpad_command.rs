@@ -6,14 +6,13 @@ use bytes::Bytes;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::Command;
 use crate::client::command::error::CommandError;
 
 pub struct CreatePrivateScratchpadCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     owner: SecretKey,
     content_type: u64,
     data: Bytes,
@@ -21,7 +20,7 @@ pub struct CreatePrivateScratchpadCommand {
 }
 
 impl CreatePrivateScratchpadCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, owner: SecretKey, content_type: u64,
+    pub fn new(client_harness: Data<ClientHarness>, owner: SecretKey, content_type: u64,
                data: Bytes, payment_option: PaymentOption,) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, owner, content_type, data, payment_option }
@@ -33,7 +32,7 @@ const STRUCT_NAME: &'static str = "CreatePrivateScratchpadCommand";
 #[async_trait]
 impl Command for CreatePrivateScratchpadCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let scratchpad_address_hex = autonomi::ScratchpadAddress::new(self.owner.public_key()).to_hex();
         debug!("creating private scratchpad at [{}] async", scratchpad_address_hex);
         client.scratchpad_create(&self.owner, self.content_type, &self.data, self.payment_option.clone()).await?;
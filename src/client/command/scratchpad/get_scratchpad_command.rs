@@ -5,7 +5,6 @@ use foyer::HybridCache;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::cache_item::CacheItem;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
@@ -14,14 +13,14 @@ use crate::client::SCRATCHPAD_CACHE_KEY;
 
 pub struct GetScratchpadCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
     scratchpad_address: ScratchpadAddress,
     ttl: u64,
 }
 
 impl GetScratchpadCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, hybrid_cache: Data<HybridCache<String, Vec<u8>>>, scratchpad_address: ScratchpadAddress, ttl: u64) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, hybrid_cache: Data<HybridCache<String, Vec<u8>>>, scratchpad_address: ScratchpadAddress, ttl: u64) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, hybrid_cache, scratchpad_address, ttl }
     }
@@ -32,7 +31,7 @@ const STRUCT_NAME: &'static str = "GetScratchpadCommand";
 #[async_trait]
 impl Command for GetScratchpadCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let scratchpad_address_hex = self.scratchpad_address.to_hex();
         debug!("refreshing hybrid cache with scratchpad for [{}] from network", scratchpad_address_hex);
         let scratchpad = client.scratchpad_get(&self.scratchpad_address).await?;
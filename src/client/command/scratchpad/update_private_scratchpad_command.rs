@@ -5,21 +5,20 @@ use bytes::Bytes;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
 
 pub struct UpdatePrivateScratchpadCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     owner: SecretKey,
     content_type: u64,
     data: Bytes,
 }
 
 impl UpdatePrivateScratchpadCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, owner: SecretKey, content_type: u64, data: Bytes) -> Self {
+    pub fn new(client_harness: Data<ClientHarness>, owner: SecretKey, content_type: u64, data: Bytes) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, owner, content_type, data }
     }
@@ -30,7 +29,7 @@ const STRUCT_NAME: &'static str = "UpdatePrivateScratchpadCommand";
 #[async_trait]
 impl Command for UpdatePrivateScratchpadCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let scratchpad_address_hex = ScratchpadAddress::new(self.owner.public_key()).to_hex();
         debug!("updating private scratchpad at [{}] async", scratchpad_address_hex);
         client.scratchpad_update(&self.owner, self.content_type, &self.data).await?;
@@ -6,14 +6,13 @@ use bytes::Bytes;
 use indexmap::IndexMap;
 use log::{debug, info};
 use sha2::Digest;
-use tokio::sync::Mutex;
 use crate::client::client_harness::ClientHarness;
 use crate::client::command::error::CommandError;
 use crate::client::command::Command;
 
 pub struct UpdatePublicScratchpadCommand {
     id: u128,
-    client_harness: Data<Mutex<ClientHarness>>,
+    client_harness: Data<ClientHarness>,
     owner: SecretKey,
     content_type: u64,
     data: Bytes,
@@ -21,7 +20,7 @@ pub struct UpdatePublicScratchpadCommand {
 }
 
 impl UpdatePublicScratchpadCommand {
-    pub fn new(client_harness: Data<Mutex<ClientHarness>>, owner: SecretKey, content_type: u64,
+    pub fn new(client_harness: Data<ClientHarness>, owner: SecretKey, content_type: u64,
                data: Bytes, payment_option: PaymentOption) -> Self {
         let id = rand::random::<u128>();
         Self { id, client_harness, owner, content_type, data, payment_option }
@@ -33,7 +32,7 @@ const STRUCT_NAME: &'static str = "UpdatePublicScratchpadCommand";
 #[async_trait]
 impl Command for UpdatePublicScratchpadCommand {
     async fn execute(&self) -> Result<(), CommandError> {
-        let client = self.client_harness.get_ref().lock().await.get_client().await?;
+        let client = self.client_harness.get_ref().get_client().await?;
         let address = ScratchpadAddress::new(self.owner.public_key());
         debug!("updating public scratchpad at [{}] async", address.to_hex());
         let scratchpad = client.scratchpad_get(&address).await?;
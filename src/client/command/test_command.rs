@@ -0,0 +1,33 @@
+use async_trait::async_trait;
+use crate::client::command::Command;
+use crate::client::command::error::CommandError;
+
+/// Command which does nothing, for tests which only need a command with an id.
+pub struct IdCommand {
+    pub id: u128,
+}
+
+impl IdCommand {
+    pub fn boxed(id: u128) -> Box<dyn Command> {
+        Box::new(Self { id })
+    }
+}
+
+#[async_trait]
+impl Command for IdCommand {
+    async fn execute(&self) -> Result<(), CommandError> {
+        Ok(())
+    }
+
+    fn action_hash(&self) -> Vec<u8> {
+        self.id.to_be_bytes().to_vec()
+    }
+
+    fn id(&self) -> u128 {
+        self.id
+    }
+
+    fn name(&self) -> String {
+        "IdCommand".to_string()
+    }
+}
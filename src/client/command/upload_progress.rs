@@ -0,0 +1,84 @@
+use ant_core::data::UploadEvent;
+use indexmap::IndexMap;
+
+/// Counts the chunks of an upload as they are encrypted, quoted and stored.
+///
+/// Stored chunks include those which were already on the network. Once the upload completes,
+/// these are split into chunks which were paid for and chunks which were already stored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UploadProgress {
+    chunks_total: usize,
+    chunks_quoted: usize,
+    chunks_stored: usize,
+    chunks_paid: Option<usize>,
+}
+
+impl UploadProgress {
+    pub fn new(chunks_total: usize) -> Self {
+        Self { chunks_total, ..Self::default() }
+    }
+
+    pub fn update(&mut self, upload_event: &UploadEvent) {
+        // events may be dropped when the channel is full, but their counts are cumulative
+        match upload_event {
+            UploadEvent::ChunkQuoted { quoted, .. } => self.chunks_quoted = self.chunks_quoted.max(*quoted),
+            UploadEvent::ChunkStored { stored, .. } => self.chunks_stored = self.chunks_stored.max(*stored),
+            UploadEvent::WaveComplete { stored_so_far, .. } => self.chunks_stored = self.chunks_stored.max(*stored_so_far),
+            _ => {}
+        }
+    }
+
+    pub fn complete(&mut self, chunks_paid: usize) {
+        self.chunks_quoted = self.chunks_total;
+        self.chunks_stored = self.chunks_total;
+        self.chunks_paid = Some(chunks_paid.min(self.chunks_total));
+    }
+
+    pub fn percent_complete(&self) -> usize {
+        (self.chunks_stored.min(self.chunks_total) * 100).checked_div(self.chunks_total).unwrap_or(0)
+    }
+
+    pub fn add_properties(&self, properties: &mut IndexMap<String, String>) {
+        if self.chunks_total == 0 {
+            return;
+        }
+        properties.insert("chunks_total".to_string(), self.chunks_total.to_string());
+        properties.insert("chunks_quoted".to_string(), self.chunks_quoted.to_string());
+        properties.insert("chunks_stored".to_string(), self.chunks_stored.to_string());
+        if let Some(chunks_paid) = self.chunks_paid {
+            properties.insert("chunks_paid".to_string(), chunks_paid.to_string());
+            properties.insert("chunks_already_stored".to_string(), (self.chunks_total - chunks_paid).to_string());
+        }
+        properties.insert("percent_complete".to_string(), self.percent_complete().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_and_complete() {
+        let mut properties = IndexMap::new();
+        UploadProgress::default().add_properties(&mut properties);
+        assert!(properties.is_empty());
+
+        let mut upload_progress = UploadProgress::new(4);
+        upload_progress.update(&UploadEvent::ChunkQuoted { quoted: 3, total: 4 });
+        upload_progress.update(&UploadEvent::ChunkStored { stored: 2, total: 4 });
+        upload_progress.update(&UploadEvent::ChunkStored { stored: 1, total: 4 });
+        upload_progress.add_properties(&mut properties);
+        assert_eq!(properties.get("chunks_total"), Some(&"4".to_string()));
+        assert_eq!(properties.get("chunks_quoted"), Some(&"3".to_string()));
+        assert_eq!(properties.get("chunks_stored"), Some(&"2".to_string()));
+        assert_eq!(properties.get("chunks_paid"), None);
+        assert_eq!(properties.get("percent_complete"), Some(&"50".to_string()));
+
+        upload_progress.complete(3);
+        upload_progress.add_properties(&mut properties);
+        assert_eq!(properties.get("chunks_stored"), Some(&"4".to_string()));
+        assert_eq!(properties.get("chunks_paid"), Some(&"3".to_string()));
+        assert_eq!(properties.get("chunks_already_stored"), Some(&"1".to_string()));
+        assert_eq!(properties.get("percent_complete"), Some(&"100".to_string()));
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use ant_core::data::{compute_address, DataChunk, Error, UploadEvent, XorName, U256};
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::{debug, info};
+use tokio::sync::mpsc;
+use crate::client::network_backend::{ChunksUpload, NetworkBackend};
+
+/// Faults injected by the fake network, so that failure handling can be tested deterministically.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FakeNetworkFaults {
+    /// Delay before each operation completes.
+    pub latency_ms: u64,
+    /// Fail every nth chunk get, or never if zero.
+    pub fail_get_every: u64,
+    /// Fail every nth chunk put, including puts within uploads, or never if zero.
+    pub fail_put_every: u64,
+}
+
+impl FakeNetworkFaults {
+    /// Parses comma separated faults, e.g. `latency_ms=100,fail_get_every=3,fail_put_every=5`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut fake_network_faults = Self::default();
+        for fault in value.split(',').map(|fault| fault.trim()).filter(|fault| !fault.is_empty()) {
+            let (name, amount) = fault.split_once('=')
+                .ok_or(format!("expected <FAULT>=<AMOUNT>, got [{}]", fault))?;
+            let amount = amount.trim().parse::<u64>()
+                .map_err(|e| format!("invalid amount for fault [{}]: {}", name, e))?;
+            match name.trim() {
+                "latency_ms" => fake_network_faults.latency_ms = amount,
+                "fail_get_every" => fake_network_faults.fail_get_every = amount,
+                "fail_put_every" => fake_network_faults.fail_put_every = amount,
+                other => return Err(format!("unknown fault [{}]", other)),
+            }
+        }
+        Ok(fake_network_faults)
+    }
+}
+
+/// Network backend which stores chunks in memory, or in a directory to persist them across restarts.
+///
+/// Storage costs one atto per byte and is free for chunks which are already stored.
+pub struct FakeNetworkBackend {
+    directory: Option<PathBuf>,
+    chunks: Mutex<HashMap<XorName, Bytes>>,
+    faults: FakeNetworkFaults,
+    gets: AtomicU64,
+    puts: AtomicU64,
+}
+
+impl FakeNetworkBackend {
+    pub fn new(directory: Option<PathBuf>, faults: FakeNetworkFaults) -> Self {
+        match &directory {
+            Some(directory) => info!("using fake network stored in [{:?}] with faults [{:?}]", directory, faults),
+            None => info!("using fake network stored in memory with faults [{:?}]", faults),
+        }
+        Self { directory, chunks: Mutex::new(HashMap::new()), faults, gets: AtomicU64::new(0), puts: AtomicU64::new(0) }
+    }
+
+    fn is_fault(counter: &AtomicU64, every: u64) -> bool {
+        every > 0 && (counter.fetch_add(1, Ordering::Relaxed) + 1).is_multiple_of(every)
+    }
+
+    async fn delay(&self) {
+        if self.faults.latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.faults.latency_ms)).await;
+        }
+    }
+
+    fn read(&self, address: &XorName) -> Result<Option<Bytes>, Error> {
+        match &self.directory {
+            Some(directory) => match fs::read(directory.join(hex::encode(address))) {
+                Ok(content) => Ok(Some(Bytes::from(content))),
+                Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(Error::Io(e)),
+            },
+            None => Ok(self.chunks.lock().unwrap().get(address).cloned()),
+        }
+    }
+
+    fn write(&self, address: &XorName, content: Bytes) -> Result<(), Error> {
+        match &self.directory {
+            Some(directory) => {
+                fs::create_dir_all(directory)?;
+                Ok(fs::write(directory.join(hex::encode(address)), &content)?)
+            }
+            None => {
+                self.chunks.lock().unwrap().insert(*address, content);
+                Ok(())
+            }
+        }
+    }
+
+    /// Stores the chunk, returning whether it had to be paid for.
+    fn store(&self, content: Bytes) -> Result<(XorName, bool), Error> {
+        let address = compute_address(&content);
+        if Self::is_fault(&self.puts, self.faults.fail_put_every) {
+            return Err(Error::Network(format!("fake network fault storing chunk [{}]", hex::encode(address))));
+        }
+        if self.read(&address)?.is_some() {
+            return Ok((address, false));
+        }
+        self.write(&address, content)?;
+        Ok((address, true))
+    }
+}
+
+#[async_trait]
+impl NetworkBackend for FakeNetworkBackend {
+    fn name(&self) -> String {
+        "fake".to_string()
+    }
+
+    async fn chunk_get(&self, address: &XorName) -> Result<Option<DataChunk>, Error> {
+        self.delay().await;
+        if Self::is_fault(&self.gets, self.faults.fail_get_every) {
+            return Err(Error::Network(format!("fake network fault retrieving chunk [{}]", hex::encode(address))));
+        }
+        Ok(self.read(address)?.map(|content| DataChunk::new(*address, content)))
+    }
+
+    async fn chunk_put(&self, content: Bytes) -> Result<XorName, Error> {
+        self.delay().await;
+        let (address, _) = self.store(content)?;
+        debug!("stored chunk [{}] on fake network", hex::encode(address));
+        Ok(address)
+    }
+
+    async fn chunks_upload(&self, chunks: Vec<Bytes>, progress: Option<&mpsc::Sender<UploadEvent>>) -> Result<ChunksUpload, Error> {
+        self.delay().await;
+        let total = chunks.len();
+        let mut addresses = HashSet::new();
+        let mut storage_cost = U256::ZERO;
+        let mut chunks_paid = 0;
+        for (index, content) in chunks.into_iter().enumerate() {
+            let size = content.len() as u64;
+            let address = compute_address(&content);
+            if let Some(sender) = progress {
+                let _ = sender.try_send(UploadEvent::ChunkQuoted { quoted: index + 1, total });
+            }
+            // duplicate chunks within the upload are only paid for once
+            if addresses.insert(address) && self.store(content)?.1 {
+                storage_cost += U256::from(size);
+                chunks_paid += 1;
+            }
+            if let Some(sender) = progress {
+                let _ = sender.try_send(UploadEvent::ChunkStored { stored: index + 1, total });
+            }
+        }
+        Ok(ChunksUpload { storage_cost: storage_cost.to_string(), gas_cost: 0, chunks_paid })
+    }
+
+    async fn chunk_quote(&self, address: &XorName, size: u64) -> Result<U256, Error> {
+        self.delay().await;
+        match self.read(address)? {
+            Some(_) => Ok(U256::ZERO),
+            None => Ok(U256::from(size)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_put_get_and_quote() {
+        let fake_network_backend = FakeNetworkBackend::new(None, FakeNetworkFaults::default());
+        let content = Bytes::from("hello fake network");
+        let address = compute_address(&content);
+
+        assert!(fake_network_backend.chunk_get(&address).await.unwrap().is_none());
+        assert_eq!(fake_network_backend.chunk_quote(&address, content.len() as u64).await.unwrap(), U256::from(content.len()));
+
+        assert_eq!(fake_network_backend.chunk_put(content.clone()).await.unwrap(), address);
+        assert_eq!(fake_network_backend.chunk_get(&address).await.unwrap().unwrap().content, content);
+        assert_eq!(fake_network_backend.chunk_quote(&address, content.len() as u64).await.unwrap(), U256::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_persist_in_directory() {
+        let directory = tempfile::tempdir().unwrap();
+        let content = Bytes::from("persisted chunk");
+        let address = FakeNetworkBackend::new(Some(directory.path().to_path_buf()), FakeNetworkFaults::default())
+            .chunk_put(content.clone()).await.unwrap();
+
+        let fake_network_backend = FakeNetworkBackend::new(Some(directory.path().to_path_buf()), FakeNetworkFaults::default());
+        assert_eq!(fake_network_backend.chunk_get(&address).await.unwrap().unwrap().content, content);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_upload_pays_for_new_chunks_only() {
+        let fake_network_backend = FakeNetworkBackend::new(None, FakeNetworkFaults::default());
+        fake_network_backend.chunk_put(Bytes::from("old")).await.unwrap();
+
+        let (sender, mut receiver) = mpsc::channel(10);
+        let chunks_upload = fake_network_backend.chunks_upload(
+            vec![Bytes::from("old"), Bytes::from("new"), Bytes::from("new")], Some(&sender)).await.unwrap();
+
+        assert_eq!(chunks_upload, ChunksUpload { storage_cost: "3".to_string(), gas_cost: 0, chunks_paid: 1 });
+        drop(sender);
+        let mut last_stored = 0;
+        while let Some(upload_event) = receiver.recv().await {
+            if let UploadEvent::ChunkStored { stored, .. } = upload_event {
+                last_stored = stored;
+            }
+        }
+        assert_eq!(last_stored, 3);
+    }
+
+    #[tokio::test]
+    async fn test_faults_are_deterministic() {
+        let fake_network_backend = FakeNetworkBackend::new(None, FakeNetworkFaults::parse("fail_get_every=2,fail_put_every=3").unwrap());
+        let address = fake_network_backend.chunk_put(Bytes::from("a")).await.unwrap();
+        fake_network_backend.chunk_put(Bytes::from("b")).await.unwrap();
+        assert!(matches!(fake_network_backend.chunk_put(Bytes::from("c")).await, Err(Error::Network(_))));
+
+        assert!(fake_network_backend.chunk_get(&address).await.unwrap().is_some());
+        assert!(matches!(fake_network_backend.chunk_get(&address).await, Err(Error::Network(_))));
+        assert!(fake_network_backend.chunk_get(&address).await.unwrap().is_some());
+    }
+
+    #[test]
+    fn test_parse_faults() {
+        assert_eq!(FakeNetworkFaults::parse("").unwrap(), FakeNetworkFaults::default());
+        assert_eq!(
+            FakeNetworkFaults::parse("latency_ms=100, fail_get_every=3").unwrap(),
+            FakeNetworkFaults { latency_ms: 100, fail_get_every: 3, fail_put_every: 0 }
+        );
+        assert!(FakeNetworkFaults::parse("fail_get_every").is_err());
+        assert!(FakeNetworkFaults::parse("fail_get_every=often").is_err());
+        assert!(FakeNetworkFaults::parse("explode=1").is_err());
+    }
+}
@@ -85,7 +85,7 @@ impl GraphEntryCachingClient {
         let local_address = address.clone();
         let local_ant_tp_config = self.caching_client.get_ant_tp_config().clone();
         let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&format!("{}{}", GRAPH_ENTRY_CACHE_KEY, local_address.to_hex()), {
-            let client = self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?;
+            let client = self.caching_client.get_client_harness().get_ref().get_client().await?;
             || async move {
                 match client.graph_entry_get(&local_address).await {
                     Ok(scratchpad) => {
@@ -1,5 +1,7 @@
 #![cfg_attr(test, warn(unused_imports, unused_variables, dead_code))]
 const ARCHIVE_CACHE_KEY: &'static str = "ar";
+const ARCHIVE_NEGATIVE_CACHE_KEY: &str = "nar";
+const CHUNK_NEGATIVE_CACHE_KEY: &str = "nch";
 const GRAPH_ENTRY_CACHE_KEY: &'static str = "gg";
 const POINTER_CACHE_KEY: &'static str = "pg";
 const POINTER_CHECK_CACHE_KEY: &'static str = "pce";
@@ -10,7 +12,16 @@ const TARCHIVE_CACHE_KEY: &'static str = "tar";
 
 pub mod caching_client;
 pub mod cache_item;
+pub mod negative_cache;
+pub mod cache_integrity;
+pub mod chunk_fetch_policy;
+pub mod chunk_source;
+pub mod upstream_chunk_source;
 pub mod client_harness;
+pub mod network_backend;
+pub mod fake_network_backend;
+pub mod wallet_backend;
+pub mod wallet_ledger;
 pub mod chunk_caching_client;
 //pub mod scratchpad_caching_client;
 //pub mod graph_entry_caching_client;
@@ -0,0 +1,182 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use actix_web::web::Data;
+use foyer::HybridCache;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::client::cache_item::CacheItem;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default, PartialEq)]
+pub struct NegativeCacheStats {
+    #[schema(read_only)]
+    pub hits: u64,
+    #[schema(read_only)]
+    pub inserts: u64,
+    #[schema(read_only)]
+    pub expired: u64,
+    #[schema(read_only)]
+    pub bypasses: u64,
+}
+
+#[derive(Debug, Default)]
+struct NegativeCacheCounters {
+    hits: AtomicU64,
+    inserts: AtomicU64,
+    expired: AtomicU64,
+    bypasses: AtomicU64,
+}
+
+/// Remembers addresses which could not be retrieved (or were not the expected type), so that
+/// repeated requests fail fast instead of paying the network timeout again.
+///
+/// Entries are stored as `CacheItem<String>` (with the reason) in the memory tier of the hybrid
+/// cache only, as they are short-lived. A TTL of 0 disables negative caching. The TTL may be
+/// changed while running, with `set_ttl`, and applies to entries inserted after the change.
+#[derive(Clone)]
+pub struct NegativeCache {
+    hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
+    ttl: Data<AtomicU64>,
+    counters: Data<NegativeCacheCounters>,
+}
+
+impl NegativeCache {
+    pub fn new(hybrid_cache: Data<HybridCache<String, Vec<u8>>>, ttl: u64) -> Self {
+        Self { hybrid_cache, ttl: Data::new(AtomicU64::new(ttl)), counters: Data::new(NegativeCacheCounters::default()) }
+    }
+
+    pub fn set_ttl(&self, ttl: u64) {
+        self.ttl.store(ttl, Ordering::Relaxed);
+    }
+
+    pub fn get(&self, key: &String) -> Option<String> {
+        if self.ttl.load(Ordering::Relaxed) == 0 {
+            return None;
+        }
+        let cache_entry = self.hybrid_cache.memory().get(key)?;
+        match rmp_serde::from_slice::<CacheItem<String>>(cache_entry.value()) {
+            Ok(cache_item) if cache_item.has_expired() => {
+                debug!("negative cache entry for [{}] has expired", key);
+                self.hybrid_cache.remove(key);
+                self.counters.expired.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            Ok(cache_item) => {
+                debug!("negative cache hit for [{}]", key);
+                self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                Some(cache_item.item.unwrap_or_default())
+            }
+            Err(e) => {
+                warn!("Failed to deserialize negative cache entry for [{}]: {:?}. Evicting...", key, e);
+                self.hybrid_cache.remove(key);
+                None
+            }
+        }
+    }
+
+    pub fn insert(&self, key: String, reason: String) {
+        let ttl = self.ttl.load(Ordering::Relaxed);
+        if ttl == 0 {
+            return;
+        }
+        match rmp_serde::to_vec(&CacheItem::new(Some(reason), ttl)) {
+            Ok(serialised_cache_item) => {
+                debug!("storing negative cache entry for [{}] with TTL [{}]", key, ttl);
+                self.hybrid_cache.memory().insert(key, serialised_cache_item);
+                self.counters.inserts.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => warn!("Failed to serialize negative cache entry for [{}]: {:?}", key, e),
+        }
+    }
+
+    pub fn bypass(&self, key: &String) {
+        if self.hybrid_cache.memory().contains(key) {
+            debug!("bypassing negative cache entry for [{}]", key);
+            self.hybrid_cache.remove(key);
+            self.counters.bypasses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn stats(&self) -> NegativeCacheStats {
+        NegativeCacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            inserts: self.counters.inserts.load(Ordering::Relaxed),
+            expired: self.counters.expired.load(Ordering::Relaxed),
+            bypasses: self.counters.bypasses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foyer::HybridCacheBuilder;
+
+    async fn create_negative_cache(ttl: u64) -> NegativeCache {
+        let hybrid_cache = HybridCacheBuilder::new().memory(1024).storage().build().await.unwrap();
+        NegativeCache::new(Data::new(hybrid_cache), ttl)
+    }
+
+    #[tokio::test]
+    async fn test_insert_and_get() {
+        let negative_cache = create_negative_cache(60).await;
+        let key = "ncabc".to_string();
+
+        assert_eq!(negative_cache.get(&key), None);
+        negative_cache.insert(key.clone(), "not found".to_string());
+        assert_eq!(negative_cache.get(&key), Some("not found".to_string()));
+
+        let stats = negative_cache.stats();
+        assert_eq!(stats.inserts, 1);
+        assert_eq!(stats.hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_zero_ttl_disables() {
+        let negative_cache = create_negative_cache(0).await;
+        let key = "ncabc".to_string();
+
+        negative_cache.insert(key.clone(), "not found".to_string());
+        assert_eq!(negative_cache.get(&key), None);
+        assert_eq!(negative_cache.stats(), NegativeCacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn test_set_ttl() {
+        let negative_cache = create_negative_cache(0).await;
+        let key = "ncabc".to_string();
+
+        negative_cache.set_ttl(60);
+        negative_cache.insert(key.clone(), "not found".to_string());
+        assert_eq!(negative_cache.get(&key), Some("not found".to_string()));
+
+        negative_cache.clone().set_ttl(0);
+        assert_eq!(negative_cache.get(&key), None);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_evicted() {
+        let negative_cache = create_negative_cache(60).await;
+        let key = "ncabc".to_string();
+        let mut cache_item = CacheItem::new(Some("not found".to_string()), 1);
+        cache_item.timestamp -= 10;
+        negative_cache.hybrid_cache.memory().insert(key.clone(), rmp_serde::to_vec(&cache_item).unwrap());
+
+        assert_eq!(negative_cache.get(&key), None);
+        assert!(!negative_cache.hybrid_cache.memory().contains(&key));
+        assert_eq!(negative_cache.stats().expired, 1);
+    }
+
+    #[tokio::test]
+    async fn test_bypass_removes_entry() {
+        let negative_cache = create_negative_cache(60).await;
+        let key = "ncabc".to_string();
+
+        negative_cache.bypass(&key);
+        assert_eq!(negative_cache.stats().bypasses, 0);
+
+        negative_cache.insert(key.clone(), "not found".to_string());
+        negative_cache.bypass(&key);
+        assert_eq!(negative_cache.get(&key), None);
+        assert_eq!(negative_cache.stats().bypasses, 1);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+use actix_web::web::Data;
+use ant_core::data::{compute_address, Client, DataChunk, Error, UploadEvent, XorName, U256};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tokio::sync::mpsc;
+
+// data type of chunks when requesting store quotes, as defined by ant_protocol
+const DATA_TYPE_CHUNK: u32 = 0;
+// payments are 3x the median quote, as for ant_core single node payments
+const QUOTE_MEDIAN_MULTIPLIER: u64 = 3;
+
+/// Outcome of uploading a batch of chunks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChunksUpload {
+    pub storage_cost: String,
+    pub gas_cost: u128,
+    /// Chunks which were paid for, excluding those already stored.
+    pub chunks_paid: usize,
+}
+
+/// Quoted cost of uploading a batch of chunks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChunksQuote {
+    /// Distinct chunks, including those already stored.
+    pub chunks: usize,
+    pub bytes: u64,
+    /// Chunks which are already stored, so are free.
+    pub chunks_stored: usize,
+    pub bytes_stored: u64,
+    pub cost: U256,
+}
+
+/// Operations performed against the Autonomi network.
+///
+/// Implemented by the Autonomi client and by a fake network, which allows AntTP to run offline.
+#[async_trait]
+pub trait NetworkBackend: Send + Sync {
+    fn name(&self) -> String;
+
+    /// Retrieves a chunk, or `None` if it is not stored.
+    async fn chunk_get(&self, address: &XorName) -> Result<Option<DataChunk>, Error>;
+
+    /// Pays for and stores a chunk, returning its address.
+    async fn chunk_put(&self, content: Bytes) -> Result<XorName, Error>;
+
+    /// Pays for and stores chunks which are not already stored, sending progress events.
+    async fn chunks_upload(&self, chunks: Vec<Bytes>, progress: Option<&mpsc::Sender<UploadEvent>>) -> Result<ChunksUpload, Error>;
+
+    /// Quotes the storage cost of a chunk, which is zero if it is already stored.
+    async fn chunk_quote(&self, address: &XorName, size: u64) -> Result<U256, Error>;
+}
+
+/// Network backend using the Autonomi client.
+pub struct ClientNetworkBackend {
+    client: Data<Client>,
+}
+
+impl ClientNetworkBackend {
+    pub fn new(client: Data<Client>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl NetworkBackend for ClientNetworkBackend {
+    fn name(&self) -> String {
+        "autonomi".to_string()
+    }
+
+    async fn chunk_get(&self, address: &XorName) -> Result<Option<DataChunk>, Error> {
+        self.client.chunk_get(address).await
+    }
+
+    async fn chunk_put(&self, content: Bytes) -> Result<XorName, Error> {
+        self.client.chunk_put(content).await
+    }
+
+    async fn chunks_upload(&self, chunks: Vec<Bytes>, progress: Option<&mpsc::Sender<UploadEvent>>) -> Result<ChunksUpload, Error> {
+        let chunks_total = chunks.len();
+        let (_, storage_cost, gas_cost, wave_stats) = self.client.batch_upload_chunks_with_events(chunks, progress, 0, chunks_total).await?;
+        Ok(ChunksUpload { storage_cost, gas_cost, chunks_paid: wave_stats.store_durations_ms.len() })
+    }
+
+    async fn chunk_quote(&self, address: &XorName, size: u64) -> Result<U256, Error> {
+        match self.client.get_store_quotes(address, size, DATA_TYPE_CHUNK).await {
+            Ok(quotes) => {
+                let mut prices: Vec<U256> = quotes.into_iter().map(|(_, _, _, price)| price).collect();
+                prices.sort();
+                let median_price = prices.get(prices.len() / 2).copied().unwrap_or(U256::ZERO);
+                Ok(median_price * U256::from(QUOTE_MEDIAN_MULTIPLIER))
+            }
+            Err(Error::AlreadyStored) => Ok(U256::ZERO),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Quotes storing the chunks, skipping duplicates. Chunks quoted at zero are already stored.
+pub async fn quote_chunks(network_backend: &dyn NetworkBackend, contents: Vec<Bytes>, concurrency: usize) -> Result<ChunksQuote, Error> {
+    let mut addresses = HashSet::new();
+    let chunks: Vec<(XorName, u64)> = contents.iter()
+        .map(|content| (compute_address(content), content.len() as u64))
+        .filter(|(address, _)| addresses.insert(*address))
+        .collect();
+    stream::iter(chunks)
+        .map(|(address, size)| async move { network_backend.chunk_quote(&address, size).await.map(|cost| (size, cost)) })
+        .buffer_unordered(concurrency.max(1))
+        .try_fold(ChunksQuote::default(), |mut chunks_quote, (size, cost)| async move {
+            chunks_quote.chunks += 1;
+            chunks_quote.bytes += size;
+            if cost.is_zero() {
+                chunks_quote.chunks_stored += 1;
+                chunks_quote.bytes_stored += size;
+            }
+            chunks_quote.cost = chunks_quote.cost.saturating_add(cost);
+            Ok(chunks_quote)
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fake_network_backend::{FakeNetworkBackend, FakeNetworkFaults};
+
+    #[tokio::test]
+    async fn test_quote_chunks() {
+        let fake_network_backend = FakeNetworkBackend::new(None, FakeNetworkFaults::default());
+        fake_network_backend.chunk_put(Bytes::from("123")).await.unwrap();
+        let contents = vec![Bytes::from("12345"), Bytes::from("12345"), Bytes::from("123")];
+
+        let chunks_quote = quote_chunks(&fake_network_backend, contents, 2).await.unwrap();
+        assert_eq!(chunks_quote, ChunksQuote { chunks: 2, bytes: 8, chunks_stored: 1, bytes_stored: 3, cost: U256::from(5) });
+    }
+}
@@ -162,7 +162,7 @@ impl PointerCachingClient {
         let local_address = address.clone();
         let local_ant_tp_config = self.caching_client.get_ant_tp_config().clone();
         let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&format!("{}{}", POINTER_CACHE_KEY, local_address.to_hex()), {
-            let client = self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?;
+            let client = self.caching_client.get_client_harness().get_ref().get_client().await?;
             || async move {
                 match client.pointer_get(&local_address).await {
                     Ok(pointer) => {
@@ -195,7 +195,7 @@ impl PointerCachingClient {
         let local_address = address.clone();
         let local_ant_tp_config = self.caching_client.get_ant_tp_config().clone();
         let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&format!("{}{}", POINTER_CHECK_CACHE_KEY, local_address.to_hex()), {
-            let client = self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?;
+            let client = self.caching_client.get_client_harness().get_ref().get_client().await?;
             || async move {
                 match client.pointer_check_existence(&local_address).await {
                     Ok(_) => {
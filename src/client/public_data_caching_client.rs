@@ -9,6 +9,7 @@ use mockall_double::double;
 #[double]
 use crate::client::CachingClient;
 use crate::client::command::public_data::create_public_data_command::CreatePublicDataCommand;
+use crate::client::network_backend::ChunksQuote;
 #[double]
 use crate::client::StreamingClient;
 use crate::error::{CreateError, GetError};
@@ -31,6 +32,7 @@ mock! {
         ) -> Result<XorName, PublicDataError>;
         pub async fn data_get_public(&self, addr: &XorName) -> Result<Bytes, PublicDataError>;
         pub async fn file_content_upload_public(&self, path: PathBuf, store_type: StoreType) -> Result<XorName, PublicDataError>;
+        pub async fn data_quote_public(&self, data: Bytes) -> Result<(XorName, ChunksQuote), PublicDataError>;
     }
     impl Clone for PublicDataCachingClient {
         fn clone(&self) -> Self;
@@ -58,10 +60,30 @@ impl PublicDataCachingClient {
         Ok(data_address)
     }
 
+    /// Self-encrypts the data locally and quotes uploading its chunks, without caching or uploading them.
+    pub async fn data_quote_public(&self, data: Bytes) -> Result<(XorName, ChunksQuote), PublicDataError> {
+        let chunk_encrypter = ChunkEncrypter::new();
+        match chunk_encrypter.encrypt(true, data).await {
+            Ok((chunks, data_map_chunk)) => {
+                let data_map_addr = data_map_chunk.chunk_identifiers.first().unwrap().dst_hash.0;
+                let contents = chunks.into_iter().map(|chunk| chunk.content).collect();
+                Ok((XorName::from(data_map_addr), self.caching_client.quote_chunks(contents).await?))
+            },
+            Err(e) => Err(CreateError::Encryption(e.to_string()).into())
+        }
+    }
+
+    /// Caches the chunks of the encrypted data, returning the data address.
+    ///
+    /// Chunks bound for the network are only cached once the wallet is known to be able to pay
+    /// for them, so that refused uploads leave nothing behind.
     async fn cache_public_data(&self, data: Bytes, store_type: StoreType) -> Result<XorName, PublicDataError> {
         let chunk_encrypter = ChunkEncrypter::new();
         match chunk_encrypter.encrypt(true, data.clone()).await {
             Ok((chunks, data_map_chunk)) => {
+                if store_type == StoreType::Network {
+                    self.caching_client.ensure_funds(chunks.iter().map(|chunk| chunk.content.clone()).collect()).await?;
+                }
                 let data_map_addr = data_map_chunk.chunk_identifiers.get(0).unwrap().dst_hash.0;
                 info!("updating cache with data map chunk at address [{}]", hex::encode(data_map_addr));
 
@@ -111,7 +111,7 @@ impl RegisterCachingClient {
         let local_address = address.clone();
         let local_ant_tp_config = self.caching_client.get_ant_tp_config().clone();
         let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&format!("{}{}", REGISTER_CACHE_KEY, local_address.to_hex()), {
-            let client = self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?;
+            let client = self.caching_client.get_client_harness().get_ref().get_client().await?;
             || async move {
                 match client.register_get(&local_address).await {
                     Ok(register_value) => {
@@ -138,6 +138,6 @@ impl RegisterCachingClient {
     }
 
     pub async fn register_history(&self, addr: &RegisterAddress) -> Result<RegisterHistory, RegisterError> {
-        Ok(self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?.register_history(addr))
+        Ok(self.caching_client.get_client_harness().get_ref().get_client().await?.register_history(addr))
     }
 }*/
\ No newline at end of file
@@ -174,7 +174,7 @@ impl ScratchpadCachingClient {
         let local_address = address.clone();
         let local_ant_tp_config = self.caching_client.get_ant_tp_config().clone();
         let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&format!("{}{}", SCRATCHPAD_CACHE_KEY, local_address.to_hex()), {
-            let client = self.caching_client.get_client_harness().get_ref().lock().await.get_client().await?;
+            let client = self.caching_client.get_client_harness().get_ref().get_client().await?;
             || async move {
                 match client.scratchpad_get(&local_address).await {
                     Ok(scratchpad) => {
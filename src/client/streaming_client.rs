@@ -68,20 +68,17 @@ impl StreamingClient {
                         debug!("streaming from addr [{}], range_from: [{}], range_to: [{}], derived_range_from: [{}], derived_range_to: [{}]",
                             addr_hex, range_from, range_to, derived_range_from, derived_range_to);
                         let mut buf = BytesMut::with_capacity(usize::try_from(derived_range_to - derived_range_from).expect("Failed to convert range from u64 to usize"));
-                        let mut has_data = true;
-                        while has_data {
-                            match chunk_receiver.next().await {
-                                Some(item) => match item {
-                                    Ok(bytes) => buf.put(bytes),
-                                    Err(e) => {
-                                        error!("Error downloading stream from data address [{}] with range [{} - {}]: {}", addr_hex, derived_range_from, derived_range_to, e);
-                                        has_data = false
-                                    },
-                                },
-                                None => has_data = false
-                            };
+                        while let Some(item) = chunk_receiver.next().await {
+                            match item {
+                                Ok(bytes) => buf.put(bytes),
+                                Err(e) => {
+                                    // fail rather than return a truncated range
+                                    error!("Error downloading stream from data address [{}] with range [{} - {}]: {}", addr_hex, derived_range_from, derived_range_to, e);
+                                    return Err(ChunkError::GetStreamError(GetStreamError::BadReceiver(format!("failed to download stream for data address [{}]: {}", addr_hex, e))));
+                                }
+                            }
                         }
-                        Ok(buf.freeze())       
+                        Ok(buf.freeze())
                     }
                     None => {
                         Err(ChunkError::GetStreamError(GetStreamError::BadReceiver(format!("failed to get data map chunk for data address: {}", addr_hex))))
@@ -1,4 +1,6 @@
 use std::cmp::min;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use ant_core::data::XorName;
 use bytes::Bytes;
 use log::{debug, info};
@@ -33,9 +35,13 @@ impl TArchiveCachingClient {
         Self { caching_client, streaming_client }
     }
 
+    /// Gets the tar index of a tarchive. Fails with `GetError::Decode` if the data was retrieved
+    /// but is not a tarchive, and with `GetError::RecordNotFound` if it could not be retrieved.
     pub async fn get_archive_from_tar(&self, addr: &XorName) -> Result<Bytes, GetError> {
         let local_streaming_client = self.streaming_client.clone();
         let local_address = addr.clone();
+        let is_not_tarchive = Arc::new(AtomicBool::new(false));
+        let local_is_not_tarchive = is_not_tarchive.clone();
         let cache_entry = self.caching_client.get_hybrid_cache().get_ref().get_or_fetch(&format!("{}{}", TARCHIVE_CACHE_KEY, hex::encode(local_address)), || async move {
             let trailer_bytes = local_streaming_client.download_stream(&local_address, -20480, 0).await;
             match trailer_bytes {
@@ -50,13 +56,18 @@ impl TArchiveCachingClient {
                         },
                         None => {
                             debug!("no archive.tar.idx found in tar trailer");
+                            local_is_not_tarchive.store(true, Ordering::Relaxed);
                             Err(anyhow::anyhow!(format!("Failed to retrieve archive.tar.idx in tar trailer for [{}] from network", hex::encode(local_address))))
                         }
                     }
                 },
                 Err(e) => Err(anyhow::anyhow!(format!("Failed to download stream for [{}] from network {:?}", hex::encode(local_address), e)))
             }
-        }).await?;
+        }).await.map_err(|e| if is_not_tarchive.load(Ordering::Relaxed) {
+            GetError::Decode(e.to_string())
+        } else {
+            GetError::from(e)
+        })?;
         info!("retrieved tarchive for [{}] from hybrid cache", hex::encode(addr));
         Ok(Bytes::from(cache_entry.value().to_vec()))
     }
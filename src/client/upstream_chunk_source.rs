@@ -0,0 +1,182 @@
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use ant_core::data::{DataChunk, XorName};
+use async_trait::async_trait;
+use log::{debug, info, warn};
+use reqwest::StatusCode;
+use crate::client::chunk_source::ChunkSource;
+use crate::error::chunk_error::ChunkError;
+use crate::error::GetError;
+
+const FAILURE_THRESHOLD: u32 = 3;
+const UNHEALTHY_COOLDOWN_SECS: u64 = 30;
+
+struct Upstream {
+    base_url: String,
+    failures: AtomicU32,
+    unhealthy_until: AtomicU64,
+}
+
+impl Upstream {
+    fn new(base_url: String) -> Self {
+        Upstream { base_url: base_url.trim_end_matches('/').to_string(), failures: AtomicU32::new(0), unhealthy_until: AtomicU64::new(0) }
+    }
+
+    fn is_healthy(&self) -> bool {
+        // once the cooldown has passed, the upstream is tried again and either recovers or fails over
+        Self::now() >= self.unhealthy_until.load(Ordering::Relaxed)
+    }
+
+    fn record_success(&self) {
+        if self.failures.swap(0, Ordering::Relaxed) >= FAILURE_THRESHOLD {
+            info!("upstream [{}] has recovered", self.base_url);
+        }
+    }
+
+    fn record_failure(&self) {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= FAILURE_THRESHOLD {
+            warn!("upstream [{}] failed [{}] times - marking unhealthy for [{}] seconds", self.base_url, failures, UNHEALTHY_COOLDOWN_SECS);
+            self.unhealthy_until.store(Self::now() + UNHEALTHY_COOLDOWN_SECS, Ordering::Relaxed);
+        }
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+}
+
+/// Retrieves chunks from other AntTP instances via `GET /anttp-0/binary/chunk/{address}`.
+///
+/// Upstreams are tried in the order configured. Those which repeatedly fail are skipped until a
+/// cooldown has passed. Content is only accepted if it hashes to the requested address.
+pub struct UpstreamChunkSource {
+    upstreams: Vec<Upstream>,
+    http_client: reqwest::Client,
+}
+
+impl UpstreamChunkSource {
+    pub fn new(base_urls: Vec<String>, timeout: Duration) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+        UpstreamChunkSource { upstreams: base_urls.into_iter().map(Upstream::new).collect(), http_client }
+    }
+
+    async fn chunk_get_upstream(&self, upstream: &Upstream, address: &XorName) -> Result<Option<DataChunk>, String> {
+        let url = format!("{}/anttp-0/binary/chunk/{}", upstream.base_url, hex::encode(address));
+        let response = self.http_client.get(&url).send().await.map_err(|e| e.to_string())?;
+        match response.status() {
+            StatusCode::OK => {
+                let content = response.bytes().await.map_err(|e| e.to_string())?;
+                let chunk = DataChunk::from_content(content);
+                if chunk.address == *address {
+                    Ok(Some(chunk))
+                } else {
+                    Err(format!("content hash [{}] does not match address", hex::encode(chunk.address)))
+                }
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            status => Err(format!("unexpected status [{}]", status)),
+        }
+    }
+}
+
+#[async_trait]
+impl ChunkSource for UpstreamChunkSource {
+    fn name(&self) -> String {
+        "UpstreamChunkSource".to_string()
+    }
+
+    async fn chunk_get(&self, address: &XorName) -> Result<DataChunk, ChunkError> {
+        for upstream in self.upstreams.iter().filter(|upstream| upstream.is_healthy()) {
+            match self.chunk_get_upstream(upstream, address).await {
+                Ok(Some(chunk)) => {
+                    upstream.record_success();
+                    info!("retrieved chunk for [{}] from upstream [{}]", hex::encode(address), upstream.base_url);
+                    return Ok(chunk);
+                }
+                Ok(None) => {
+                    upstream.record_success();
+                    debug!("chunk for [{}] not found at upstream [{}]", hex::encode(address), upstream.base_url);
+                }
+                Err(e) => {
+                    upstream.record_failure();
+                    warn!("failed to retrieve chunk for [{}] from upstream [{}]: {}", hex::encode(address), upstream.base_url, e);
+                }
+            }
+        }
+        Err(ChunkError::GetError(GetError::RecordNotFound(format!("Failed to retrieve chunk for [{}] from upstreams", hex::encode(address)))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use bytes::Bytes;
+
+    const CONTENT: &str = "upstream chunk content";
+
+    // stand-in for a second AntTP instance, serving CONTENT for every chunk address
+    fn start_upstream(content: &'static str) -> String {
+        let server = HttpServer::new(move || {
+            App::new().route("/anttp-0/binary/chunk/{address}", web::get().to(move || async move {
+                HttpResponse::Ok().body(content)
+            }))
+        })
+            .workers(1)
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let base_url = format!("http://{}", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        base_url
+    }
+
+    fn content_address() -> XorName {
+        DataChunk::from_content(Bytes::from(CONTENT)).address
+    }
+
+    #[actix_web::test]
+    async fn test_chunk_get_verifies_content() {
+        let upstream_chunk_source = UpstreamChunkSource::new(vec![start_upstream(CONTENT)], Duration::from_secs(5));
+
+        let chunk = upstream_chunk_source.chunk_get(&content_address()).await.unwrap();
+        assert_eq!(chunk.content, Bytes::from(CONTENT));
+    }
+
+    #[actix_web::test]
+    async fn test_chunk_get_rejects_hash_mismatch_and_fails_over() {
+        let upstream_chunk_source = UpstreamChunkSource::new(
+            vec![start_upstream("tampered content"), start_upstream(CONTENT)], Duration::from_secs(5));
+
+        let chunk = upstream_chunk_source.chunk_get(&content_address()).await.unwrap();
+        assert_eq!(chunk.content, Bytes::from(CONTENT));
+        assert_eq!(upstream_chunk_source.upstreams[0].failures.load(Ordering::Relaxed), 1);
+    }
+
+    #[actix_web::test]
+    async fn test_unhealthy_upstream_is_skipped() {
+        let upstream_chunk_source = UpstreamChunkSource::new(
+            vec!["http://127.0.0.1:1".to_string(), start_upstream(CONTENT)], Duration::from_secs(5));
+
+        for _ in 0..FAILURE_THRESHOLD {
+            assert!(upstream_chunk_source.chunk_get(&content_address()).await.is_ok());
+        }
+        assert!(!upstream_chunk_source.upstreams[0].is_healthy());
+
+        assert!(upstream_chunk_source.chunk_get(&content_address()).await.is_ok());
+        assert_eq!(upstream_chunk_source.upstreams[0].failures.load(Ordering::Relaxed), FAILURE_THRESHOLD);
+    }
+
+    #[actix_web::test]
+    async fn test_chunk_get_all_upstreams_failed() {
+        let upstream_chunk_source = UpstreamChunkSource::new(vec!["http://127.0.0.1:1".to_string()], Duration::from_secs(5));
+
+        match upstream_chunk_source.chunk_get(&content_address()).await {
+            Err(ChunkError::GetError(GetError::RecordNotFound(_))) => (),
+            _ => panic!("Expected RecordNotFound error"),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use ant_core::data::{Error, EvmAddress, U256, Wallet};
+use async_trait::async_trait;
+use bytes::Bytes;
+use log::{debug, warn};
+use crate::client::network_backend::{quote_chunks, NetworkBackend};
+use crate::error::CreateError;
+
+/// Balances of the wallet which pays for uploads.
+///
+/// Implemented by an EVM wallet and by a fake wallet, which stands in for a local EVM.
+#[async_trait]
+pub trait WalletBackend: Send + Sync {
+    fn address(&self) -> EvmAddress;
+
+    /// Address of the payment vault, which is paid for storage.
+    fn payment_vault_address(&self) -> EvmAddress;
+
+    async fn token_balance(&self) -> Result<U256, Error>;
+
+    async fn gas_balance(&self) -> Result<U256, Error>;
+
+    /// Tokens which the payment vault is approved to spend.
+    async fn payment_allowance(&self) -> Result<U256, Error>;
+}
+
+/// Wallet backend using an EVM wallet.
+pub struct EvmWalletBackend {
+    wallet: Wallet,
+}
+
+impl EvmWalletBackend {
+    pub fn new(wallet: Wallet) -> Self {
+        Self { wallet }
+    }
+}
+
+#[async_trait]
+impl WalletBackend for EvmWalletBackend {
+    fn address(&self) -> EvmAddress {
+        self.wallet.address()
+    }
+
+    fn payment_vault_address(&self) -> EvmAddress {
+        *self.wallet.network().payment_vault_address()
+    }
+
+    async fn token_balance(&self) -> Result<U256, Error> {
+        self.wallet.balance_of_tokens().await.map_err(|e| Error::Payment(e.to_string()))
+    }
+
+    async fn gas_balance(&self) -> Result<U256, Error> {
+        self.wallet.balance_of_gas_tokens().await.map_err(|e| Error::Payment(e.to_string()))
+    }
+
+    async fn payment_allowance(&self) -> Result<U256, Error> {
+        self.wallet.token_allowance(self.payment_vault_address()).await.map_err(|e| Error::Payment(e.to_string()))
+    }
+}
+
+/// Wallet backend with fixed balances, for use with the fake network.
+pub struct FakeWalletBackend {
+    address: EvmAddress,
+    balance: U256,
+}
+
+impl FakeWalletBackend {
+    pub fn new(address: EvmAddress, balance: U256) -> Self {
+        Self { address, balance }
+    }
+}
+
+#[async_trait]
+impl WalletBackend for FakeWalletBackend {
+    fn address(&self) -> EvmAddress {
+        self.address
+    }
+
+    fn payment_vault_address(&self) -> EvmAddress {
+        EvmAddress::ZERO
+    }
+
+    async fn token_balance(&self) -> Result<U256, Error> {
+        Ok(self.balance)
+    }
+
+    async fn gas_balance(&self) -> Result<U256, Error> {
+        Ok(self.balance)
+    }
+
+    async fn payment_allowance(&self) -> Result<U256, Error> {
+        Ok(U256::MAX)
+    }
+}
+
+/// Quotes storing the chunks, skipping duplicates, and checks that the wallet can pay for them.
+///
+/// If the network or wallet cannot be queried, the check is skipped and left to the upload itself.
+pub async fn ensure_funds(network_backend: &dyn NetworkBackend, wallet_backend: &dyn WalletBackend,
+                          contents: Vec<Bytes>, concurrency: usize) -> Result<(), CreateError> {
+    let quoted_cost = match quote_chunks(network_backend, contents, concurrency).await {
+        Ok(chunks_quote) => chunks_quote.cost,
+        Err(e) => {
+            warn!("skipping funding check, as chunks could not be quoted: [{}]", e);
+            return Ok(());
+        }
+    };
+    if quoted_cost.is_zero() {
+        return Ok(());
+    }
+    let (token_balance, gas_balance) = match tokio::try_join!(wallet_backend.token_balance(), wallet_backend.gas_balance()) {
+        Ok(balances) => balances,
+        Err(e) => {
+            warn!("skipping funding check, as wallet balances could not be retrieved: [{}]", e);
+            return Ok(());
+        }
+    };
+    debug!("quoted cost [{}] for wallet [{}] with token balance [{}] and gas balance [{}]",
+        quoted_cost, wallet_backend.address(), token_balance, gas_balance);
+    if token_balance < quoted_cost {
+        Err(CreateError::InsufficientFunds(format!(
+            "wallet [{}] has a token balance of [{}], but the quoted cost is [{}]", wallet_backend.address(), token_balance, quoted_cost)))
+    } else if gas_balance.is_zero() {
+        Err(CreateError::InsufficientFunds(format!(
+            "wallet [{}] has no gas to pay the quoted cost of [{}]", wallet_backend.address(), quoted_cost)))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ant_core::data::{DataChunk, UploadEvent, XorName};
+    use tokio::sync::mpsc;
+    use crate::client::fake_network_backend::{FakeNetworkBackend, FakeNetworkFaults};
+    use crate::client::network_backend::ChunksUpload;
+
+    #[tokio::test]
+    async fn test_ensure_funds() {
+        let fake_network_backend = FakeNetworkBackend::new(None, FakeNetworkFaults::default());
+        let contents = vec![Bytes::from("12345"), Bytes::from("12345"), Bytes::from("123")];
+
+        // duplicate chunks are only quoted once, so the cost is 8
+        let fake_wallet_backend = FakeWalletBackend::new(EvmAddress::ZERO, U256::from(8));
+        assert!(ensure_funds(&fake_network_backend, &fake_wallet_backend, contents.clone(), 2).await.is_ok());
+
+        let fake_wallet_backend = FakeWalletBackend::new(EvmAddress::ZERO, U256::from(7));
+        assert!(matches!(
+            ensure_funds(&fake_network_backend, &fake_wallet_backend, contents.clone(), 2).await,
+            Err(CreateError::InsufficientFunds(_))
+        ));
+
+        // stored chunks are free
+        fake_network_backend.chunk_put(Bytes::from("12345")).await.unwrap();
+        fake_network_backend.chunk_put(Bytes::from("123")).await.unwrap();
+        let fake_wallet_backend = FakeWalletBackend::new(EvmAddress::ZERO, U256::ZERO);
+        assert!(ensure_funds(&fake_network_backend, &fake_wallet_backend, contents, 2).await.is_ok());
+    }
+
+    struct OfflineNetworkBackend;
+
+    #[async_trait]
+    impl NetworkBackend for OfflineNetworkBackend {
+        fn name(&self) -> String {
+            "offline".to_string()
+        }
+
+        async fn chunk_get(&self, _address: &XorName) -> Result<Option<DataChunk>, Error> {
+            Err(Error::Network("offline".to_string()))
+        }
+
+        async fn chunk_put(&self, _content: Bytes) -> Result<XorName, Error> {
+            Err(Error::Network("offline".to_string()))
+        }
+
+        async fn chunks_upload(&self, _chunks: Vec<Bytes>, _progress: Option<&mpsc::Sender<UploadEvent>>) -> Result<ChunksUpload, Error> {
+            Err(Error::Network("offline".to_string()))
+        }
+
+        async fn chunk_quote(&self, _address: &XorName, _size: u64) -> Result<U256, Error> {
+            Err(Error::Network("offline".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ensure_funds_skipped_when_offline() {
+        let fake_wallet_backend = FakeWalletBackend::new(EvmAddress::ZERO, U256::ZERO);
+        assert!(ensure_funds(&OfflineNetworkBackend, &fake_wallet_backend, vec![Bytes::from("new")], 1).await.is_ok());
+    }
+}
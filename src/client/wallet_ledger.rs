@@ -0,0 +1,146 @@
+use std::str::FromStr;
+use std::sync::Mutex;
+use actix_web::web::Data;
+use ant_core::data::U256;
+use indexmap::IndexMap;
+use log::warn;
+use tokio::sync::broadcast::error::RecvError;
+use crate::client::command::command_details::{CommandDetails, CommandState};
+use crate::client::command::command_events::CommandEvents;
+
+/// What a wallet has paid for uploads.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WalletTotals {
+    pub uploads: u64,
+    pub bytes_uploaded: u64,
+    pub storage_cost: U256,
+    pub gas_cost: U256,
+}
+
+/// Totals what each wallet has paid, from the receipts of commands as they complete.
+///
+/// Wallets are keyed by keystore name, with `None` for the default wallet. Totals are kept in
+/// memory, so they count from startup.
+#[derive(Clone)]
+pub struct WalletLedger {
+    wallet_totals: Data<Mutex<IndexMap<Option<String>, WalletTotals>>>,
+}
+
+impl WalletLedger {
+    pub fn new() -> Self {
+        Self { wallet_totals: Data::new(Mutex::new(IndexMap::new())) }
+    }
+
+    pub fn start(&self, command_events: &CommandEvents) {
+        let mut receiver = command_events.subscribe();
+        let wallet_ledger = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(command_details) => wallet_ledger.record(&command_details),
+                    Err(RecvError::Lagged(skipped)) => warn!("wallet ledger lagged, skipping [{}] events", skipped),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    /// Adds the receipt of a completed command to the totals of the wallet which paid for it.
+    pub fn record(&self, command_details: &CommandDetails) {
+        let receipt = command_details.receipt();
+        if *command_details.state() != CommandState::COMPLETED
+            || (receipt.bytes_uploaded.is_none() && receipt.storage_cost.is_none() && receipt.gas_cost.is_none()) {
+            return;
+        }
+        let mut wallet_totals = self.wallet_totals.lock().unwrap();
+        let totals = wallet_totals.entry(command_details.wallet().cloned()).or_default();
+        totals.uploads += 1;
+        totals.bytes_uploaded += receipt.bytes_uploaded.unwrap_or_default();
+        totals.storage_cost = totals.storage_cost.saturating_add(Self::parse_cost(receipt.storage_cost.as_ref()));
+        totals.gas_cost = totals.gas_cost.saturating_add(Self::parse_cost(receipt.gas_cost.as_ref()));
+    }
+
+    pub fn totals(&self) -> IndexMap<Option<String>, WalletTotals> {
+        self.wallet_totals.lock().unwrap().clone()
+    }
+
+    fn parse_cost(cost: Option<&String>) -> U256 {
+        match cost.map(|cost| U256::from_str(cost)) {
+            Some(Ok(cost)) => cost,
+            Some(Err(e)) => {
+                warn!("skipping unreadable cost [{:?}] in wallet ledger: [{}]", cost, e);
+                U256::ZERO
+            }
+            None => U256::ZERO,
+        }
+    }
+}
+
+impl Default for WalletLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::command::command_details::CommandReceipt;
+    use crate::client::command::command_metadata::CommandMetadata;
+    use crate::client::command::command_wallet::CommandWallet;
+    use crate::client::command::test_command::IdCommand;
+
+    fn create_command_details(id: u128, command_wallet: Option<CommandWallet>, command_state: CommandState, receipt: CommandReceipt) -> CommandDetails {
+        let command = CommandMetadata { wallet: command_wallet, ..Default::default() }.apply(IdCommand::boxed(id));
+        let mut command_details = CommandDetails::new(&command);
+        command_details.set_state(command_state);
+        command_details.set_receipt(receipt);
+        command_details
+    }
+
+    fn create_receipt(bytes_uploaded: u64, storage_cost: &str, gas_cost: &str) -> CommandReceipt {
+        CommandReceipt {
+            result_address: None,
+            bytes_uploaded: Some(bytes_uploaded),
+            storage_cost: Some(storage_cost.to_string()),
+            gas_cost: Some(gas_cost.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_record_totals_by_wallet() {
+        let wallet_ledger = WalletLedger::new();
+        let team_a = Some(CommandWallet::new("team-a".to_string()));
+        wallet_ledger.record(&create_command_details(1, team_a.clone(), CommandState::COMPLETED, create_receipt(100, "10", "1")));
+        wallet_ledger.record(&create_command_details(2, team_a.clone(), CommandState::COMPLETED, create_receipt(50, "5", "2")));
+        wallet_ledger.record(&create_command_details(3, None, CommandState::COMPLETED, create_receipt(20, "7", "3")));
+        // only completed commands with a receipt are paid for
+        wallet_ledger.record(&create_command_details(4, team_a.clone(), CommandState::FAILED, create_receipt(1000, "1000", "1000")));
+        wallet_ledger.record(&create_command_details(5, team_a, CommandState::COMPLETED, CommandReceipt::default()));
+
+        let totals = wallet_ledger.totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals.get(&Some("team-a".to_string())).unwrap(), &WalletTotals {
+            uploads: 2, bytes_uploaded: 150, storage_cost: U256::from(15), gas_cost: U256::from(3)
+        });
+        assert_eq!(totals.get(&None).unwrap(), &WalletTotals {
+            uploads: 1, bytes_uploaded: 20, storage_cost: U256::from(7), gas_cost: U256::from(3)
+        });
+    }
+
+    #[tokio::test]
+    async fn test_start_records_command_events() {
+        let command_events = CommandEvents::new(10);
+        let wallet_ledger = WalletLedger::new();
+        wallet_ledger.start(&command_events);
+
+        command_events.publish(create_command_details(1, None, CommandState::COMPLETED, create_receipt(10, "1", "1")));
+        for _ in 0..50 {
+            if !wallet_ledger.totals().is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(wallet_ledger.totals().get(&None).unwrap().uploads, 1);
+    }
+}
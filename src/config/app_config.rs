@@ -10,6 +10,10 @@ pub struct AppConfig {
 }
 
 impl AppConfig {
+    pub fn new(route_map: HashMap<String, String>) -> Self {
+        AppConfig { route_map }
+    }
+
     pub fn resolve_route(&self, search_string: &String) -> (String, bool) {
         debug!("resolving route [{}]", search_string);
         for (key, value) in self.route_map.clone() {
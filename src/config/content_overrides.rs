@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use actix_web::web::Data;
+use mime::Mime;
+use crate::config::app_config::AppConfig;
+
+/// Route and MIME type overrides from the configuration, which apply to every archive and file.
+///
+/// Clones share the overrides, so that a configuration reload changes them for all requests.
+#[derive(Clone, Default)]
+pub struct ContentOverrides {
+    app_config: Data<RwLock<AppConfig>>,
+    mime_types: Data<RwLock<HashMap<String, Mime>>>,
+}
+
+impl ContentOverrides {
+    pub fn new(route_overrides: &[(String, String)], mime_overrides: &[(String, String)]) -> Self {
+        let content_overrides = ContentOverrides::default();
+        content_overrides.update(route_overrides, mime_overrides);
+        content_overrides
+    }
+
+    /// Replaces the overrides, as when the configuration is reloaded.
+    pub fn update(&self, route_overrides: &[(String, String)], mime_overrides: &[(String, String)]) {
+        *self.app_config.write().unwrap() = AppConfig::new(route_overrides.iter().cloned().collect());
+        *self.mime_types.write().unwrap() = mime_overrides.iter()
+            .filter_map(|(extension, mime)| mime.parse::<Mime>().ok().map(|mime| (extension.clone(), mime)))
+            .collect();
+    }
+
+    /// Resolves a path within an archive, for archives without a route of their own.
+    pub fn resolve_route(&self, search_string: &String) -> (String, bool) {
+        self.app_config.read().unwrap().resolve_route(search_string)
+    }
+
+    pub fn mime_types(&self) -> HashMap<String, Mime> {
+        self.mime_types.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect()
+    }
+
+    #[test]
+    fn test_resolve_route() {
+        let content_overrides = ContentOverrides::new(&to_pairs(&[("blog/*", "index.html")]), &[]);
+        assert_eq!(content_overrides.resolve_route(&"blog/post".to_string()), ("index.html".to_string(), true));
+        assert_eq!(content_overrides.resolve_route(&"about.html".to_string()), ("about.html".to_string(), false));
+    }
+
+    #[test]
+    fn test_update_is_shared_by_clones() {
+        let content_overrides = ContentOverrides::new(&to_pairs(&[("blog/*", "index.html")]), &[]);
+        let cloned_content_overrides = content_overrides.clone();
+
+        content_overrides.update(&[], &to_pairs(&[("md", "text/markdown")]));
+        assert_eq!(cloned_content_overrides.resolve_route(&"blog/post".to_string()), ("blog/post".to_string(), false));
+        assert_eq!(cloned_content_overrides.mime_types().get("md"), Some(&"text/markdown".parse::<Mime>().unwrap()));
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use ant_core::data::{EvmNetwork, Wallet};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use clap::ValueEnum;
+use saorsa_pqc::api::sig::{ml_dsa_87, MlDsaSecretKey, MlDsaVariant};
+use saorsa_pqc::dsa_traits::{SerDes, Signer};
+use saorsa_pqc::ml_dsa_87 as ml_dsa_87_fips;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use crate::error::key_store_error::KeyStoreError;
+
+pub const WALLET_KEY_NAME: &str = "wallet";
+pub const APP_KEY_NAME: &str = "app";
+pub const RESOLVER_KEY_NAME: &str = "resolver";
+const KEY_STORE_VERSION: u32 = 1;
+const KEY_STORE_KDF: &str = "pbkdf2-hmac-sha256";
+const KEY_STORE_CIPHER: &str = "chacha20-poly1305";
+const KDF_ITERATIONS: u32 = 600_000;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyType {
+    /// EVM private key, to pay for uploads
+    Evm,
+    /// ML-DSA-87 secret key, to own and sign mutable data
+    MlDsa,
+}
+
+/// Private key held in the key store, with its public key or address.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredKey {
+    pub key_type: KeyType,
+    pub private_key: String,
+    pub public_key: String,
+}
+
+impl StoredKey {
+    fn new(key_type: KeyType, private_key: &str) -> Result<Self, KeyStoreError> {
+        let private_key = private_key.trim();
+        let public_key = match key_type {
+            KeyType::Evm => Wallet::new_from_private_key(EvmNetwork::ArbitrumOne, private_key)
+                .map_err(|e| KeyStoreError::InvalidKey(format!("invalid EVM private key: {}", e)))?
+                .address()
+                .to_string(),
+            KeyType::MlDsa => {
+                let bytes: [u8; ml_dsa_87_fips::SK_LEN] = hex::decode(private_key)
+                    .map_err(|e| KeyStoreError::InvalidKey(format!("ML-DSA secret key is not hex: {}", e)))?
+                    .try_into()
+                    .map_err(|_| KeyStoreError::InvalidKey(format!("ML-DSA secret key must be {} bytes", ml_dsa_87_fips::SK_LEN)))?;
+                let secret_key = ml_dsa_87_fips::PrivateKey::try_from_bytes(bytes)
+                    .map_err(|e| KeyStoreError::InvalidKey(format!("invalid ML-DSA secret key: {}", e)))?;
+                hex::encode(secret_key.get_public_key().into_bytes())
+            }
+        };
+        Ok(Self { key_type, private_key: private_key.to_string(), public_key })
+    }
+
+    fn generate(key_type: KeyType) -> Result<Self, KeyStoreError> {
+        match key_type {
+            KeyType::Evm => Self::new(key_type, &Wallet::random_private_key()),
+            KeyType::MlDsa => {
+                let (public_key, secret_key) = ml_dsa_87().generate_keypair()
+                    .map_err(|e| KeyStoreError::InvalidKey(format!("failed to generate ML-DSA key: {}", e)))?;
+                Ok(Self { key_type, private_key: hex::encode(secret_key.to_bytes()), public_key: hex::encode(public_key.to_bytes()) })
+            }
+        }
+    }
+
+    pub fn get_ml_dsa_secret_key(&self) -> Result<MlDsaSecretKey, KeyStoreError> {
+        match self.key_type {
+            KeyType::MlDsa => MlDsaSecretKey::from_bytes(MlDsaVariant::MlDsa87, &hex::decode(&self.private_key)
+                .map_err(|e| KeyStoreError::InvalidKey(e.to_string()))?)
+                .map_err(|e| KeyStoreError::InvalidKey(e.to_string())),
+            KeyType::Evm => Err(KeyStoreError::InvalidKey("expected an ML-DSA key, but found an EVM key".to_string())),
+        }
+    }
+}
+
+impl fmt::Debug for StoredKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoredKey")
+            .field("key_type", &self.key_type)
+            .field("private_key", &"*****")
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+/// Key store file, with the keys encrypted by a key derived from the passphrase.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeyStore {
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    cipher: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Named EVM and ML-DSA keys, kept in a file encrypted with a passphrase.
+///
+/// Keys are encrypted together with ChaCha20-Poly1305, using a key derived from the passphrase
+/// with PBKDF2-HMAC-SHA256. A new salt and nonce are used each time the file is saved, so that
+/// the file only ever reveals the number of iterations and the size of its contents.
+pub struct KeyStore {
+    path: PathBuf,
+    passphrase: String,
+    iterations: u32,
+    keys: BTreeMap<String, StoredKey>,
+}
+
+impl KeyStore {
+    /// Opens an existing key store, decrypting it with the passphrase.
+    pub fn open(path: &Path, passphrase: &str) -> Result<Self, KeyStoreError> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| KeyStoreError::File(format!("failed to read [{}]: {}", path.display(), e)))?;
+        let encrypted_key_store: EncryptedKeyStore = serde_json::from_str(&content)
+            .map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        if encrypted_key_store.version != KEY_STORE_VERSION
+            || encrypted_key_store.kdf != KEY_STORE_KDF
+            || encrypted_key_store.cipher != KEY_STORE_CIPHER {
+            return Err(KeyStoreError::Corrupt(format!("unsupported key store version [{}] with [{}] and [{}]",
+                encrypted_key_store.version, encrypted_key_store.kdf, encrypted_key_store.cipher)));
+        }
+        let salt = hex::decode(&encrypted_key_store.salt).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        let nonce = hex::decode(&encrypted_key_store.nonce).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        let ciphertext = hex::decode(&encrypted_key_store.ciphertext).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(KeyStoreError::Corrupt(format!("nonce must be {} bytes", NONCE_LENGTH)));
+        }
+
+        let cipher = Self::create_cipher(passphrase, &salt, encrypted_key_store.iterations);
+        let plaintext = cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| KeyStoreError::WrongPassphrase)?;
+        let keys = serde_json::from_slice(&plaintext).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        Ok(Self { path: path.to_path_buf(), passphrase: passphrase.to_string(), iterations: encrypted_key_store.iterations, keys })
+    }
+
+    /// Opens the key store, or starts an empty one if the file does not exist yet.
+    pub fn open_or_create(path: &Path, passphrase: &str) -> Result<Self, KeyStoreError> {
+        if passphrase.is_empty() {
+            return Err(KeyStoreError::InvalidKey("a passphrase is required for the key store".to_string()));
+        }
+        match fs::metadata(path) {
+            Ok(_) => Self::open(path, passphrase),
+            Err(e) if e.kind() == ErrorKind::NotFound =>
+                Ok(Self { path: path.to_path_buf(), passphrase: passphrase.to_string(), iterations: KDF_ITERATIONS, keys: BTreeMap::new() }),
+            Err(e) => Err(KeyStoreError::File(format!("failed to read [{}]: {}", path.display(), e))),
+        }
+    }
+
+    /// Keeps tests quick, as the iterations are only there to slow down guessing the passphrase.
+    #[cfg(test)]
+    pub(crate) fn with_iterations(mut self, iterations: u32) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Result<&StoredKey, KeyStoreError> {
+        self.keys.get(name).ok_or_else(|| KeyStoreError::KeyNotFound(name.to_string()))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.keys.keys().cloned().collect()
+    }
+
+    /// Generates a new key, which is kept once the key store is saved.
+    pub fn generate(&mut self, name: &str, key_type: KeyType) -> Result<StoredKey, KeyStoreError> {
+        self.check_new_name(name)?;
+        let stored_key = StoredKey::generate(key_type)?;
+        self.keys.insert(name.to_string(), stored_key.clone());
+        Ok(stored_key)
+    }
+
+    /// Adds an existing hex-encoded private key, which is kept once the key store is saved.
+    pub fn import(&mut self, name: &str, key_type: KeyType, private_key: &str) -> Result<StoredKey, KeyStoreError> {
+        self.check_new_name(name)?;
+        let stored_key = StoredKey::new(key_type, private_key)?;
+        self.keys.insert(name.to_string(), stored_key.clone());
+        Ok(stored_key)
+    }
+
+    /// Encrypts and writes the key store, replacing the file. On Unix, only the owner may read it.
+    pub fn save(&self) -> Result<(), KeyStoreError> {
+        let salt: [u8; SALT_LENGTH] = rand::random();
+        let nonce: [u8; NONCE_LENGTH] = rand::random();
+        let plaintext = serde_json::to_vec(&self.keys).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        let ciphertext = Self::create_cipher(&self.passphrase, &salt, self.iterations)
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+        let encrypted_key_store = EncryptedKeyStore {
+            version: KEY_STORE_VERSION,
+            kdf: KEY_STORE_KDF.to_string(),
+            iterations: self.iterations,
+            cipher: KEY_STORE_CIPHER.to_string(),
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        };
+        let content = serde_json::to_string_pretty(&encrypted_key_store).map_err(|e| KeyStoreError::Corrupt(e.to_string()))?;
+
+        // write a new file and rename it over the old, so that a failed write cannot lose keys
+        let temp_path = self.path.with_extension("tmp");
+        let mut open_options = fs::OpenOptions::new();
+        open_options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut open_options, 0o600);
+        let mut file = open_options.open(&temp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn check_new_name(&self, name: &str) -> Result<(), KeyStoreError> {
+        if name.trim().is_empty() || name.trim() != name {
+            Err(KeyStoreError::InvalidKey(format!("invalid key name [{}]", name)))
+        } else if self.keys.contains_key(name) {
+            Err(KeyStoreError::KeyExists(name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn create_cipher(passphrase: &str, salt: &[u8], iterations: u32) -> ChaCha20Poly1305 {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+        ChaCha20Poly1305::new(Key::from_slice(&key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_key_store(path: &Path, passphrase: &str) -> KeyStore {
+        KeyStore::open_or_create(path, passphrase).unwrap().with_iterations(1_000)
+    }
+
+    #[test]
+    fn test_save_and_open() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("keys.json");
+        let mut key_store = create_key_store(&path, "passphrase");
+        let wallet = key_store.generate("wallet", KeyType::Evm).unwrap();
+        let app = key_store.generate("app", KeyType::MlDsa).unwrap();
+        key_store.save().unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(&wallet.private_key));
+        assert!(!content.contains(&app.private_key));
+        assert!(!content.contains("wallet"));
+
+        let key_store = KeyStore::open(&path, "passphrase").unwrap();
+        assert_eq!(key_store.names(), vec!["app".to_string(), "wallet".to_string()]);
+        assert_eq!(key_store.get("wallet").unwrap(), &wallet);
+        assert!(wallet.public_key.starts_with("0x"));
+        assert_eq!(key_store.get("app").unwrap().get_ml_dsa_secret_key().unwrap().to_bytes(), hex::decode(&app.private_key).unwrap());
+        assert!(key_store.get("wallet").unwrap().get_ml_dsa_secret_key().is_err());
+        assert_eq!(key_store.get("resolver"), Err(KeyStoreError::KeyNotFound("resolver".to_string())));
+    }
+
+    #[test]
+    fn test_open_with_wrong_passphrase() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("keys.json");
+        let mut key_store = create_key_store(&path, "passphrase");
+        key_store.generate("wallet", KeyType::Evm).unwrap();
+        key_store.save().unwrap();
+
+        assert!(matches!(KeyStore::open(&path, "guess"), Err(KeyStoreError::WrongPassphrase)));
+        assert!(matches!(KeyStore::open_or_create(&path, ""), Err(KeyStoreError::InvalidKey(_))));
+        assert!(matches!(KeyStore::open(&directory.path().join("missing.json"), "passphrase"), Err(KeyStoreError::File(_))));
+    }
+
+    #[test]
+    fn test_import_derives_public_key() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut key_store = create_key_store(&directory.path().join("keys.json"), "passphrase");
+        let generated = key_store.generate("generated", KeyType::MlDsa).unwrap();
+
+        let imported = key_store.import("imported", KeyType::MlDsa, &format!(" {}\n", generated.private_key)).unwrap();
+        assert_eq!(imported, generated);
+
+        // the first Anvil development account
+        let evm = key_store.import("evm", KeyType::Evm, "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80").unwrap();
+        assert_eq!(evm.public_key.to_lowercase(), "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+
+        assert_eq!(key_store.import("evm", KeyType::Evm, "0x01"), Err(KeyStoreError::KeyExists("evm".to_string())));
+        assert!(matches!(key_store.import("bad", KeyType::Evm, "not a key"), Err(KeyStoreError::InvalidKey(_))));
+        assert!(matches!(key_store.import("bad", KeyType::MlDsa, "abcd"), Err(KeyStoreError::InvalidKey(_))));
+        assert!(matches!(key_store.generate(" ", KeyType::Evm), Err(KeyStoreError::InvalidKey(_))));
+    }
+
+    #[test]
+    fn test_debug_redacts_private_key() {
+        let stored_key = StoredKey::generate(KeyType::Evm).unwrap();
+        assert!(!format!("{:?}", stored_key).contains(&stored_key.private_key));
+    }
+}
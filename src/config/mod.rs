@@ -1,3 +1,6 @@
 #![cfg_attr(test, warn(unused_imports, unused_variables, dead_code))]
 pub mod app_config;
-pub mod anttp_config;
\ No newline at end of file
+pub mod content_overrides;
+pub mod anttp_config;
+pub mod reloadable_logger;
+pub mod key_store;
\ No newline at end of file
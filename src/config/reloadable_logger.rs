@@ -0,0 +1,55 @@
+use std::sync::{OnceLock, RwLock};
+use env_logger::{Builder, Env, Logger};
+use log::{Log, Metadata, Record};
+
+pub const DEFAULT_LOGGING: &str = "info,anttp=info,saorsa_transport=error,saorsa_core=error,chunk_streamer=info";
+
+static RELOADABLE_LOGGER: OnceLock<ReloadableLogger> = OnceLock::new();
+
+/// Logs with env_logger, using filters which may be changed while running.
+pub struct ReloadableLogger {
+    logger: RwLock<Logger>,
+}
+
+impl ReloadableLogger {
+    /// Installs the logger, filtering by the log level, or by `RUST_LOG` (or the default) when empty.
+    pub fn init(log_level: &str) {
+        let reloadable_logger = RELOADABLE_LOGGER.get_or_init(|| ReloadableLogger { logger: RwLock::new(Self::build(log_level)) });
+        log::set_max_level(reloadable_logger.logger.read().unwrap().filter());
+        log::set_logger(reloadable_logger).expect("failed to install logger");
+    }
+
+    /// Changes the filters of the installed logger, if any.
+    pub fn reload(log_level: &str) {
+        if let Some(reloadable_logger) = RELOADABLE_LOGGER.get() {
+            let logger = Self::build(log_level);
+            log::set_max_level(logger.filter());
+            *reloadable_logger.logger.write().unwrap() = logger;
+        }
+    }
+
+    fn build(log_level: &str) -> Logger {
+        let mut builder = if log_level.is_empty() {
+            Builder::from_env(Env::default().default_filter_or(DEFAULT_LOGGING))
+        } else {
+            let mut builder = Builder::new();
+            builder.parse_filters(log_level);
+            builder
+        };
+        builder.format_timestamp_millis().build()
+    }
+}
+
+impl Log for ReloadableLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.logger.read().unwrap().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.logger.read().unwrap().log(record)
+    }
+
+    fn flush(&self) {
+        self.logger.read().unwrap().flush()
+    }
+}
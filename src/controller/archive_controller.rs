@@ -1,9 +1,9 @@
 use actix_multipart::form::MultipartForm;
 use actix_web::{web, web::Data, HttpRequest, HttpResponse};
-use ant_core::data::Wallet;
 use crate::controller::get_store_type;
 use crate::error::archive_error::ArchiveError;
 use crate::service::archive_service::{ArchiveForm, ArchiveResponse, ArchiveService, Upload};
+use crate::model::quote::Quote;
 
 /// GET /anttp-0/archive/{address}
 #[utoipa::path(
@@ -65,7 +65,6 @@ pub async fn put_archive_root(
     path_params: web::Path<String>,
     archive_form: MultipartForm<ArchiveForm>,
     archive_service: Data<ArchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, ArchiveError> {
     let address = path_params.into_inner();
@@ -75,7 +74,6 @@ pub async fn put_archive_root(
             address,
             None,
             archive_form,
-            evm_wallet_data.get_ref().clone(),
             store_type,
         )
         .await?;
@@ -100,7 +98,6 @@ pub async fn put_archive(
     path_params: web::Path<(String, String)>,
     archive_form: MultipartForm<ArchiveForm>,
     archive_service: Data<ArchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, ArchiveError> {
     let (address, path) = path_params.into_inner();
@@ -110,13 +107,61 @@ pub async fn put_archive(
             address,
             Some(path),
             archive_form,
-            evm_wallet_data.get_ref().clone(),
             store_type,
         )
         .await?;
     Ok(HttpResponse::Ok().json(res))
 }
 
+/// PUT /anttp-0/quote/multipart/archive/{address}
+#[utoipa::path(
+    put,
+    path = "/anttp-0/quote/multipart/archive/{address}",
+    request_body(content = ArchiveForm, content_type = "multipart/form-data"),
+    responses(
+        (status = OK, description = "Archive update quoted successfully", body = Quote)
+    ),
+    params(
+        ("address" = String, Path, description = "Archive address")
+    )
+)]
+pub async fn put_archive_quote_root(
+    path_params: web::Path<String>,
+    archive_form: MultipartForm<ArchiveForm>,
+    archive_service: Data<ArchiveService>,
+) -> Result<HttpResponse, ArchiveError> {
+    let address = path_params.into_inner();
+    let res = archive_service
+        .quote_archive_update(address, None, archive_form)
+        .await?;
+    Ok(HttpResponse::Ok().json(res))
+}
+
+/// PUT /anttp-0/quote/multipart/archive/{address}/{path}
+#[utoipa::path(
+    put,
+    path = "/anttp-0/quote/multipart/archive/{address}/{path}",
+    request_body(content = ArchiveForm, content_type = "multipart/form-data"),
+    responses(
+        (status = OK, description = "Archive update quoted successfully", body = Quote)
+    ),
+    params(
+        ("address" = String, Path, description = "Archive address"),
+        ("path" = String, Path, description = "Target path within the archive")
+    )
+)]
+pub async fn put_archive_quote(
+    path_params: web::Path<(String, String)>,
+    archive_form: MultipartForm<ArchiveForm>,
+    archive_service: Data<ArchiveService>,
+) -> Result<HttpResponse, ArchiveError> {
+    let (address, path) = path_params.into_inner();
+    let res = archive_service
+        .quote_archive_update(address, Some(path), archive_form)
+        .await?;
+    Ok(HttpResponse::Ok().json(res))
+}
+
 /// DELETE /anttp-0/archive/{address}/{path}
 #[utoipa::path(
     delete,
@@ -133,7 +178,6 @@ pub async fn put_archive(
 pub async fn delete_archive(
     path_params: web::Path<(String, String)>,
     archive_service: Data<ArchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, ArchiveError> {
     let (address, path) = path_params.into_inner();
@@ -142,7 +186,6 @@ pub async fn delete_archive(
         .truncate_archive(
             address,
             path,
-            evm_wallet_data.get_ref().clone(),
             store_type,
         )
         .await?;
@@ -164,7 +207,6 @@ pub async fn delete_archive(
 pub async fn push_archive(
     path_params: web::Path<String>,
     archive_service: Data<ArchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, ArchiveError> {
     let address = path_params.into_inner();
@@ -172,7 +214,6 @@ pub async fn push_archive(
     let res = archive_service
         .push_archive(
             address,
-            evm_wallet_data.get_ref().clone(),
             store_type,
         )
         .await?;
@@ -0,0 +1,91 @@
+use actix_files::NamedFile;
+use actix_web::{web, Error, HttpRequest, HttpResponse, Responder};
+use actix_web::web::Data;
+use futures_util::StreamExt;
+use log::debug;
+use tokio::io::AsyncWriteExt;
+use crate::config::anttp_config::AntTpConfig;
+use crate::error::chunk_error::ChunkError;
+use crate::error::CreateError;
+use crate::service::bundle_service::{BundleExport, BundleImport, BundleService};
+use crate::service::cache_service::{CacheService, CacheStats};
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/cache/stats",
+    responses(
+        (status = OK, description = "Cache statistics retrieved successfully", body = CacheStats),
+    )
+)]
+pub async fn get_cache_stats(cache_service: Data<CacheService>) -> Result<HttpResponse, Error> {
+    debug!("Getting cache stats");
+    Ok(HttpResponse::Ok().json(cache_service.get_cache_stats()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/cache/export",
+    request_body(
+        content = BundleExport
+    ),
+    responses(
+        (status = OK, description = "Cache bundle exported successfully", content_type = "application/x-tar"),
+        (status = BAD_REQUEST, description = "Invalid address"),
+        (status = NOT_FOUND, description = "Chunk not found"),
+    ),
+)]
+pub async fn post_cache_export(
+    bundle_service: Data<BundleService>,
+    bundle_export: web::Json<BundleExport>,
+    request: HttpRequest,
+) -> Result<HttpResponse, ChunkError> {
+    debug!("Exporting cache bundle for [{:?}]", bundle_export.addresses);
+    let temp_file = tempfile::NamedTempFile::new().map_err(to_temporary_storage_error)?;
+    let file = temp_file.as_file().try_clone().map_err(to_temporary_storage_error)?;
+    bundle_service.export_bundle(bundle_export.into_inner().addresses, file).await?;
+    let named_file = NamedFile::from_file(temp_file.reopen().map_err(to_temporary_storage_error)?, "bundle.tar")
+        .map_err(to_temporary_storage_error)?
+        .set_content_type("application/x-tar".parse().unwrap());
+    Ok(named_file.respond_to(&request).map_into_boxed_body())
+}
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/cache/import",
+    request_body(
+        content = Vec<u8>,
+        content_type = "application/x-tar"
+    ),
+    responses(
+        (status = OK, description = "Cache bundle imported successfully", body = BundleImport),
+        (status = BAD_REQUEST, description = "Invalid bundle or bundle too large"),
+    ),
+)]
+pub async fn post_cache_import(
+    bundle_service: Data<BundleService>,
+    ant_tp_config: Data<AntTpConfig>,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, ChunkError> {
+    debug!("Importing cache bundle");
+    let max_bundle_bytes = ant_tp_config.max_bundle_size * 1024 * 1024;
+    let mut bundle_bytes = 0;
+    let temp_file = tempfile::NamedTempFile::new().map_err(to_temporary_storage_error)?;
+    let mut file = tokio::fs::File::from_std(temp_file.as_file().try_clone().map_err(to_temporary_storage_error)?);
+    while let Some(bytes) = payload.next().await {
+        let bytes = bytes.map_err(|e| ChunkError::CreateError(CreateError::InvalidData(e.to_string())))?;
+        bundle_bytes += bytes.len() as u64;
+        if bundle_bytes > max_bundle_bytes {
+            return Err(ChunkError::CreateError(CreateError::InvalidData(
+                format!("bundle exceeds the maximum size of [{}] MB", ant_tp_config.max_bundle_size))));
+        }
+        file.write_all(&bytes).await.map_err(to_temporary_storage_error)?;
+    }
+    file.flush().await.map_err(to_temporary_storage_error)?;
+    // read back from the start with a new handle, which the import reads on a blocking thread
+    let file = temp_file.reopen().map_err(to_temporary_storage_error)?;
+    Ok(HttpResponse::Ok().json(bundle_service.import_bundle(file).await?))
+}
+
+fn to_temporary_storage_error(e: std::io::Error) -> ChunkError {
+    ChunkError::CreateError(CreateError::TemporaryStorage(e.to_string()))
+}
@@ -2,12 +2,12 @@ use actix_http::header;
 use actix_web::{web, HttpRequest, HttpResponse};
 use actix_web::http::header::{ContentLength, ContentType};
 use actix_web::web::{Data, Payload};
-use ant_core::data::Wallet;
 use log::debug;
 use crate::error::chunk_error::ChunkError;
-use crate::controller::get_store_type;
+use crate::controller::{get_store_type, is_no_cache};
 use crate::error::CreateError;
 use crate::service::chunk_service::{Chunk, ChunkService};
+use crate::model::quote::Quote;
 
 #[utoipa::path(
     post,
@@ -25,7 +25,6 @@ use crate::service::chunk_service::{Chunk, ChunkService};
 )]
 pub async fn post_chunk(
     chunk_service: Data<ChunkService>,
-    evm_wallet_data: Data<Wallet>,
     chunk: web::Json<Chunk>,
     request: HttpRequest
 ) -> Result<HttpResponse, ChunkError> {
@@ -34,6 +33,26 @@ pub async fn post_chunk(
         chunk_service.create_chunk(chunk.into_inner(), get_store_type(&request)).await?))
 }
 
+#[utoipa::path(
+    post,
+    path = "/anttp-0/quote/chunk",
+    request_body(
+        content = Chunk
+    ),
+    responses(
+        (status = OK, description = "Chunk quoted successfully", body = Quote),
+        (status = BAD_GATEWAY, description = "Network is offline"),
+    ),
+)]
+pub async fn post_chunk_quote(
+    chunk_service: Data<ChunkService>,
+    chunk: web::Json<Chunk>,
+) -> Result<HttpResponse, ChunkError> {
+    debug!("Quoting new chunk");
+    Ok(HttpResponse::Ok().json(
+        chunk_service.quote_chunk(chunk.into_inner()).await?))
+}
+
 #[utoipa::path(
     post,
     path = "/anttp-0/binary/chunk",
@@ -51,7 +70,6 @@ pub async fn post_chunk(
 )]
 pub async fn post_chunk_binary(
     chunk_service: Data<ChunkService>,
-    evm_wallet_data: Data<Wallet>,
     payload: Payload,
     request: HttpRequest
 ) -> Result<HttpResponse, ChunkError> {
@@ -76,14 +94,20 @@ pub async fn post_chunk_binary(
     ),
     params(
         ("address" = String, Path, description = "Chunk address"),
+        ("Cache-Control", Header, description = "Bypass the negative cache for chunks recently not found (no-cache)",
+        example = "no-cache"),
     )
 )]
 pub async fn get_chunk(
     path: web::Path<String>,
     chunk_service: Data<ChunkService>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, ChunkError> {
     let address = path.into_inner();
     debug!("Getting chunk at [{}]", address);
+    if is_no_cache(request.headers()) {
+        chunk_service.bypass_negative_cache(&address).await;
+    }
     Ok(HttpResponse::Ok().json(chunk_service.get_chunk(address).await?))
 }
 
@@ -96,14 +120,20 @@ pub async fn get_chunk(
     ),
     params(
         ("address" = String, Path, description = "Chunk address"),
+        ("Cache-Control", Header, description = "Bypass the negative cache for chunks recently not found (no-cache)",
+        example = "no-cache"),
     )
 )]
 pub async fn get_chunk_binary(
     path: web::Path<String>,
     chunk_service: Data<ChunkService>,
+    request: HttpRequest,
 ) -> Result<HttpResponse, ChunkError> {
     let address = path.into_inner();
     debug!("Getting chunk at [{}]", address);
+    if is_no_cache(request.headers()) {
+        chunk_service.bypass_negative_cache(&address).await;
+    }
     let chunk = chunk_service.get_chunk_binary(address).await?;
     Ok(HttpResponse::Ok()
         .insert_header(ContentType::octet_stream())
@@ -1,7 +1,14 @@
-use actix_web::{Error, HttpResponse};
+use std::time::Duration;
+use actix_web::{web, Error, HttpResponse};
+use actix_web::http::header::{CacheControl, CacheDirective};
 use actix_web::web::Data;
+use async_stream::stream;
+use bytes::Bytes;
+use futures_util::StreamExt;
 use log::debug;
-use crate::service::command_service::{CommandList, CommandService};
+use tokio::time::interval;
+use crate::error::command_error::CommandError;
+use crate::service::command_service::{Batch, Command, CommandList, CommandService};
 
 #[utoipa::path(
     get,
@@ -14,3 +21,153 @@ pub async fn get_commands(command_service: Data<CommandService>) -> Result<HttpR
     debug!("Getting command list");
     Ok(HttpResponse::Ok().json(command_service.get_commands().await?))
 }
+
+const EVENTS_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/command/events",
+    responses(
+        (status = OK, description = "Server-sent events of commands as they change state or report progress", content_type = "text/event-stream", body = Command),
+    )
+)]
+pub async fn get_command_events(command_service: Data<CommandService>) -> HttpResponse {
+    debug!("Watching command events");
+    let mut commands = command_service.watch_commands();
+    let events = stream! {
+        // comment lines keep idle connections open through proxies
+        let mut keep_alive_interval = interval(EVENTS_KEEP_ALIVE_INTERVAL);
+        keep_alive_interval.tick().await;
+        loop {
+            let maybe_event = tokio::select! {
+                maybe_command = commands.next() => maybe_command.map(|command| {
+                    format!("event: command\nid: {}\ndata: {}\n\n", command.id, serde_json::to_string(&command).unwrap_or_default())
+                }),
+                _ = keep_alive_interval.tick() => Some(": keep-alive\n\n".to_string()),
+            };
+            match maybe_event {
+                Some(event) => yield Ok::<Bytes, Error>(Bytes::from(event)),
+                None => break,
+            }
+        }
+    };
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .streaming(events)
+}
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/command/{id}",
+    params(
+        ("id" = String, Path, description = "Command id"),
+    ),
+    responses(
+        (status = OK, description = "Command found successfully", body = Command),
+        (status = BAD_REQUEST, description = "Invalid command id"),
+        (status = NOT_FOUND, description = "Command not found"),
+    )
+)]
+pub async fn get_command(
+    path: web::Path<String>,
+    command_service: Data<CommandService>,
+) -> Result<HttpResponse, CommandError> {
+    let id = path.into_inner();
+    debug!("Getting command [{}]", id);
+    Ok(HttpResponse::Ok().json(command_service.get_command(id).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/anttp-0/command/{id}",
+    params(
+        ("id" = String, Path, description = "Command id"),
+    ),
+    responses(
+        (status = OK, description = "Command cancelled successfully", body = Command),
+        (status = BAD_REQUEST, description = "Command is not waiting or running"),
+        (status = NOT_FOUND, description = "Command not found"),
+    )
+)]
+pub async fn delete_command(
+    path: web::Path<String>,
+    command_service: Data<CommandService>,
+) -> Result<HttpResponse, CommandError> {
+    let id = path.into_inner();
+    debug!("Cancelling command [{}]", id);
+    Ok(HttpResponse::Ok().json(command_service.cancel_command(id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/command/{id}/retry",
+    params(
+        ("id" = String, Path, description = "Command id"),
+    ),
+    responses(
+        (status = OK, description = "Command queued for retry successfully", body = Command),
+        (status = BAD_REQUEST, description = "Command has not failed"),
+        (status = NOT_FOUND, description = "Command not found"),
+    )
+)]
+pub async fn post_command_retry(
+    path: web::Path<String>,
+    command_service: Data<CommandService>,
+) -> Result<HttpResponse, CommandError> {
+    let id = path.into_inner();
+    debug!("Retrying command [{}]", id);
+    Ok(HttpResponse::Ok().json(command_service.retry_command(id).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/anttp-0/command",
+    responses(
+        (status = OK, response = CommandList),
+    )
+)]
+pub async fn delete_commands(command_service: Data<CommandService>) -> Result<HttpResponse, CommandError> {
+    debug!("Purging terminated commands");
+    Ok(HttpResponse::Ok().json(command_service.purge_commands().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/command/batch/{id}",
+    params(
+        ("id" = String, Path, description = "Command batch id"),
+    ),
+    responses(
+        (status = OK, description = "Command batch found successfully", body = Batch),
+        (status = NOT_FOUND, description = "Command batch not found"),
+    )
+)]
+pub async fn get_command_batch(
+    path: web::Path<String>,
+    command_service: Data<CommandService>,
+) -> Result<HttpResponse, CommandError> {
+    let id = path.into_inner();
+    debug!("Getting command batch [{}]", id);
+    Ok(HttpResponse::Ok().json(command_service.get_batch(id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/command/batch/{id}/retry",
+    params(
+        ("id" = String, Path, description = "Command batch id"),
+    ),
+    responses(
+        (status = OK, description = "Failed commands in batch queued for retry successfully", body = Batch),
+        (status = NOT_FOUND, description = "Command batch not found"),
+    )
+)]
+pub async fn post_command_batch_retry(
+    path: web::Path<String>,
+    command_service: Data<CommandService>,
+) -> Result<HttpResponse, CommandError> {
+    let id = path.into_inner();
+    debug!("Retrying failed commands in batch [{}]", id);
+    Ok(HttpResponse::Ok().json(command_service.retry_batch(id).await?))
+}
@@ -0,0 +1,21 @@
+use actix_web::HttpResponse;
+use actix_web::http::header;
+use actix_web::web::Data;
+use log::debug;
+use crate::error::UpdateError;
+use crate::service::config_service::{ConfigReload, ConfigService};
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/config/reload",
+    responses(
+        (status = OK, description = "Configuration reloaded successfully", body = ConfigReload),
+        (status = BAD_REQUEST, description = "Configuration file or environment variables are invalid"),
+    )
+)]
+pub async fn post_config_reload(config_service: Data<ConfigService>) -> Result<HttpResponse, UpdateError> {
+    debug!("Reloading configuration");
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(config_service.reload().await?))
+}
@@ -2,7 +2,10 @@ use actix_http::header::HeaderMap;
 use actix_web::{web, HttpRequest, HttpResponse, HttpResponseBuilder};
 use actix_web::dev::ConnectionInfo;
 use actix_web::web::Data;
+use bytes::Bytes;
+use futures_util::{stream, Stream, StreamExt};
 use log::debug;
+use std::fmt::Display;
 use mime::{Mime, APPLICATION_JSON, TEXT_HTML};
 use mockall_double::double;
 #[double]
@@ -10,6 +13,7 @@ use crate::client::ArchiveCachingClient;
 #[double]
 use crate::client::TArchiveCachingClient;
 use crate::config::anttp_config::AntTpConfig;
+use crate::config::content_overrides::ContentOverrides;
 /*use crate::service::public_archive_service::PublicArchiveService;*/
 #[double]
 use crate::client::ChunkCachingClient;
@@ -21,7 +25,7 @@ use crate::client::PublicDataCachingClient;
 use crate::client::CachingClient;
 #[double]
 use crate::client::StreamingClient;
-use crate::error::GetError;
+use crate::error::{GetError, GetStreamError};
 use crate::error::chunk_error::ChunkError;
 use crate::service::archive_helper::{ArchiveAction, ArchiveHelper, ArchiveInfo};
 use crate::service::archive_service::ArchiveService;
@@ -46,9 +50,10 @@ pub async fn get_public_data(
     conn: ConnectionInfo,
     ant_tp_config_data: Data<AntTpConfig>,
     crypto_service_data: Data<CryptoService>,
+    content_overrides_data: Data<ContentOverrides>,
 ) -> Result<HttpResponse, ChunkError> {
     fetch_public_data(request, path, resolver_service, caching_client_data, streaming_client_data,
-                      conn, ant_tp_config_data, crypto_service_data, true).await
+                      conn, ant_tp_config_data, crypto_service_data, content_overrides_data, true).await
 }
 
 pub async fn head_public_data(
@@ -60,9 +65,10 @@ pub async fn head_public_data(
     conn: ConnectionInfo,
     ant_tp_config_data: Data<AntTpConfig>,
     crypto_service_data: Data<CryptoService>,
+    content_overrides_data: Data<ContentOverrides>,
 ) -> Result<HttpResponse, ChunkError> {
     fetch_public_data(request, path, resolver_service, caching_client_data, streaming_client_data,
-                      conn, ant_tp_config_data, crypto_service_data, false).await
+                      conn, ant_tp_config_data, crypto_service_data, content_overrides_data, false).await
 }
 
 async fn fetch_public_data(
@@ -74,9 +80,11 @@ async fn fetch_public_data(
     conn: ConnectionInfo,
     ant_tp_config_data: Data<AntTpConfig>,
     crypto_service_data: Data<CryptoService>,
+    content_overrides_data: Data<ContentOverrides>,
     has_body: bool,
 ) -> Result<HttpResponse, ChunkError> {
     let ant_tp_config = ant_tp_config_data.get_ref().clone();
+    let content_overrides = content_overrides_data.get_ref().clone();
     let caching_client = caching_client_data.get_ref().clone();
     let streaming_client = streaming_client_data.get_ref().clone();
     let crypto_service = crypto_service_data.get_ref().clone();
@@ -84,7 +92,7 @@ async fn fetch_public_data(
 
     match resolver_service_data.resolve(&conn.host(), &path.into_inner(), &request.headers()).await {
         Some(resolved_address) => {
-            let header_builder = HeaderBuilder::new(resolved_address.ttl);
+            let header_builder = HeaderBuilder::new(resolved_address.ttl, content_overrides.mime_types());
             if !resolved_address.is_allowed {
                 Err(GetError::AccessNotAllowed(format!("Access forbidden: {}", hex::encode(resolved_address.xor_name))).into())
             } else if !resolved_address.is_modified {
@@ -99,7 +107,7 @@ async fn fetch_public_data(
                 let tarchive_caching_client = TArchiveCachingClient::new(caching_client.clone(), streaming_client.clone());
                 let tarchive_service = TarchiveService::new(public_data_service, tarchive_caching_client, file_service.clone(), resolver_service.clone(), ant_tp_config);
                 let archive_caching_client = ArchiveCachingClient::new(caching_client, streaming_client);
-                let archive_service = ArchiveService::new(tarchive_service, resolver_service.clone(), archive_caching_client, file_service.clone());
+                let archive_service = ArchiveService::new(tarchive_service, resolver_service.clone(), archive_caching_client, file_service.clone(), content_overrides);
 
                 let archive_info = archive_service.get_archive_info(&resolved_address, &request).await;
 
@@ -240,6 +248,7 @@ fn update_full_content_response(builder: &mut HttpResponseBuilder, resolved_addr
 
 async fn get_data_archive(request: &HttpRequest, resolved_address: &ResolvedAddress, header_builder: &HeaderBuilder, file_service: FileService, archive_info: ArchiveInfo, signature_verified: Option<bool>, has_body: bool) -> Result<HttpResponse, ChunkError> {
     let (chunk_receiver, range_props) = file_service.download_data_request(request, archive_info.path_string, archive_info.resolved_xor_addr, archive_info.offset, archive_info.size).await?;
+    let chunk_receiver = peek_stream(chunk_receiver).await?;
 
     if range_props.is_range() {
         let mut builder = HttpResponse::PartialContent();
@@ -271,6 +280,7 @@ fn get_accept_header_value(header_map: &HeaderMap) -> Mime {
 
 async fn get_data_xor(request: &HttpRequest, resolved_address: &ResolvedAddress, header_builder: &HeaderBuilder, file_service: FileService, signature_verified: Option<bool>, has_body: bool) -> Result<HttpResponse, ChunkError> {
     let (chunk_receiver, range_props) = file_service.get_data(&request, &resolved_address).await?;
+    let chunk_receiver = peek_stream(chunk_receiver).await?;
     if range_props.is_range() {
         let mut builder = HttpResponse::PartialContent();
         update_partial_content_response(&mut builder, &resolved_address, &header_builder, &range_props, None, signature_verified);
@@ -290,6 +300,20 @@ async fn get_data_xor(request: &HttpRequest, resolved_address: &ResolvedAddress,
     }
 }
 
+/// Waits for the first bytes of the stream, so that a chunk which cannot be retrieved fails the
+/// request with an error status, instead of a 200 with a truncated body. Later failures abort
+/// the response, as the status has already been sent.
+async fn peek_stream<S, E>(mut chunk_receiver: S) -> Result<impl Stream<Item = Result<Bytes, E>>, ChunkError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: Display,
+{
+    match chunk_receiver.next().await {
+        Some(Err(e)) => Err(ChunkError::GetStreamError(GetStreamError::BadReceiver(format!("failed to stream data: {}", e)))),
+        first => Ok(stream::iter(first).chain(chunk_receiver)),
+    }
+}
+
 /*#[cfg(test)]
 mod tests {
     use super::*;
@@ -314,7 +338,7 @@ mod tests {
     async fn create_test_data() -> (Data<ResolverService>, Data<CachingClient>, Data<MockStreamingClient>, Data<AntTpConfig>) {
         let config = AntTpConfig::parse_from(vec!["anttp"]);
         let evm_network = EvmNetwork::ArbitrumOne;
-        let client_harness = Data::new(tokio::sync::Mutex::new(ClientHarness::new(evm_network, config.clone())));
+        let client_harness = Data::new(ClientHarness::new(evm_network, config.clone()));
         let hybrid_cache = Data::new(HybridCacheBuilder::new().memory(10).storage().build().await.unwrap());
         let (tx, _rx) = mpsc::channel::<Box<dyn Command>>(100);
         let command_executor = Data::new(tx);
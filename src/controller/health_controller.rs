@@ -0,0 +1,50 @@
+use actix_web::{Error, HttpResponse};
+use actix_web::http::header;
+use actix_web::web::Data;
+use log::debug;
+use crate::service::health_service::{HealthService, Liveness, Readiness, ServerStatus};
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/health",
+    responses(
+        (status = OK, description = "AntTP is alive", body = Liveness),
+    )
+)]
+pub async fn get_health(health_service: Data<HealthService>) -> Result<HttpResponse, Error> {
+    debug!("Getting liveness");
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(health_service.get_liveness()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/ready",
+    responses(
+        (status = OK, description = "AntTP is ready to serve requests", body = Readiness),
+        (status = SERVICE_UNAVAILABLE, description = "AntTP is shutting down or cannot connect to the network", body = Readiness),
+    )
+)]
+pub async fn get_ready(health_service: Data<HealthService>) -> Result<HttpResponse, Error> {
+    debug!("Getting readiness");
+    let readiness = health_service.get_readiness().await;
+    let mut response = if readiness.ready { HttpResponse::Ok() } else { HttpResponse::ServiceUnavailable() };
+    Ok(response
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(readiness))
+}
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/status",
+    responses(
+        (status = OK, description = "Status retrieved successfully", body = ServerStatus),
+    )
+)]
+pub async fn get_status(health_service: Data<HealthService>) -> Result<HttpResponse, Error> {
+    debug!("Getting status");
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .json(health_service.get_status().await))
+}
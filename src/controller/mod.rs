@@ -1,5 +1,14 @@
 #![cfg_attr(test, warn(unused_imports, unused_variables, dead_code))]
 use actix_web::HttpRequest;
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderMap, CACHE_CONTROL};
+use actix_web::middleware::Next;
+use actix_web::web::Data;
+use crate::client::command::command_batch::{CommandBatch, BATCH_HEADER};
+use crate::client::command::command_schedule::{CommandSchedule, NOT_BEFORE_HEADER, PRIORITY_HEADER};
+use crate::client::command::command_wallet::{CommandWallet, API_KEY_HEADER};
+use crate::service::wallet_service::WalletService;
 
 pub mod archive_controller;
 //pub mod pointer_controller;
@@ -13,6 +22,11 @@ pub mod chunk_controller;
 //pub mod graph_controller;
 pub mod public_data_controller;
 pub mod command_controller;
+pub mod prefetch_controller;
+pub mod cache_controller;
+pub mod health_controller;
+pub mod config_controller;
+pub mod wallet_controller;
 pub mod connect_controller;
 //pub mod pnr_controller;
 //pub mod key_value_controller;
@@ -72,13 +86,41 @@ impl From<String> for DataKey {
     }
 }
 
-fn data_key(request: &HttpRequest) -> DataKey {
-    match request.headers().get("x-data-key") {
-        Some(header_value) => DataKey::from(header_value.to_str().unwrap_or("").to_string()),
-        None => DataKey::Personal
+pub fn is_no_cache(headers: &HeaderMap) -> bool {
+    headers.get_all(CACHE_CONTROL)
+        .filter_map(|header_value| header_value.to_str().ok())
+        .flat_map(|header_value| header_value.split(','))
+        .any(|directive| directive.trim().eq_ignore_ascii_case("no-cache"))
+}
+
+/// Applies the `x-command-priority` and `x-command-not-before` headers to any commands queued
+/// while handling the request.
+pub async fn command_schedule(request: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let header = |name: &str| request.headers().get(name).and_then(|header_value| header_value.to_str().ok()).map(|value| value.to_string());
+    let command_schedule = CommandSchedule::parse(header(PRIORITY_HEADER).as_deref(), header(NOT_BEFORE_HEADER).as_deref())?;
+    command_schedule.scope(next.call(request)).await
+}
+
+/// Adds any commands queued while handling the request to the `x-command-batch` batch.
+pub async fn command_batch(request: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let header_value = request.headers().get(BATCH_HEADER).and_then(|header_value| header_value.to_str().ok()).map(|value| value.to_string());
+    match CommandBatch::parse(header_value.as_deref())? {
+        Some(command_batch) => command_batch.scope(next.call(request)).await,
+        None => next.call(request).await,
     }
 }
 
+/// Pays for any commands queued while handling the request with the keystore wallet selected by
+/// its `x-api-key` header, if any.
+pub async fn command_wallet(request: ServiceRequest, next: Next<impl MessageBody>) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let header_value = request.headers().get(API_KEY_HEADER).and_then(|header_value| header_value.to_str().ok()).map(|value| value.to_string());
+    let command_wallet = match request.app_data::<Data<WalletService>>() {
+        Some(wallet_service) => wallet_service.get_command_wallet(header_value.as_deref())?,
+        None => CommandWallet::from_api_key(header_value.as_deref(), &[])?,
+    };
+    CommandWallet::scope_optional(command_wallet, next.call(request)).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +172,122 @@ mod tests {
             .to_http_request();
         assert_eq!(get_store_type(&req), StoreType::Network);
     }
+
+    #[actix_web::test]
+    async fn test_command_schedule() {
+        use actix_web::{middleware, test, web, App, HttpResponse};
+
+        let app = test::init_service(
+            App::new()
+                .wrap(middleware::from_fn(command_schedule))
+                .route("/", web::get().to(|| async {
+                    HttpResponse::Ok().body(CommandSchedule::current().priority.to_string())
+                }))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/")
+            .insert_header((PRIORITY_HEADER, "5"))
+            .insert_header((NOT_BEFORE_HEADER, "2030-01-01T00:00:00Z"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(test::read_body(resp).await, "5");
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(test::read_body(resp).await, "0");
+
+        let req = test::TestRequest::get().uri("/").insert_header((PRIORITY_HEADER, "high")).to_request();
+        let resp = test::try_call_service(&app, req).await;
+        assert_eq!(resp.map(|resp| resp.status()).unwrap_or_else(|e| e.as_response_error().status_code()), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn test_command_batch() {
+        use actix_web::{middleware, test, web, App, HttpResponse};
+
+        let app = test::init_service(
+            App::new()
+                .wrap(middleware::from_fn(command_batch))
+                .route("/", web::get().to(|| async {
+                    HttpResponse::Ok().body(CommandBatch::current().map(|command_batch| command_batch.id).unwrap_or_default())
+                }))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/").insert_header((BATCH_HEADER, "batch1")).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(test::read_body(resp).await, "batch1");
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(test::read_body(resp).await, "");
+    }
+
+    #[actix_web::test]
+    async fn test_command_wallet() {
+        use actix_web::{middleware, test, web, App, HttpResponse};
+        use ant_core::data::Wallet;
+        use clap::Parser;
+        use evmlib::Network::ArbitrumOne;
+        use crate::client::client_harness::ClientHarness;
+        use crate::client::wallet_ledger::WalletLedger;
+        use crate::config::anttp_config::AntTpConfig;
+        use crate::config::key_store::{KeyStore, KeyType};
+
+        let directory = tempfile::tempdir().unwrap();
+        let key_store_file = directory.path().join("keys.json");
+        let mut key_store = KeyStore::open_or_create(&key_store_file, "passphrase").unwrap().with_iterations(1_000);
+        key_store.import("team-a", KeyType::Evm, &Wallet::random_private_key()).unwrap();
+        key_store.save().unwrap();
+        let ant_tp_config = AntTpConfig::try_parse_from([
+            "anttp", "--fake-network", "--key-store-file", key_store_file.to_str().unwrap(), "--key-store-passphrase", "passphrase"
+        ]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let wallet_service = WalletService::new(client_harness, ant_tp_config, WalletLedger::new());
+        wallet_service.load_api_key_wallets(vec![("key-a".to_string(), "team-a".to_string())]).await.unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(wallet_service))
+                .wrap(middleware::from_fn(command_wallet))
+                .route("/", web::get().to(|| async {
+                    HttpResponse::Ok().body(CommandWallet::current().map(|command_wallet| command_wallet.name).unwrap_or_default())
+                }))
+        ).await;
+
+        let req = test::TestRequest::get().uri("/").insert_header((API_KEY_HEADER, "key-a")).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(test::read_body(resp).await, "team-a");
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(test::read_body(resp).await, "");
+
+        let req = test::TestRequest::get().uri("/").insert_header((API_KEY_HEADER, "key-b")).to_request();
+        let resp = test::try_call_service(&app, req).await;
+        assert_eq!(resp.map(|resp| resp.status()).unwrap_or_else(|e| e.as_response_error().status_code()), actix_web::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_is_no_cache() {
+        use actix_web::test::TestRequest;
+
+        let req = TestRequest::default()
+            .insert_header(("Cache-Control", "no-cache"))
+            .to_http_request();
+        assert!(is_no_cache(req.headers()));
+
+        let req = TestRequest::default()
+            .insert_header(("Cache-Control", "max-age=0, No-Cache"))
+            .to_http_request();
+        assert!(is_no_cache(req.headers()));
+
+        let req = TestRequest::default()
+            .insert_header(("Cache-Control", "max-age=0"))
+            .to_http_request();
+        assert!(!is_no_cache(req.headers()));
+
+        let req = TestRequest::default()
+            .to_http_request();
+        assert!(!is_no_cache(req.headers()));
+    }
 }
\ No newline at end of file
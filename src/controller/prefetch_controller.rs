@@ -0,0 +1,25 @@
+use actix_web::{web, HttpResponse};
+use actix_web::web::Data;
+use log::debug;
+use crate::error::chunk_error::ChunkError;
+use crate::service::prefetch_service::{Prefetch, PrefetchService};
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/prefetch/{address}",
+    responses(
+        (status = ACCEPTED, description = "Prefetch queued successfully", body = Prefetch),
+        (status = BAD_REQUEST, description = "Address was invalid")
+    ),
+    params(
+        ("address" = String, Path, description = "Data map or archive address to warm the cache with"),
+    )
+)]
+pub async fn post_prefetch(
+    path: web::Path<String>,
+    prefetch_service: Data<PrefetchService>,
+) -> Result<HttpResponse, ChunkError> {
+    let address = path.into_inner();
+    debug!("Prefetching [{}]", address);
+    Ok(HttpResponse::Accepted().json(prefetch_service.prefetch(address).await?))
+}
@@ -2,13 +2,13 @@ use actix_http::header;
 use actix_web::{web, HttpRequest, HttpResponse};
 use actix_web::http::header::{ContentLength, ContentType};
 use actix_web::web::{Data, Payload};
-use ant_core::data::Wallet;
 use log::debug;
 use crate::error::public_data_error::PublicDataError;
 use crate::controller::get_store_type;
 use crate::error::CreateError;
 use crate::service::public_data_service::{PublicData, PublicDataService};
 use crate::service::chunk_service::Chunk;
+use crate::model::quote::Quote;
 
 #[utoipa::path(
     post,
@@ -24,7 +24,6 @@ use crate::service::chunk_service::Chunk;
 pub async fn push_public_data(
     path: web::Path<String>,
     public_data_service: Data<PublicDataService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, PublicDataError> {
     let address = path.into_inner();
@@ -52,7 +51,6 @@ pub async fn push_public_data(
 )]
 pub async fn post_public_data(
     public_data_service: Data<PublicDataService>,
-    evm_wallet_data: Data<Wallet>,
     payload: Payload,
     request: HttpRequest
 ) -> Result<HttpResponse, PublicDataError> {
@@ -69,6 +67,35 @@ pub async fn post_public_data(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/anttp-0/quote/binary/public_data",
+    request_body(
+        content = PublicData,
+        content_type = "application/octet-stream"
+    ),
+    responses(
+        (status = OK, description = "Public data quoted successfully", body = Quote),
+        (status = BAD_GATEWAY, description = "Network is offline"),
+    ),
+)]
+pub async fn post_public_data_quote(
+    public_data_service: Data<PublicDataService>,
+    payload: Payload,
+) -> Result<HttpResponse, PublicDataError> {
+    debug!("Quoting new public data");
+    match payload.to_bytes().await {
+        Ok(bytes) => {
+            Ok(HttpResponse::Ok().json(
+                public_data_service.quote_public_data(bytes).await?
+            ))
+        }
+        Err(e) => {
+            Err(CreateError::InvalidData(e.to_string()).into())
+        }
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/anttp-0/binary/public_data/{address}",
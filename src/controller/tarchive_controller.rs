@@ -1,13 +1,13 @@
 use actix_multipart::form::MultipartForm;
 use actix_web::{web, HttpRequest, HttpResponse};
 use actix_web::web::Data;
-use ant_core::data::Wallet;
 use log::debug;
 /*use crate::service::public_archive_service::{PublicArchiveForm, Upload, ArchiveResponse};*/
 use crate::service::tarchive_service::TarchiveService;
 use crate::error::tarchive_error::TarchiveError;
 use crate::controller::get_store_type;
 use crate::service::archive_service::{ArchiveResponse, PublicArchiveForm, Upload};
+use crate::model::quote::Quote;
 
 #[utoipa::path(
     delete,
@@ -25,16 +25,14 @@ use crate::service::archive_service::{ArchiveResponse, PublicArchiveForm, Upload
 pub async fn delete_tarchive(
     path_params: web::Path<(String, String)>,
     tarchive_service: Data<TarchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, TarchiveError> {
     let (address, mut path) = path_params.into_inner();
     path = path.replace("%2F", "/");
-    let evm_wallet = evm_wallet_data.get_ref().clone();
 
     debug!("Truncating tarchive at address [{}] and path [{}]", address, path);
     Ok(HttpResponse::Ok().json(
-        tarchive_service.truncate_tarchive(address, path, evm_wallet, get_store_type(&request)).await?
+        tarchive_service.truncate_tarchive(address, path, get_store_type(&request)).await?
     ))
 }
 
@@ -52,15 +50,13 @@ pub async fn delete_tarchive(
 pub async fn push_tarchive(
     path: web::Path<String>,
     tarchive_service: Data<TarchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, TarchiveError> {
     let address = path.into_inner();
-    let evm_wallet = evm_wallet_data.get_ref().clone();
 
     debug!("Pushing tarchive [{}] to target store type [{:?}]", address, get_store_type(&request));
     Ok(HttpResponse::Ok().json(
-        tarchive_service.push_tarchive(address, evm_wallet, get_store_type(&request)).await?
+        tarchive_service.push_tarchive(address, get_store_type(&request)).await?
     ))
 }
 
@@ -82,14 +78,12 @@ pub async fn push_tarchive(
 pub async fn post_tarchive_root(
     tarchive_form: MultipartForm<PublicArchiveForm>,
     tarchive_service: Data<TarchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest
 ) -> Result<HttpResponse, TarchiveError> {
-    let evm_wallet = evm_wallet_data.get_ref().clone();
 
     debug!("Creating new tarchive from multipart POST");
     Ok(HttpResponse::Created().json(
-        tarchive_service.create_tarchive(None, tarchive_form, evm_wallet, get_store_type(&request)).await?
+        tarchive_service.create_tarchive(None, tarchive_form, get_store_type(&request)).await?
     ))
 }
 
@@ -113,16 +107,14 @@ pub async fn post_tarchive(
     path_params: web::Path<String>,
     tarchive_form: MultipartForm<PublicArchiveForm>,
     tarchive_service: Data<TarchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest
 ) -> Result<HttpResponse, TarchiveError> {
     let mut path = path_params.into_inner();
     path = path.replace("%2F", "/");
-    let evm_wallet = evm_wallet_data.get_ref().clone();
 
     debug!("Creating new tarchive from multipart POST at path [{}]", path);
     Ok(HttpResponse::Created().json(
-        tarchive_service.create_tarchive(Some(path), tarchive_form, evm_wallet, get_store_type(&request)).await?
+        tarchive_service.create_tarchive(Some(path), tarchive_form, get_store_type(&request)).await?
     ))
 }
 
@@ -146,15 +138,13 @@ pub async fn put_tarchive_root(
     path: web::Path<String>,
     tarchive_form: MultipartForm<PublicArchiveForm>,
     tarchive_service: Data<TarchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, TarchiveError> {
     let address = path.into_inner();
-    let evm_wallet = evm_wallet_data.get_ref().clone();
 
     debug!("Updating [{}] tarchive from multipart PUT with store type [{:?}]", address, get_store_type(&request));
     Ok(HttpResponse::Ok().json(
-        tarchive_service.update_tarchive(address, None, tarchive_form, evm_wallet, get_store_type(&request)).await?
+        tarchive_service.update_tarchive(address, None, tarchive_form, get_store_type(&request)).await?
     ))
 }
 
@@ -179,16 +169,14 @@ pub async fn put_tarchive(
     path_params: web::Path<(String, String)>,
     tarchive_form: MultipartForm<PublicArchiveForm>,
     tarchive_service: Data<TarchiveService>,
-    evm_wallet_data: Data<Wallet>,
     request: HttpRequest,
 ) -> Result<HttpResponse, TarchiveError> {
     let (address, mut path) = path_params.into_inner();
     path = path.replace("%2F", "/");
-    let evm_wallet = evm_wallet_data.get_ref().clone();
 
     debug!("Updating [{}] tarchive from multipart PUT at path [{}] with store type [{:?}]", address, path, get_store_type(&request));
     Ok(HttpResponse::Ok().json(
-        tarchive_service.update_tarchive(address, Some(path), tarchive_form, evm_wallet, get_store_type(&request)).await?
+        tarchive_service.update_tarchive(address, Some(path), tarchive_form, get_store_type(&request)).await?
     ))
 }
 
@@ -235,3 +223,108 @@ pub async fn get_tarchive(
         tarchive_service.get_tarchive(address, Some(path)).await?
     ))
 }
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/quote/multipart/tarchive",
+    request_body(
+        content = PublicArchiveForm,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = OK, description = "Tarchive quoted successfully", body = Quote)
+    ),
+)]
+pub async fn post_tarchive_quote_root(
+    tarchive_form: MultipartForm<PublicArchiveForm>,
+    tarchive_service: Data<TarchiveService>,
+) -> Result<HttpResponse, TarchiveError> {
+    debug!("Quoting new tarchive from multipart POST");
+    Ok(HttpResponse::Ok().json(
+        tarchive_service.quote_tarchive(None, tarchive_form).await?
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/anttp-0/quote/multipart/tarchive/{path}",
+    request_body(
+        content = PublicArchiveForm,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = OK, description = "Tarchive quoted successfully", body = Quote)
+    ),
+    params(
+        ("path" = String, Path, description = "Target path (directory) for all uploads"),
+    ),
+)]
+pub async fn post_tarchive_quote(
+    path_params: web::Path<String>,
+    tarchive_form: MultipartForm<PublicArchiveForm>,
+    tarchive_service: Data<TarchiveService>,
+) -> Result<HttpResponse, TarchiveError> {
+    let mut path = path_params.into_inner();
+    path = path.replace("%2F", "/");
+
+    debug!("Quoting new tarchive from multipart POST at path [{}]", path);
+    Ok(HttpResponse::Ok().json(
+        tarchive_service.quote_tarchive(Some(path), tarchive_form).await?
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/anttp-0/quote/multipart/tarchive/{address}",
+    request_body(
+        content = PublicArchiveForm,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = OK, description = "Tarchive update quoted successfully", body = Quote)
+    ),
+    params(
+        ("address" = String, Path, description = "Tarchive address"),
+    ),
+)]
+pub async fn put_tarchive_quote_root(
+    path: web::Path<String>,
+    tarchive_form: MultipartForm<PublicArchiveForm>,
+    tarchive_service: Data<TarchiveService>,
+) -> Result<HttpResponse, TarchiveError> {
+    let address = path.into_inner();
+
+    debug!("Quoting update of [{}] tarchive from multipart PUT", address);
+    Ok(HttpResponse::Ok().json(
+        tarchive_service.quote_tarchive_update(address, None, tarchive_form).await?
+    ))
+}
+
+#[utoipa::path(
+    put,
+    path = "/anttp-0/quote/multipart/tarchive/{address}/{path}",
+    request_body(
+        content = PublicArchiveForm,
+        content_type = "multipart/form-data"
+    ),
+    responses(
+        (status = OK, description = "Tarchive update quoted successfully", body = Quote)
+    ),
+    params(
+        ("address" = String, Path, description = "Tarchive address"),
+        ("path" = String, Path, description = "Target path (directory) for all uploads"),
+    ),
+)]
+pub async fn put_tarchive_quote(
+    path_params: web::Path<(String, String)>,
+    tarchive_form: MultipartForm<PublicArchiveForm>,
+    tarchive_service: Data<TarchiveService>,
+) -> Result<HttpResponse, TarchiveError> {
+    let (address, mut path) = path_params.into_inner();
+    path = path.replace("%2F", "/");
+
+    debug!("Quoting update of [{}] tarchive from multipart PUT at path [{}]", address, path);
+    Ok(HttpResponse::Ok().json(
+        tarchive_service.quote_tarchive_update(address, Some(path), tarchive_form).await?
+    ))
+}
@@ -0,0 +1,60 @@
+use actix_web::{web, HttpResponse};
+use actix_web::web::Data;
+use log::debug;
+use crate::error::wallet_error::WalletError;
+use crate::service::wallet_service::{WalletDetails, WalletLoad, WalletService, WalletSpend};
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/wallet",
+    responses(
+        (status = OK, description = "Wallet address and balances retrieved successfully", body = WalletDetails),
+        (status = BAD_GATEWAY, description = "Wallet balances could not be retrieved"),
+    )
+)]
+pub async fn get_wallet(wallet_service: Data<WalletService>) -> Result<HttpResponse, WalletError> {
+    debug!("Getting wallet");
+    Ok(HttpResponse::Ok().json(wallet_service.get_wallet().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/wallet/keystore",
+    responses(
+        (status = OK, description = "Names of EVM wallets in the key store retrieved successfully", body = Vec<String>),
+        (status = BAD_REQUEST, description = "Key store file is not configured"),
+    )
+)]
+pub async fn get_wallet_keystore(wallet_service: Data<WalletService>) -> Result<HttpResponse, WalletError> {
+    debug!("Getting wallet keystore");
+    Ok(HttpResponse::Ok().json(wallet_service.get_wallets().await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/anttp-0/wallet/spend",
+    responses(
+        (status = OK, description = "What each wallet has paid for uploads since startup", body = Vec<WalletSpend>),
+    )
+)]
+pub async fn get_wallet_spend(wallet_service: Data<WalletService>) -> Result<HttpResponse, WalletError> {
+    debug!("Getting wallet spend");
+    Ok(HttpResponse::Ok().json(wallet_service.get_wallet_spends()))
+}
+
+#[utoipa::path(
+    put,
+    path = "/anttp-0/wallet",
+    request_body(
+        content = WalletLoad
+    ),
+    responses(
+        (status = OK, description = "Wallet loaded from the key store successfully", body = WalletDetails),
+        (status = BAD_REQUEST, description = "Key store is not configured or the key is not an EVM wallet"),
+        (status = NOT_FOUND, description = "Wallet not found in the key store"),
+    )
+)]
+pub async fn put_wallet(wallet_service: Data<WalletService>, wallet_load: web::Json<WalletLoad>) -> Result<HttpResponse, WalletError> {
+    debug!("Loading wallet [{}]", wallet_load.name);
+    Ok(HttpResponse::Ok().json(wallet_service.load_wallet(wallet_load.into_inner().name).await?))
+}
@@ -0,0 +1,55 @@
+use thiserror::Error;
+use serde::Serialize;
+use actix_http::StatusCode;
+use actix_web::HttpResponse;
+use actix_web::http::header::ContentType;
+use tokio::sync::mpsc::error::SendError;
+use crate::client::command::Command;
+use crate::error::{GetError, UpdateError};
+
+#[derive(Error, Debug, Serialize)]
+pub enum CommandError {
+    #[error("update error: {0}")]
+    UpdateError(UpdateError),
+    #[error("get error: {0}")]
+    GetError(GetError),
+}
+
+impl From<UpdateError> for CommandError {
+    fn from(value: UpdateError) -> Self {
+        Self::UpdateError(value)
+    }
+}
+
+impl From<GetError> for CommandError {
+    fn from(value: GetError) -> Self {
+        Self::GetError(value)
+    }
+}
+
+impl From<SendError<Box<dyn Command>>> for CommandError {
+    fn from(value: SendError<Box<dyn Command>>) -> Self {
+        Self::UpdateError(value.into())
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(value: std::io::Error) -> Self {
+        Self::UpdateError(value.into())
+    }
+}
+
+impl actix_web::ResponseError for CommandError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CommandError::GetError(v) => v.status_code(),
+            CommandError::UpdateError(v) => v.status_code(),
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::json())
+            .json(self)
+    }
+}
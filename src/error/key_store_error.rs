@@ -0,0 +1,24 @@
+use thiserror::Error;
+use serde::Serialize;
+
+#[derive(Error, Debug, Serialize, PartialEq)]
+pub enum KeyStoreError {
+    #[error("key store file error: {0}")]
+    File(String),
+    #[error("key store is corrupt: {0}")]
+    Corrupt(String),
+    #[error("wrong passphrase for key store")]
+    WrongPassphrase,
+    #[error("key [{0}] not found in key store")]
+    KeyNotFound(String),
+    #[error("key [{0}] already exists in key store")]
+    KeyExists(String),
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+}
+
+impl From<std::io::Error> for KeyStoreError {
+    fn from(value: std::io::Error) -> Self {
+        Self::File(value.to_string())
+    }
+}
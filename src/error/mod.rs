@@ -19,6 +19,9 @@ pub mod public_data_error;
 //pub mod register_error;
 //pub mod scratchpad_error;
 pub mod archive_error;
+pub mod command_error;
+pub mod wallet_error;
+pub mod key_store_error;
 // todo: split into a crate + separate files
 
 #[derive(Error, Debug, Serialize)]
@@ -37,6 +40,8 @@ pub enum CreateError {
     DataKeyMissing(String),
     #[error("network is offline: {0}")]
     NetworkOffline(String),
+    #[error("insufficient funds: {0}")]
+    InsufficientFunds(String),
 }
 
 impl From<SendError<Box<dyn Command>>> for CreateError {
@@ -45,6 +50,12 @@ impl From<SendError<Box<dyn Command>>> for CreateError {
     }
 }
 
+impl From<io::Error> for CreateError {
+    fn from(value: io::Error) -> Self {
+        Self::TemporaryStorage(value.to_string())
+    }
+}
+
 impl error::ResponseError for CreateError {
     fn status_code(&self) -> StatusCode {
         match self {
@@ -54,7 +65,8 @@ impl error::ResponseError for CreateError {
             CreateError::TemporaryStorage(_) => StatusCode::INSUFFICIENT_STORAGE,
             CreateError::InvalidData(_) => StatusCode::BAD_REQUEST,
             CreateError::DataKeyMissing(_) => StatusCode::PRECONDITION_FAILED,
-            CreateError::NetworkOffline(_) => StatusCode::BAD_GATEWAY
+            CreateError::NetworkOffline(_) => StatusCode::BAD_GATEWAY,
+            CreateError::InsufficientFunds(_) => StatusCode::PAYMENT_REQUIRED
         }
     }
 
@@ -165,6 +177,12 @@ impl From<SendError<Box<dyn Command>>> for GetError {
     }
 }
 
+impl From<io::Error> for GetError {
+    fn from(value: io::Error) -> Self {
+        Self::Command(value.to_string())
+    }
+}
+
 impl From<foyer::Error> for GetError {
     fn from(value: foyer::Error) -> Self {
         Self::RecordNotFound(value.to_string())
@@ -219,6 +237,12 @@ impl From<SendError<Box<dyn Command>>> for CheckError {
     }
 }
 
+impl From<io::Error> for CheckError {
+    fn from(value: io::Error) -> Self {
+        Self::Command(value.to_string())
+    }
+}
+
 impl From<foyer::Error> for CheckError {
     fn from(value: foyer::Error) -> Self {
         Self::RecordNotFound(value.to_string())
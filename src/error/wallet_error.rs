@@ -0,0 +1,47 @@
+use actix_http::StatusCode;
+use actix_web::HttpResponse;
+use actix_web::http::header::ContentType;
+use thiserror::Error;
+use serde::Serialize;
+use crate::error::{GetError, UpdateError};
+
+#[derive(Error, Debug, Serialize)]
+pub enum WalletError {
+    #[error("get error: {0}")]
+    GetError(GetError),
+    #[error("update error: {0}")]
+    UpdateError(UpdateError),
+}
+
+impl From<GetError> for WalletError {
+    fn from(value: GetError) -> Self {
+        Self::GetError(value)
+    }
+}
+
+impl From<UpdateError> for WalletError {
+    fn from(value: UpdateError) -> Self {
+        Self::UpdateError(value)
+    }
+}
+
+impl From<ant_core::data::Error> for WalletError {
+    fn from(value: ant_core::data::Error) -> Self {
+        Self::GetError(GetError::NetworkOffline(value.to_string()))
+    }
+}
+
+impl actix_web::ResponseError for WalletError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            WalletError::GetError(v) => v.status_code(),
+            WalletError::UpdateError(v) => v.status_code(),
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::json())
+            .json(self)
+    }
+}
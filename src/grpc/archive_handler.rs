@@ -1,6 +1,5 @@
 use tonic::{Request, Response, Status};
 use actix_web::web::Data;
-use ant_core::data::Wallet;
 use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::MultipartForm;
 use std::io::Write;
@@ -8,6 +7,9 @@ use crate::service::archive_service::{ArchiveForm, ArchiveService, ArchiveRespon
 use crate::model::archive::ArchiveType as ServiceArchiveType;
 use crate::controller::StoreType;
 use crate::error::archive_error::ArchiveError;
+use crate::grpc::{parse_command_schedule, parse_command_wallet};
+use crate::service::wallet_service::WalletService;
+use crate::client::command::command_wallet::CommandWallet;
 
 pub mod archive_proto {
     tonic::include_proto!("archive");
@@ -19,12 +21,12 @@ use archive_proto::{ArchiveType, CreateArchiveRequest, UpdateArchiveRequest, Tru
 
 pub struct ArchiveHandler {
     archive_service: Data<ArchiveService>,
-    evm_wallet: Data<Wallet>,
+    wallet_service: Data<WalletService>,
 }
 
 impl ArchiveHandler {
-    pub fn new(archive_service: Data<ArchiveService>, evm_wallet: Data<Wallet>) -> Self {
-        Self { archive_service, evm_wallet }
+    pub fn new(archive_service: Data<ArchiveService>, wallet_service: Data<WalletService>) -> Self {
+        Self { archive_service, wallet_service }
     }
 
     fn map_to_multipart_form(&self, files: Vec<ProtoFile>) -> Result<MultipartForm<ArchiveForm>, Status> {
@@ -59,6 +61,7 @@ impl From<ServiceUpload> for ArchiveResponse {
     fn from(upload: ServiceUpload) -> Self {
         ArchiveResponse {
             address: upload.address,
+            batch_id: upload.batch_id,
             ..Default::default()
         }
     }
@@ -70,6 +73,7 @@ impl From<ServiceArchiveResponse> for ArchiveResponse {
             address: Some(res.address),
             items: vec![], // Items are for listing/getting
             content: None,
+            batch_id: None,
         }
     }
 }
@@ -87,6 +91,7 @@ impl From<ServiceArchiveRaw> for ArchiveResponse {
             address: Some(res.address),
             items,
             content: Some(res.content.into()),
+            batch_id: None,
         }
     }
 }
@@ -103,46 +108,46 @@ impl ArchiveServiceTrait for ArchiveHandler {
         &self,
         request: Request<CreateArchiveRequest>,
     ) -> Result<Response<ArchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
         let archive_form = self.map_to_multipart_form(req.files)?;
         let archive_type = Self::map_archive_type(req.archive_type as i32);
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
 
-        match archive_type {
-            ServiceArchiveType::Public => {
-                let result = self.archive_service.create_public_archive(
+        // boxed, as the archive futures are otherwise nested too deeply for the compiler to lay out
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(Box::pin(async {
+            match archive_type {
+                ServiceArchiveType::Public => self.archive_service.create_public_archive(
                     req.path,
                     archive_form,
-                    self.evm_wallet.get_ref().clone(),
                     StoreType::from(req.store_type.unwrap_or_default())
-                ).await?;
-                Ok(Response::new(ArchiveResponse::from(result)))
-            },
-            ServiceArchiveType::Tarchive => {
-                let result = self.archive_service.create_tarchive(
+                ).await.map(ArchiveResponse::from),
+                ServiceArchiveType::Tarchive => self.archive_service.create_tarchive(
                     req.path,
                     archive_form,
-                    self.evm_wallet.get_ref().clone(),
                     StoreType::from(req.store_type.unwrap_or_default())
-                ).await?;
-                Ok(Response::new(ArchiveResponse::from(result)))
+                ).await.map(ArchiveResponse::from),
             }
-        }
+        }))).await?;
+
+        Ok(Response::new(result))
     }
 
     async fn update_archive(
         &self,
         request: Request<UpdateArchiveRequest>,
     ) -> Result<Response<ArchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
         let archive_form = self.map_to_multipart_form(req.files)?;
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
 
-        let result = self.archive_service.update_archive(
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.archive_service.update_archive(
             req.address,
             req.path,
             archive_form,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(req.store_type.unwrap_or_default()),
-        ).await?;
+        ))).await?;
 
         Ok(Response::new(ArchiveResponse::from(result)))
     }
@@ -151,14 +156,14 @@ impl ArchiveServiceTrait for ArchiveHandler {
         &self,
         request: Request<TruncateArchiveRequest>,
     ) -> Result<Response<ArchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
 
-        let result = self.archive_service.truncate_archive(
+        let result = CommandWallet::scope_optional(command_wallet, self.archive_service.truncate_archive(
             req.address,
             req.path,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(req.store_type.unwrap_or_default()),
-        ).await?;
+        )).await?;
 
         Ok(Response::new(ArchiveResponse::from(result)))
     }
@@ -178,13 +183,13 @@ impl ArchiveServiceTrait for ArchiveHandler {
         &self,
         request: Request<PushArchiveRequest>,
     ) -> Result<Response<ArchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
 
-        let result = self.archive_service.push_archive(
+        let result = CommandWallet::scope_optional(command_wallet, self.archive_service.push_archive(
             req.address,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(req.store_type.unwrap_or_else(|| "network".to_string())),
-        ).await?;
+        )).await?;
 
         Ok(Response::new(ArchiveResponse::from(result)))
     }
@@ -0,0 +1,107 @@
+use tonic::{Request, Response, Status};
+use actix_web::web::Data;
+use crate::client::cache_integrity::CacheIntegrityStats as ServiceCacheIntegrityStats;
+use crate::client::chunk_fetch_policy::ChunkFetchStats as ServiceChunkFetchStats;
+use crate::client::negative_cache::NegativeCacheStats as ServiceNegativeCacheStats;
+use crate::service::cache_service::{CacheService, CacheStats as ServiceCacheStats};
+
+pub mod cache_proto {
+    tonic::include_proto!("cache");
+}
+
+use cache_proto::cache_service_server::CacheService as CacheServiceTrait;
+pub use cache_proto::cache_service_server::CacheServiceServer;
+use cache_proto::{CacheIntegrityStats, CacheStats, ChunkFetchStats, GetCacheStatsRequest, NegativeCacheStats};
+
+pub struct CacheHandler {
+    cache_service: Data<CacheService>,
+}
+
+impl CacheHandler {
+    pub fn new(cache_service: Data<CacheService>) -> Self {
+        Self { cache_service }
+    }
+}
+
+impl From<ServiceNegativeCacheStats> for NegativeCacheStats {
+    fn from(s: ServiceNegativeCacheStats) -> Self {
+        NegativeCacheStats {
+            hits: s.hits,
+            inserts: s.inserts,
+            expired: s.expired,
+            bypasses: s.bypasses,
+        }
+    }
+}
+
+impl From<ServiceCacheIntegrityStats> for CacheIntegrityStats {
+    fn from(s: ServiceCacheIntegrityStats) -> Self {
+        CacheIntegrityStats {
+            verified: s.verified,
+            corrupted: s.corrupted,
+            scrubbed: s.scrubbed,
+        }
+    }
+}
+
+impl From<ServiceChunkFetchStats> for ChunkFetchStats {
+    fn from(s: ServiceChunkFetchStats) -> Self {
+        ChunkFetchStats {
+            fetches: s.fetches,
+            timeouts: s.timeouts,
+            retries: s.retries,
+            hedges: s.hedges,
+            hedge_wins: s.hedge_wins,
+            failures: s.failures,
+        }
+    }
+}
+
+impl From<ServiceCacheStats> for CacheStats {
+    fn from(s: ServiceCacheStats) -> Self {
+        CacheStats {
+            negative_cache: Some(NegativeCacheStats::from(s.negative_cache)),
+            integrity: Some(CacheIntegrityStats::from(s.integrity)),
+            chunk_fetch: Some(ChunkFetchStats::from(s.chunk_fetch)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl CacheServiceTrait for CacheHandler {
+    async fn get_cache_stats(
+        &self,
+        _request: Request<GetCacheStatsRequest>,
+    ) -> Result<Response<CacheStats>, Status> {
+        Ok(Response::new(CacheStats::from(self.cache_service.get_cache_stats())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_stats_from_service() {
+        let negative_cache_stats = ServiceNegativeCacheStats { hits: 1, inserts: 2, expired: 3, bypasses: 4 };
+        let cache_integrity_stats = ServiceCacheIntegrityStats { verified: 5, corrupted: 6, scrubbed: 7 };
+        let chunk_fetch_stats = ServiceChunkFetchStats { fetches: 8, timeouts: 9, retries: 10, hedges: 11, hedge_wins: 12, failures: 13 };
+        let cache_stats = CacheStats::from(ServiceCacheStats::new(negative_cache_stats, cache_integrity_stats, chunk_fetch_stats));
+        let negative_cache = cache_stats.negative_cache.unwrap();
+        assert_eq!(negative_cache.hits, 1);
+        assert_eq!(negative_cache.inserts, 2);
+        assert_eq!(negative_cache.expired, 3);
+        assert_eq!(negative_cache.bypasses, 4);
+        let integrity = cache_stats.integrity.unwrap();
+        assert_eq!(integrity.verified, 5);
+        assert_eq!(integrity.corrupted, 6);
+        assert_eq!(integrity.scrubbed, 7);
+        let chunk_fetch = cache_stats.chunk_fetch.unwrap();
+        assert_eq!(chunk_fetch.fetches, 8);
+        assert_eq!(chunk_fetch.timeouts, 9);
+        assert_eq!(chunk_fetch.retries, 10);
+        assert_eq!(chunk_fetch.hedges, 11);
+        assert_eq!(chunk_fetch.hedge_wins, 12);
+        assert_eq!(chunk_fetch.failures, 13);
+    }
+}
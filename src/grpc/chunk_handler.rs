@@ -1,8 +1,10 @@
 use tonic::{Request, Response, Status};
 use actix_web::web::Data;
-use ant_core::data::Wallet;
 use crate::service::chunk_service::{Chunk as ServiceChunk, ChunkService};
+use crate::service::wallet_service::WalletService;
+use crate::client::command::command_wallet::CommandWallet;
 use crate::controller::StoreType;
+use crate::model::quote::Quote as ServiceQuote;
 use bytes::Bytes;
 
 pub mod chunk_proto {
@@ -11,17 +13,18 @@ pub mod chunk_proto {
 
 use chunk_proto::chunk_service_server::ChunkService as ChunkServiceTrait;
 pub use chunk_proto::chunk_service_server::ChunkServiceServer;
-use chunk_proto::{Chunk, ChunkResponse, CreateChunkRequest, CreateChunkBinaryRequest, GetChunkRequest, GetChunkBinaryResponse};
+use chunk_proto::{Chunk, ChunkResponse, CreateChunkRequest, CreateChunkBinaryRequest, GetChunkRequest, GetChunkBinaryResponse, Quote, QuoteChunkRequest};
 use crate::error::chunk_error::ChunkError;
+use crate::grpc::{parse_command_schedule, parse_command_wallet};
 
 pub struct ChunkHandler {
     chunk_service: Data<ChunkService>,
-    evm_wallet: Data<Wallet>,
+    wallet_service: Data<WalletService>,
 }
 
 impl ChunkHandler {
-    pub fn new(chunk_service: Data<ChunkService>, evm_wallet: Data<Wallet>) -> Self {
-        Self { chunk_service, evm_wallet }
+    pub fn new(chunk_service: Data<ChunkService>, wallet_service: Data<WalletService>) -> Self {
+        Self { chunk_service, wallet_service }
     }
 }
 
@@ -43,6 +46,19 @@ impl From<ServiceChunk> for Chunk {
     }
 }
 
+impl From<ServiceQuote> for Quote {
+    fn from(q: ServiceQuote) -> Self {
+        Quote {
+            address: q.address,
+            chunks: q.chunks,
+            bytes: q.bytes,
+            chunks_stored: q.chunks_stored,
+            bytes_stored: q.bytes_stored,
+            cost: q.cost,
+        }
+    }
+}
+
 impl From<ChunkError> for Status {
     fn from(chunk_error: ChunkError) -> Self {
         Status::internal(chunk_error.to_string())
@@ -55,13 +71,15 @@ impl ChunkServiceTrait for ChunkHandler {
         &self,
         request: Request<CreateChunkRequest>,
     ) -> Result<Response<ChunkResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
         let chunk = req.chunk.ok_or_else(|| Status::invalid_argument("Chunk is required"))?;
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
 
-        let result = self.chunk_service.create_chunk(
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.chunk_service.create_chunk(
             ServiceChunk::from(chunk),
             StoreType::from(req.store_type.unwrap_or_default()),
-        ).await?;
+        ))).await?;
 
         Ok(Response::new(ChunkResponse {
             chunk: Some(Chunk::from(result)),
@@ -72,12 +90,14 @@ impl ChunkServiceTrait for ChunkHandler {
         &self,
         request: Request<CreateChunkBinaryRequest>,
     ) -> Result<Response<ChunkResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
-        
-        let result = self.chunk_service.create_chunk_binary(
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
+
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.chunk_service.create_chunk_binary(
             Bytes::from(req.data),
             StoreType::from(req.store_type.unwrap_or_default()),
-        ).await?;
+        ))).await?;
 
         Ok(Response::new(ChunkResponse {
             chunk: Some(Chunk::from(result)),
@@ -107,6 +127,17 @@ impl ChunkServiceTrait for ChunkHandler {
             data: result.content.to_vec(),
         }))
     }
+
+    async fn quote_chunk(
+        &self,
+        request: Request<QuoteChunkRequest>,
+    ) -> Result<Response<Quote>, Status> {
+        let req = request.into_inner();
+        let chunk = req.chunk.ok_or_else(|| Status::invalid_argument("Chunk is required"))?;
+        let result = self.chunk_service.quote_chunk(ServiceChunk::from(chunk)).await?;
+
+        Ok(Response::new(Quote::from(result)))
+    }
 }
 
 #[cfg(test)]
@@ -134,4 +165,21 @@ mod tests {
         assert_eq!(proto_chunk.content, service_chunk.content);
         assert_eq!(proto_chunk.address, service_chunk.address);
     }
+
+    #[test]
+    fn test_from_service_quote() {
+        let service_quote = ServiceQuote {
+            address: "0x123".to_string(),
+            chunks: 1,
+            bytes: 12,
+            chunks_stored: 0,
+            bytes_stored: 0,
+            cost: "36".to_string(),
+        };
+        let proto_quote = Quote::from(service_quote.clone());
+        assert_eq!(proto_quote.address, service_quote.address);
+        assert_eq!(proto_quote.chunks, 1);
+        assert_eq!(proto_quote.bytes, 12);
+        assert_eq!(proto_quote.cost, "36");
+    }
 }
@@ -1,6 +1,10 @@
+use std::pin::Pin;
 use tonic::{Request, Response, Status};
 use actix_web::web::Data;
-use crate::service::command_service::{CommandService, Command as ServiceCommand, Property as ServiceProperty, CommandList as ServiceCommandList};
+use futures_core::Stream;
+use futures_util::StreamExt;
+use crate::service::command_service::{CommandService, Command as ServiceCommand, Property as ServiceProperty, Attempt as ServiceAttempt, CommandList as ServiceCommandList, Batch as ServiceBatch};
+use crate::error::command_error::CommandError;
 
 pub mod command_proto {
     tonic::include_proto!("command");
@@ -8,7 +12,7 @@ pub mod command_proto {
 
 use command_proto::command_service_server::CommandService as CommandServiceTrait;
 pub use command_proto::command_service_server::CommandServiceServer;
-use command_proto::{Command, Property, CommandList, GetCommandsRequest};
+use command_proto::{Command, Property, Attempt, CommandList, Batch, GetCommandsRequest, CommandRequest, PurgeCommandsRequest, WatchCommandsRequest, BatchRequest};
 
 pub struct CommandHandler {
     command_service: Data<CommandService>,
@@ -29,6 +33,16 @@ impl From<ServiceProperty> for Property {
     }
 }
 
+impl From<ServiceAttempt> for Attempt {
+    fn from(a: ServiceAttempt) -> Self {
+        Attempt {
+            started_at: a.started_at as u64,
+            error: a.error,
+            backoff_seconds: a.backoff_seconds,
+        }
+    }
+}
+
 impl From<ServiceCommand> for Command {
     fn from(c: ServiceCommand) -> Self {
         Command {
@@ -39,10 +53,47 @@ impl From<ServiceCommand> for Command {
             waiting_at: c.waiting_at as u64,
             running_at: c.running_at.map(|v| v as u64),
             terminated_at: c.terminated_at.map(|v| v as u64),
+            attempts: c.attempts.into_iter().map(Attempt::from).collect(),
+            error: c.error,
+            result_address: c.result_address,
+            bytes_uploaded: c.bytes_uploaded,
+            storage_cost: c.storage_cost,
+            gas_cost: c.gas_cost,
+            duplicate_of: c.duplicate_of,
+            priority: c.priority,
+            not_before: c.not_before.map(|v| v as u64),
+            queue_position: c.queue_position.map(|v| v as u64),
+            eta: c.eta.map(|v| v as u64),
+            batch_id: c.batch_id,
+            wallet: c.wallet,
+        }
+    }
+}
+
+impl From<ServiceBatch> for Batch {
+    fn from(b: ServiceBatch) -> Self {
+        Batch {
+            id: b.id,
+            state: b.state,
+            total: b.total as u64,
+            waiting: b.waiting as u64,
+            running: b.running as u64,
+            completed: b.completed as u64,
+            failed: b.failed as u64,
+            aborted: b.aborted as u64,
+            percent_complete: b.percent_complete as u32,
+            bytes_uploaded: b.bytes_uploaded,
+            commands: b.commands.into_iter().map(Command::from).collect(),
         }
     }
 }
 
+impl From<CommandError> for Status {
+    fn from(command_error: CommandError) -> Self {
+        Status::internal(command_error.to_string())
+    }
+}
+
 impl From<ServiceCommandList> for CommandList {
     fn from(cl: ServiceCommandList) -> Self {
         CommandList {
@@ -53,6 +104,8 @@ impl From<ServiceCommandList> for CommandList {
 
 #[tonic::async_trait]
 impl CommandServiceTrait for CommandHandler {
+    type WatchCommandsStream = Pin<Box<dyn Stream<Item = Result<Command, Status>> + Send>>;
+
     async fn get_commands(
         &self,
         _request: Request<GetCommandsRequest>,
@@ -62,6 +115,76 @@ impl CommandServiceTrait for CommandHandler {
 
         Ok(Response::new(CommandList::from(result)))
     }
+
+    async fn get_command(
+        &self,
+        request: Request<CommandRequest>,
+    ) -> Result<Response<Command>, Status> {
+        let req = request.into_inner();
+        let result = self.command_service.get_command(req.id).await?;
+
+        Ok(Response::new(Command::from(result)))
+    }
+
+    async fn cancel_command(
+        &self,
+        request: Request<CommandRequest>,
+    ) -> Result<Response<Command>, Status> {
+        let req = request.into_inner();
+        let result = self.command_service.cancel_command(req.id).await?;
+
+        Ok(Response::new(Command::from(result)))
+    }
+
+    async fn retry_command(
+        &self,
+        request: Request<CommandRequest>,
+    ) -> Result<Response<Command>, Status> {
+        let req = request.into_inner();
+        let result = self.command_service.retry_command(req.id).await?;
+
+        Ok(Response::new(Command::from(result)))
+    }
+
+    async fn purge_commands(
+        &self,
+        _request: Request<PurgeCommandsRequest>,
+    ) -> Result<Response<CommandList>, Status> {
+        let result = self.command_service.purge_commands().await?;
+
+        Ok(Response::new(CommandList::from(result)))
+    }
+
+    async fn watch_commands(
+        &self,
+        _request: Request<WatchCommandsRequest>,
+    ) -> Result<Response<Self::WatchCommandsStream>, Status> {
+        let commands = self.command_service.watch_commands()
+            .map(Command::from)
+            .map(Ok);
+
+        Ok(Response::new(Box::pin(commands)))
+    }
+
+    async fn get_batch(
+        &self,
+        request: Request<BatchRequest>,
+    ) -> Result<Response<Batch>, Status> {
+        let req = request.into_inner();
+        let result = self.command_service.get_batch(req.id).await?;
+
+        Ok(Response::new(Batch::from(result)))
+    }
+
+    async fn retry_batch(
+        &self,
+        request: Request<BatchRequest>,
+    ) -> Result<Response<Batch>, Status> {
+        let req = request.into_inner();
+        let result = self.command_service.retry_batch(req.id).await?;
+
+        Ok(Response::new(Batch::from(result)))
+    }
 }
 
 #[cfg(test)]
@@ -69,14 +192,47 @@ mod tests {
     use super::*;
     use tokio::sync::Mutex;
     use indexmap::IndexMap;
-    use crate::client::command::command_details::CommandDetails;
+    use tokio::sync::mpsc::{channel, Receiver};
+    use tempfile::TempDir;
+    use crate::client::command::Command as ClientCommand;
+    use crate::client::command::command_batch::CommandBatch;
+    use crate::client::command::command_control::CommandControl;
+    use crate::client::command::command_events::CommandEvents;
+    use crate::client::command::command_details::{CommandDetails, CommandState};
+    use crate::client::command::command_journal::CommandJournal;
+    use crate::client::command::command_metadata::CommandMetadata;
+    use crate::client::command::test_command::IdCommand;
     use crate::service::command_service::Property as ServiceProperty;
 
+    fn create_handler(commands_map: Data<Mutex<IndexMap<u128, CommandDetails>>>, command_control: CommandControl) -> (CommandHandler, Receiver<Box<dyn ClientCommand>>, TempDir) {
+        create_handler_with_events(commands_map, command_control, CommandEvents::new(10))
+    }
+
+    fn create_handler_with_events(commands_map: Data<Mutex<IndexMap<u128, CommandDetails>>>, command_control: CommandControl, command_events: CommandEvents) -> (CommandHandler, Receiver<Box<dyn ClientCommand>>, TempDir) {
+        let (command_executor, command_receiver) = channel(10);
+        let journal_directory = tempfile::tempdir().unwrap();
+        let command_journal = CommandJournal::new(journal_directory.path()).unwrap();
+        let command_service = Data::new(CommandService::new(commands_map, command_control, Data::new(command_executor), command_journal, command_events));
+        (CommandHandler::new(command_service), command_receiver, journal_directory)
+    }
+
+    fn insert_command(commands_map: &mut IndexMap<u128, CommandDetails>, id: u128, command_state: Option<CommandState>) {
+        insert_batch_command(commands_map, id, command_state, None);
+    }
+
+    fn insert_batch_command(commands_map: &mut IndexMap<u128, CommandDetails>, id: u128, command_state: Option<CommandState>, command_batch: Option<CommandBatch>) {
+        let command = CommandMetadata { batch: command_batch, ..Default::default() }.apply(IdCommand::boxed(id));
+        let mut command_details = CommandDetails::new(&command);
+        if let Some(command_state) = command_state {
+            command_details.set_state(command_state);
+        }
+        commands_map.insert(id, command_details);
+    }
+
     #[tokio::test]
     async fn test_get_commands() {
         let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
-        let command_service = Data::new(CommandService::new(commands_map.clone()));
-        let handler = CommandHandler::new(command_service);
+        let (handler, _command_receiver, _journal_directory) = create_handler(commands_map, CommandControl::new(10));
 
         let request = Request::new(GetCommandsRequest {});
         let response = handler.get_commands(request).await.unwrap();
@@ -84,6 +240,109 @@ mod tests {
         assert_eq!(response.into_inner().commands.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_get_command() {
+        let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
+        insert_command(&mut *commands_map.lock().await, 1, None);
+        let (handler, _command_receiver, _journal_directory) = create_handler(commands_map, CommandControl::new(10));
+
+        let response = handler.get_command(Request::new(CommandRequest { id: "1".to_string() })).await.unwrap();
+        assert_eq!(response.into_inner().state, "WAITING");
+
+        let status = handler.get_command(Request::new(CommandRequest { id: "2".to_string() })).await.unwrap_err();
+        assert!(status.message().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_command() {
+        let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
+        insert_command(&mut *commands_map.lock().await, 1, None);
+        insert_command(&mut *commands_map.lock().await, 2, Some(CommandState::COMPLETED));
+        let command_control = CommandControl::new(10);
+        let (handler, _command_receiver, _journal_directory) = create_handler(commands_map, command_control.clone());
+
+        let response = handler.cancel_command(Request::new(CommandRequest { id: "1".to_string() })).await.unwrap();
+        assert_eq!(response.into_inner().state, "ABORTED");
+        assert!(command_control.register(1).is_none());
+
+        assert!(handler.cancel_command(Request::new(CommandRequest { id: "2".to_string() })).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retry_command() {
+        let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
+        insert_command(&mut *commands_map.lock().await, 1, Some(CommandState::FAILED));
+        let command_control = CommandControl::new(10);
+        command_control.insert_failed(IdCommand::boxed(1));
+        let (handler, mut command_receiver, _journal_directory) = create_handler(commands_map, command_control);
+
+        let response = handler.retry_command(Request::new(CommandRequest { id: "1".to_string() })).await.unwrap();
+        assert_eq!(response.into_inner().state, "WAITING");
+        assert_eq!(command_receiver.recv().await.unwrap().id(), 1);
+
+        // the failed command is consumed by the retry
+        assert!(handler.retry_command(Request::new(CommandRequest { id: "1".to_string() })).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_commands() {
+        let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
+        insert_command(&mut *commands_map.lock().await, 1, None);
+        let command_events = CommandEvents::new(10);
+        let (handler, _command_receiver, _journal_directory) = create_handler_with_events(commands_map, CommandControl::new(10), command_events.clone());
+
+        let mut commands = handler.watch_commands(Request::new(WatchCommandsRequest {})).await.unwrap().into_inner();
+        // cancelling a waiting command publishes the transition to aborted
+        handler.cancel_command(Request::new(CommandRequest { id: "1".to_string() })).await.unwrap();
+
+        let command = commands.next().await.unwrap().unwrap();
+        assert_eq!(command.id, "1");
+        assert_eq!(command.state, "ABORTED");
+    }
+
+    #[tokio::test]
+    async fn test_get_and_retry_batch() {
+        let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
+        let command_batch = CommandBatch::new("batch1".to_string());
+        insert_batch_command(&mut *commands_map.lock().await, 1, Some(CommandState::COMPLETED), Some(command_batch.clone()));
+        insert_batch_command(&mut *commands_map.lock().await, 2, Some(CommandState::FAILED), Some(command_batch.clone()));
+        insert_batch_command(&mut *commands_map.lock().await, 3, None, Some(command_batch.clone()));
+        insert_command(&mut *commands_map.lock().await, 4, Some(CommandState::FAILED));
+        let command_control = CommandControl::new(10);
+        command_control.insert_failed(CommandMetadata { batch: Some(command_batch.clone()), ..Default::default() }.apply(IdCommand::boxed(2)));
+        let (handler, mut command_receiver, _journal_directory) = create_handler(commands_map, command_control);
+
+        let batch = handler.get_batch(Request::new(BatchRequest { id: "batch1".to_string() })).await.unwrap().into_inner();
+        assert_eq!(batch.total, 3);
+        assert_eq!(batch.completed, 1);
+        assert_eq!(batch.failed, 1);
+        assert_eq!(batch.waiting, 1);
+        assert_eq!(batch.state, "RUNNING");
+        assert_eq!(batch.percent_complete, 66);
+        assert_eq!(batch.commands[0].batch_id, Some("batch1".to_string()));
+
+        let batch = handler.retry_batch(Request::new(BatchRequest { id: "batch1".to_string() })).await.unwrap().into_inner();
+        assert_eq!(batch.failed, 0);
+        assert_eq!(batch.waiting, 2);
+        assert_eq!(command_receiver.recv().await.unwrap().id(), 2);
+
+        let status = handler.get_batch(Request::new(BatchRequest { id: "batch2".to_string() })).await.unwrap_err();
+        assert!(status.message().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_purge_commands() {
+        let commands_map = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::new()));
+        insert_command(&mut *commands_map.lock().await, 1, None);
+        insert_command(&mut *commands_map.lock().await, 2, Some(CommandState::COMPLETED));
+        insert_command(&mut *commands_map.lock().await, 3, Some(CommandState::FAILED));
+        let (handler, _command_receiver, _journal_directory) = create_handler(commands_map.clone(), CommandControl::new(10));
+
+        let response = handler.purge_commands(Request::new(PurgeCommandsRequest {})).await.unwrap();
+        assert_eq!(response.into_inner().commands.len(), 2);
+        assert_eq!(commands_map.lock().await.len(), 1);
+    }
+
     #[test]
     fn test_property_from_service() {
         let service_property = ServiceProperty::new("test_name".to_string(), "test_value".to_string());
@@ -112,5 +371,45 @@ mod tests {
         assert_eq!(proto_command.waiting_at, 100);
         assert_eq!(proto_command.running_at, Some(200));
         assert_eq!(proto_command.terminated_at, None);
+        assert!(proto_command.attempts.is_empty());
+        assert_eq!(proto_command.result_address, None);
+    }
+
+    #[test]
+    fn test_command_outcome_from_service() {
+        let service_command = ServiceCommand {
+            attempts: vec![ServiceAttempt { started_at: 100, error: Some("timeout".to_string()), backoff_seconds: Some(1) }],
+            error: None,
+            result_address: Some("address1".to_string()),
+            bytes_uploaded: Some(1024),
+            storage_cost: Some("10".to_string()),
+            gas_cost: Some("20".to_string()),
+            duplicate_of: None,
+            ..ServiceCommand::new("id1".to_string(), "name1".to_string(), vec![], "COMPLETED".to_string(), 100, Some(200), Some(300))
+        };
+        let proto_command = Command::from(service_command);
+        assert_eq!(proto_command.attempts.len(), 1);
+        assert_eq!(proto_command.attempts[0].error, Some("timeout".to_string()));
+        assert_eq!(proto_command.attempts[0].backoff_seconds, Some(1));
+        assert_eq!(proto_command.result_address, Some("address1".to_string()));
+        assert_eq!(proto_command.bytes_uploaded, Some(1024));
+        assert_eq!(proto_command.storage_cost, Some("10".to_string()));
+        assert_eq!(proto_command.gas_cost, Some("20".to_string()));
+    }
+
+    #[test]
+    fn test_command_schedule_from_service() {
+        let service_command = ServiceCommand {
+            priority: 10,
+            not_before: Some(1000),
+            queue_position: Some(2),
+            eta: Some(2000),
+            ..ServiceCommand::new("id1".to_string(), "name1".to_string(), vec![], "WAITING".to_string(), 100, None, None)
+        };
+        let proto_command = Command::from(service_command);
+        assert_eq!(proto_command.priority, 10);
+        assert_eq!(proto_command.not_before, Some(1000));
+        assert_eq!(proto_command.queue_position, Some(2));
+        assert_eq!(proto_command.eta, Some(2000));
     }
 }
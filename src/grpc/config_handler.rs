@@ -0,0 +1,68 @@
+use tonic::{Request, Response, Status};
+use actix_web::web::Data;
+use crate::error::UpdateError;
+use crate::service::config_service::{ConfigReload as ServiceConfigReload, ConfigService};
+
+pub mod config_proto {
+    tonic::include_proto!("config");
+}
+
+use config_proto::config_service_server::ConfigService as ConfigServiceTrait;
+pub use config_proto::config_service_server::ConfigServiceServer;
+use config_proto::{ConfigReload, ReloadConfigRequest};
+
+pub struct ConfigHandler {
+    config_service: Data<ConfigService>,
+}
+
+impl ConfigHandler {
+    pub fn new(config_service: Data<ConfigService>) -> Self {
+        Self { config_service }
+    }
+}
+
+impl From<ServiceConfigReload> for ConfigReload {
+    fn from(c: ServiceConfigReload) -> Self {
+        ConfigReload {
+            cached_mutable_ttl: c.cached_mutable_ttl,
+            negative_cache_ttl: c.negative_cache_ttl,
+            log_level: c.log_level,
+            restart_required: c.restart_required,
+            access_list_address: c.access_list_address,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ConfigServiceTrait for ConfigHandler {
+    async fn reload_config(
+        &self,
+        _request: Request<ReloadConfigRequest>,
+    ) -> Result<Response<ConfigReload>, Status> {
+        match self.config_service.reload().await {
+            Ok(config_reload) => Ok(Response::new(ConfigReload::from(config_reload))),
+            Err(UpdateError::InvalidData(message)) => Err(Status::invalid_argument(message)),
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_reload_from_service() {
+        let config_reload = ConfigReload::from(ServiceConfigReload {
+            cached_mutable_ttl: 60,
+            negative_cache_ttl: 30,
+            log_level: "debug".to_string(),
+            access_list_address: "".to_string(),
+            restart_required: true,
+        });
+        assert_eq!(config_reload.cached_mutable_ttl, 60);
+        assert_eq!(config_reload.negative_cache_ttl, 30);
+        assert_eq!(config_reload.log_level, "debug");
+        assert!(config_reload.restart_required);
+    }
+}
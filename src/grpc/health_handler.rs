@@ -0,0 +1,167 @@
+use tonic::{Request, Response, Status};
+use actix_web::web::Data;
+use crate::client::client_harness::NetworkStatus as ServiceNetworkStatus;
+use crate::service::health_service::{CacheUsage as ServiceCacheUsage, CommandQueueStatus as ServiceCommandQueueStatus, HealthService,
+                                     Listener as ServiceListener, Liveness as ServiceLiveness, Readiness as ServiceReadiness,
+                                     ServerStatus as ServiceServerStatus};
+
+pub mod health_proto {
+    tonic::include_proto!("health");
+}
+
+use health_proto::health_service_server::HealthService as HealthServiceTrait;
+pub use health_proto::health_service_server::HealthServiceServer;
+use health_proto::{CacheUsage, CommandQueueStatus, GetHealthRequest, GetReadyRequest, GetStatusRequest, Listener, Liveness,
+                   NetworkStatus, Readiness, ServerStatus};
+
+pub struct HealthHandler {
+    health_service: Data<HealthService>,
+}
+
+impl HealthHandler {
+    pub fn new(health_service: Data<HealthService>) -> Self {
+        Self { health_service }
+    }
+}
+
+impl From<ServiceLiveness> for Liveness {
+    fn from(l: ServiceLiveness) -> Self {
+        Liveness {
+            alive: l.alive,
+            uptime_seconds: l.uptime_seconds,
+        }
+    }
+}
+
+impl From<ServiceReadiness> for Readiness {
+    fn from(r: ServiceReadiness) -> Self {
+        Readiness {
+            ready: r.ready,
+            reasons: r.reasons,
+        }
+    }
+}
+
+impl From<ServiceNetworkStatus> for NetworkStatus {
+    fn from(n: ServiceNetworkStatus) -> Self {
+        NetworkStatus {
+            backend: n.backend,
+            connected: n.connected,
+            connecting: n.connecting,
+            last_connect_error: n.last_connect_error,
+            idle_seconds: n.idle_seconds,
+            idle_disconnect: n.idle_disconnect,
+        }
+    }
+}
+
+impl From<ServiceCacheUsage> for CacheUsage {
+    fn from(c: ServiceCacheUsage) -> Self {
+        CacheUsage {
+            memory_usage: c.memory_usage,
+            memory_capacity: c.memory_capacity,
+            disk_capacity_bytes: c.disk_capacity_bytes,
+            disk_read_bytes: c.disk_read_bytes,
+            disk_write_bytes: c.disk_write_bytes,
+        }
+    }
+}
+
+impl From<ServiceCommandQueueStatus> for CommandQueueStatus {
+    fn from(c: ServiceCommandQueueStatus) -> Self {
+        CommandQueueStatus {
+            waiting: c.waiting,
+            running: c.running,
+            buffer_size: c.buffer_size,
+            stopping: c.stopping,
+        }
+    }
+}
+
+impl From<ServiceListener> for Listener {
+    fn from(l: ServiceListener) -> Self {
+        Listener {
+            name: l.name,
+            address: l.address,
+        }
+    }
+}
+
+impl From<ServiceServerStatus> for ServerStatus {
+    fn from(s: ServiceServerStatus) -> Self {
+        ServerStatus {
+            version: s.version,
+            uptime_seconds: s.uptime_seconds,
+            readiness: Some(Readiness::from(s.readiness)),
+            network: Some(NetworkStatus::from(s.network)),
+            cache: Some(CacheUsage::from(s.cache)),
+            commands: Some(CommandQueueStatus::from(s.commands)),
+            listeners: s.listeners.into_iter().map(Listener::from).collect(),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl HealthServiceTrait for HealthHandler {
+    async fn get_health(
+        &self,
+        _request: Request<GetHealthRequest>,
+    ) -> Result<Response<Liveness>, Status> {
+        Ok(Response::new(Liveness::from(self.health_service.get_liveness())))
+    }
+
+    async fn get_ready(
+        &self,
+        _request: Request<GetReadyRequest>,
+    ) -> Result<Response<Readiness>, Status> {
+        Ok(Response::new(Readiness::from(self.health_service.get_readiness().await)))
+    }
+
+    async fn get_status(
+        &self,
+        _request: Request<GetStatusRequest>,
+    ) -> Result<Response<ServerStatus>, Status> {
+        Ok(Response::new(ServerStatus::from(self.health_service.get_status().await)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_status_from_service() {
+        let server_status = ServerStatus::from(ServiceServerStatus {
+            version: "1.0.0".to_string(),
+            uptime_seconds: 10,
+            readiness: ServiceReadiness { ready: false, reasons: vec!["shutting down".to_string()] },
+            network: ServiceNetworkStatus {
+                backend: "autonomi".to_string(),
+                connected: false,
+                connecting: false,
+                last_connect_error: Some("no peers".to_string()),
+                idle_seconds: 5,
+                idle_disconnect: 30,
+            },
+            cache: ServiceCacheUsage { memory_usage: 1, memory_capacity: 2, disk_capacity_bytes: 3, disk_read_bytes: 4, disk_write_bytes: 5 },
+            commands: ServiceCommandQueueStatus { waiting: 6, running: 7, buffer_size: 8, stopping: true },
+            listeners: vec![ServiceListener::new("grpc", "0.0.0.0:18887".to_string())],
+        });
+        assert_eq!(server_status.version, "1.0.0");
+        let readiness = server_status.readiness.unwrap();
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons, vec!["shutting down".to_string()]);
+        let network = server_status.network.unwrap();
+        assert_eq!(network.backend, "autonomi");
+        assert_eq!(network.last_connect_error, Some("no peers".to_string()));
+        assert_eq!(network.idle_disconnect, 30);
+        let cache = server_status.cache.unwrap();
+        assert_eq!(cache.memory_usage, 1);
+        assert_eq!(cache.disk_write_bytes, 5);
+        let commands = server_status.commands.unwrap();
+        assert_eq!(commands.waiting, 6);
+        assert!(commands.stopping);
+        assert_eq!(server_status.listeners[0].name, "grpc");
+        assert_eq!(server_status.listeners[0].address, "0.0.0.0:18887");
+    }
+}
@@ -1,4 +1,15 @@
 #![cfg_attr(test, warn(unused_imports, unused_variables, dead_code))]
+#[cfg(not(grpc_disabled))]
+use tonic::Status;
+#[cfg(not(grpc_disabled))]
+use tonic::metadata::MetadataMap;
+#[cfg(not(grpc_disabled))]
+use crate::client::command::command_schedule::CommandSchedule;
+#[cfg(not(grpc_disabled))]
+use crate::client::command::command_wallet::{CommandWallet, API_KEY_HEADER};
+#[cfg(not(grpc_disabled))]
+use crate::service::wallet_service::WalletService;
+
 #[cfg(not(grpc_disabled))]
 pub mod archive_handler;
 #[cfg(not(grpc_disabled))]
@@ -29,3 +40,64 @@ pub mod resolver_handler;
 pub mod key_value_handler;
 #[cfg(not(grpc_disabled))]
 pub mod crypto_handler;
+#[cfg(not(grpc_disabled))]
+pub mod prefetch_handler;
+#[cfg(not(grpc_disabled))]
+pub mod cache_handler;
+#[cfg(not(grpc_disabled))]
+pub mod health_handler;
+#[cfg(not(grpc_disabled))]
+pub mod config_handler;
+#[cfg(not(grpc_disabled))]
+pub mod wallet_handler;
+
+/// Schedule for commands queued by a request, from its optional priority and RFC 3339 not before time.
+#[cfg(not(grpc_disabled))]
+#[allow(clippy::result_large_err)]
+pub fn parse_command_schedule(priority: Option<i32>, not_before: Option<String>) -> Result<CommandSchedule, Status> {
+    CommandSchedule::with_priority(priority, not_before.as_deref())
+        .map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+/// Wallet paying for commands queued by a request, selected by its optional `x-api-key` metadata.
+#[cfg(not(grpc_disabled))]
+#[allow(clippy::result_large_err)]
+pub fn parse_command_wallet(metadata: &MetadataMap, wallet_service: &WalletService) -> Result<Option<CommandWallet>, Status> {
+    let api_key = metadata.get(API_KEY_HEADER).and_then(|metadata_value| metadata_value.to_str().ok());
+    wallet_service.get_command_wallet(api_key)
+        .map_err(|e| Status::invalid_argument(e.to_string()))
+}
+
+#[cfg(all(test, not(grpc_disabled)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command_schedule() {
+        assert!(parse_command_schedule(None, None).unwrap().is_default());
+        assert_eq!(
+            parse_command_schedule(Some(5), Some("1970-01-01T00:00:01Z".to_string())).unwrap(),
+            CommandSchedule::new(5, Some(1000))
+        );
+        assert_eq!(parse_command_schedule(Some(5), Some("soon".to_string())).unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_parse_command_wallet() {
+        use actix_web::web::Data;
+        use clap::Parser;
+        use evmlib::Network::ArbitrumOne;
+        use crate::client::client_harness::ClientHarness;
+        use crate::client::wallet_ledger::WalletLedger;
+        use crate::config::anttp_config::AntTpConfig;
+
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--fake-network"]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let wallet_service = WalletService::new(client_harness, ant_tp_config, WalletLedger::new());
+
+        assert_eq!(parse_command_wallet(&MetadataMap::new(), &wallet_service).unwrap(), None);
+        let mut metadata = MetadataMap::new();
+        metadata.insert(API_KEY_HEADER, "key-a".parse().unwrap());
+        assert_eq!(parse_command_wallet(&metadata, &wallet_service).unwrap_err().code(), tonic::Code::InvalidArgument);
+    }
+}
@@ -0,0 +1,56 @@
+use tonic::{Request, Response, Status};
+use actix_web::web::Data;
+use crate::service::prefetch_service::{Prefetch, PrefetchService};
+
+pub mod prefetch_proto {
+    tonic::include_proto!("prefetch");
+}
+
+use prefetch_proto::prefetch_service_server::PrefetchService as PrefetchServiceTrait;
+pub use prefetch_proto::prefetch_service_server::PrefetchServiceServer;
+use prefetch_proto::{PrefetchRequest, PrefetchResponse};
+
+pub struct PrefetchHandler {
+    prefetch_service: Data<PrefetchService>,
+}
+
+impl PrefetchHandler {
+    pub fn new(prefetch_service: Data<PrefetchService>) -> Self {
+        Self { prefetch_service }
+    }
+}
+
+impl From<Prefetch> for PrefetchResponse {
+    fn from(p: Prefetch) -> Self {
+        PrefetchResponse {
+            address: p.address,
+            command_id: p.command_id,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl PrefetchServiceTrait for PrefetchHandler {
+    async fn prefetch(
+        &self,
+        request: Request<PrefetchRequest>,
+    ) -> Result<Response<PrefetchResponse>, Status> {
+        let req = request.into_inner();
+        let result = self.prefetch_service.prefetch(req.address).await?;
+
+        Ok(Response::new(PrefetchResponse::from(result)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefetch_response_from_service() {
+        let prefetch = Prefetch::new("0x123".to_string(), "42".to_string());
+        let response = PrefetchResponse::from(prefetch);
+        assert_eq!(response.address, "0x123");
+        assert_eq!(response.command_id, "42");
+    }
+}
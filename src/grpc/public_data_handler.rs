@@ -1,9 +1,12 @@
 use tonic::{Request, Response, Status};
 use actix_web::web::Data;
-use ant_core::data::Wallet;
 use crate::service::public_data_service::PublicDataService;
+use crate::service::wallet_service::WalletService;
+use crate::client::command::command_wallet::CommandWallet;
 use crate::controller::StoreType;
 use crate::error::public_data_error::PublicDataError;
+use crate::grpc::{parse_command_schedule, parse_command_wallet};
+use crate::model::quote::Quote as ServiceQuote;
 
 pub mod public_data_proto {
     tonic::include_proto!("public_data");
@@ -11,16 +14,16 @@ pub mod public_data_proto {
 
 use public_data_proto::public_service_server::PublicService as PublicServiceTrait;
 pub use public_data_proto::public_service_server::PublicServiceServer;
-use public_data_proto::{CreatePublicDataRequest, PublicDataResponse, GetPublicDataRequest, GetPublicDataResponse};
+use public_data_proto::{CreatePublicDataRequest, PublicDataResponse, GetPublicDataRequest, GetPublicDataResponse, Quote, QuotePublicDataRequest};
 
 pub struct PublicDataHandler {
     public_data_service: Data<PublicDataService>,
-    evm_wallet: Data<Wallet>,
+    wallet_service: Data<WalletService>,
 }
 
 impl PublicDataHandler {
-    pub fn new(public_data_service: Data<PublicDataService>, evm_wallet: Data<Wallet>) -> Self {
-        Self { public_data_service, evm_wallet }
+    pub fn new(public_data_service: Data<PublicDataService>, wallet_service: Data<WalletService>) -> Self {
+        Self { public_data_service, wallet_service }
     }
 }
 
@@ -30,18 +33,33 @@ impl From<PublicDataError> for Status {
     }
 }
 
+impl From<ServiceQuote> for Quote {
+    fn from(q: ServiceQuote) -> Self {
+        Quote {
+            address: q.address,
+            chunks: q.chunks,
+            bytes: q.bytes,
+            chunks_stored: q.chunks_stored,
+            bytes_stored: q.bytes_stored,
+            cost: q.cost,
+        }
+    }
+}
+
 #[tonic::async_trait]
 impl PublicServiceTrait for PublicDataHandler {
     async fn create_public_data(
         &self,
         request: Request<CreatePublicDataRequest>,
     ) -> Result<Response<PublicDataResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
 
-        let result = self.public_data_service.create_public_data(
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.public_data_service.create_public_data(
             req.data.into(),
             StoreType::from(req.store_type.unwrap_or_default()),
-        ).await?;
+        ))).await?;
 
         Ok(Response::new(PublicDataResponse {
             address: result.address.unwrap_or_default(),
@@ -59,6 +77,16 @@ impl PublicServiceTrait for PublicDataHandler {
             data: bytes.into(),
         }))
     }
+
+    async fn quote_public_data(
+        &self,
+        request: Request<QuotePublicDataRequest>,
+    ) -> Result<Response<Quote>, Status> {
+        let req = request.into_inner();
+        let result = self.public_data_service.quote_public_data(req.data.into()).await?;
+
+        Ok(Response::new(Quote::from(result)))
+    }
 }
 
 #[cfg(test)]
@@ -3,11 +3,12 @@ use actix_web::web::Data;
 use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::MultipartForm;
 use std::io::Write;
-use ant_core::data::Wallet;
 /*use crate::service::public_archive_service::{PublicArchiveForm, Upload};*/
 use crate::service::tarchive_service::TarchiveService;
 use crate::controller::StoreType;
 use crate::error::tarchive_error::TarchiveError;
+use crate::grpc::{parse_command_schedule, parse_command_wallet};
+use crate::model::quote::Quote as ServiceQuote;
 
 pub mod tarchive_proto {
     tonic::include_proto!("tarchive");
@@ -15,19 +16,21 @@ pub mod tarchive_proto {
 
 use tarchive_proto::tarchive_service_server::TarchiveService as TarchiveServiceTrait;
 pub use tarchive_proto::tarchive_service_server::TarchiveServiceServer;
-use tarchive_proto::{CreateTarchiveRequest, UpdateTarchiveRequest, TruncateTarchiveRequest, TarchiveResponse, File as ProtoFile, GetTarchiveRequest, GetTarchiveResponse, Item, ListTarchiveRequest, ListTarchiveResponse, PushTarchiveRequest};
+use tarchive_proto::{CreateTarchiveRequest, UpdateTarchiveRequest, TruncateTarchiveRequest, TarchiveResponse, File as ProtoFile, GetTarchiveRequest, GetTarchiveResponse, Item, ListTarchiveRequest, ListTarchiveResponse, PushTarchiveRequest, Quote, QuoteTarchiveRequest, QuoteUpdateTarchiveRequest};
 use crate::service::archive_service::{PublicArchiveForm, Upload};
 use crate::service::public_data_service::PublicDataService;
+use crate::service::wallet_service::WalletService;
+use crate::client::command::command_wallet::CommandWallet;
 
 pub struct TarchiveHandler {
     tarchive_service: Data<TarchiveService>,
     public_data_service: Data<PublicDataService>,
-    evm_wallet: Data<Wallet>,
+    wallet_service: Data<WalletService>,
 }
 
 impl TarchiveHandler {
-    pub fn new(tarchive_service: Data<TarchiveService>, public_data_service: Data<PublicDataService>, evm_wallet: Data<Wallet>) -> Self {
-        Self { tarchive_service, public_data_service, evm_wallet }
+    pub fn new(tarchive_service: Data<TarchiveService>, public_data_service: Data<PublicDataService>, wallet_service: Data<WalletService>) -> Self {
+        Self { tarchive_service, public_data_service, wallet_service }
     }
 
     fn map_to_multipart_form(&self, files: Vec<ProtoFile>) -> Result<MultipartForm<PublicArchiveForm>, Status> {
@@ -55,6 +58,7 @@ impl From<Upload> for TarchiveResponse {
     fn from(upload: Upload) -> Self {
         TarchiveResponse {
             address: upload.address,
+            batch_id: upload.batch_id,
         }
     }
 }
@@ -65,21 +69,35 @@ impl From<TarchiveError> for Status {
     }
 }
 
+impl From<ServiceQuote> for Quote {
+    fn from(q: ServiceQuote) -> Self {
+        Quote {
+            address: q.address,
+            chunks: q.chunks,
+            bytes: q.bytes,
+            chunks_stored: q.chunks_stored,
+            bytes_stored: q.bytes_stored,
+            cost: q.cost,
+        }
+    }
+}
+
 #[tonic::async_trait]
 impl TarchiveServiceTrait for TarchiveHandler {
     async fn create_tarchive(
         &self,
         request: Request<CreateTarchiveRequest>,
     ) -> Result<Response<TarchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
         let tarchive_form = self.map_to_multipart_form(req.files)?;
-        
-        let result = self.tarchive_service.create_tarchive(
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
+
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.tarchive_service.create_tarchive(
             req.path,
             tarchive_form,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(req.store_type.unwrap_or_default())
-        ).await?;
+        ))).await?;
 
         Ok(Response::new(TarchiveResponse::from(result)))
     }
@@ -88,16 +106,17 @@ impl TarchiveServiceTrait for TarchiveHandler {
         &self,
         request: Request<UpdateTarchiveRequest>,
     ) -> Result<Response<TarchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
         let tarchive_form = self.map_to_multipart_form(req.files)?;
-        
-        let result = self.tarchive_service.update_tarchive(
+        let command_schedule = parse_command_schedule(req.priority, req.not_before)?;
+
+        let result = CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.tarchive_service.update_tarchive(
             req.address,
             req.path,
             tarchive_form,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(req.store_type.unwrap_or_default())
-        ).await?;
+        ))).await?;
 
         Ok(Response::new(TarchiveResponse::from(result)))
     }
@@ -106,14 +125,14 @@ impl TarchiveServiceTrait for TarchiveHandler {
         &self,
         request: Request<TruncateTarchiveRequest>,
     ) -> Result<Response<TarchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
         
-        let result = self.tarchive_service.truncate_tarchive(
+        let result = CommandWallet::scope_optional(command_wallet, self.tarchive_service.truncate_tarchive(
             req.address,
             req.path,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(req.store_type.unwrap_or_default())
-        ).await?;
+        )).await?;
 
         Ok(Response::new(TarchiveResponse::from(result)))
     }
@@ -163,16 +182,40 @@ impl TarchiveServiceTrait for TarchiveHandler {
         &self,
         request: Request<PushTarchiveRequest>,
     ) -> Result<Response<TarchiveResponse>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
         let req = request.into_inner();
-        let result = self.public_data_service.push_public_data(
+        let result = CommandWallet::scope_optional(command_wallet, self.public_data_service.push_public_data(
             req.address,
             StoreType::from(req.store_type.unwrap_or_else(|| "network".to_string()))
-        ).await.map_err(|e| Status::internal(e.to_string()))?;
+        )).await.map_err(|e| Status::internal(e.to_string()))?;
 
         Ok(Response::new(TarchiveResponse {
             address: result.address,
+            batch_id: None,
         }))
     }
+
+    async fn quote_tarchive(
+        &self,
+        request: Request<QuoteTarchiveRequest>,
+    ) -> Result<Response<Quote>, Status> {
+        let req = request.into_inner();
+        let tarchive_form = self.map_to_multipart_form(req.files)?;
+        let result = self.tarchive_service.quote_tarchive(req.path, tarchive_form).await?;
+
+        Ok(Response::new(Quote::from(result)))
+    }
+
+    async fn quote_update_tarchive(
+        &self,
+        request: Request<QuoteUpdateTarchiveRequest>,
+    ) -> Result<Response<Quote>, Status> {
+        let req = request.into_inner();
+        let tarchive_form = self.map_to_multipart_form(req.files)?;
+        let result = self.tarchive_service.quote_tarchive_update(req.address, req.path, tarchive_form).await?;
+
+        Ok(Response::new(Quote::from(result)))
+    }
 }
 
 #[cfg(test)]
@@ -183,8 +226,10 @@ mod tests {
     async fn test_mapping() {
         let upload = Upload {
             address: Some("0x1234".to_string()),
+            batch_id: Some("batch1".to_string()),
         };
         let response = TarchiveResponse::from(upload);
         assert_eq!(response.address, Some("0x1234".to_string()));
+        assert_eq!(response.batch_id, Some("batch1".to_string()));
     }
 }
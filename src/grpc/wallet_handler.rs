@@ -0,0 +1,115 @@
+use tonic::{Request, Response, Status};
+use actix_web::web::Data;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::error::wallet_error::WalletError;
+use crate::grpc::parse_command_wallet;
+use crate::service::wallet_service::{WalletDetails as ServiceWalletDetails, WalletService, WalletSpend as ServiceWalletSpend};
+
+pub mod wallet_proto {
+    tonic::include_proto!("wallet");
+}
+
+use wallet_proto::wallet_service_server::WalletService as WalletServiceTrait;
+pub use wallet_proto::wallet_service_server::WalletServiceServer;
+use wallet_proto::{GetWalletKeystoreRequest, GetWalletRequest, GetWalletSpendRequest, LoadWalletRequest, WalletDetails, WalletKeystore, WalletSpend, WalletSpends};
+
+pub struct WalletHandler {
+    wallet_service: Data<WalletService>,
+}
+
+impl WalletHandler {
+    pub fn new(wallet_service: Data<WalletService>) -> Self {
+        Self { wallet_service }
+    }
+}
+
+impl From<ServiceWalletDetails> for WalletDetails {
+    fn from(w: ServiceWalletDetails) -> Self {
+        WalletDetails {
+            address: w.address,
+            network: w.network,
+            token_balance: w.token_balance,
+            gas_balance: w.gas_balance,
+            payment_vault_address: w.payment_vault_address,
+            payment_allowance: w.payment_allowance,
+            payment_approved: w.payment_approved,
+        }
+    }
+}
+
+impl From<ServiceWalletSpend> for WalletSpend {
+    fn from(w: ServiceWalletSpend) -> Self {
+        WalletSpend {
+            name: w.name,
+            uploads: w.uploads,
+            bytes_uploaded: w.bytes_uploaded,
+            storage_cost: w.storage_cost,
+            gas_cost: w.gas_cost,
+        }
+    }
+}
+
+impl From<WalletError> for Status {
+    fn from(wallet_error: WalletError) -> Self {
+        Status::internal(wallet_error.to_string())
+    }
+}
+
+#[tonic::async_trait]
+impl WalletServiceTrait for WalletHandler {
+    async fn get_wallet(
+        &self,
+        request: Request<GetWalletRequest>,
+    ) -> Result<Response<WalletDetails>, Status> {
+        let command_wallet = parse_command_wallet(request.metadata(), &self.wallet_service)?;
+        Ok(Response::new(WalletDetails::from(CommandWallet::scope_optional(command_wallet, self.wallet_service.get_wallet()).await?)))
+    }
+
+    async fn get_wallet_keystore(
+        &self,
+        _request: Request<GetWalletKeystoreRequest>,
+    ) -> Result<Response<WalletKeystore>, Status> {
+        Ok(Response::new(WalletKeystore { names: self.wallet_service.get_wallets().await? }))
+    }
+
+    async fn load_wallet(
+        &self,
+        request: Request<LoadWalletRequest>,
+    ) -> Result<Response<WalletDetails>, Status> {
+        let req = request.into_inner();
+        Ok(Response::new(WalletDetails::from(self.wallet_service.load_wallet(req.name).await?)))
+    }
+
+    async fn get_wallet_spend(
+        &self,
+        _request: Request<GetWalletSpendRequest>,
+    ) -> Result<Response<WalletSpends>, Status> {
+        let wallets = self.wallet_service.get_wallet_spends().into_iter().map(WalletSpend::from).collect();
+        Ok(Response::new(WalletSpends { wallets }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_details_from_service() {
+        let wallet_details = WalletDetails::from(ServiceWalletDetails {
+            address: "0x01".to_string(),
+            network: "arbitrum-one".to_string(),
+            token_balance: "100".to_string(),
+            gas_balance: "200".to_string(),
+            payment_vault_address: "0x02".to_string(),
+            payment_allowance: "0".to_string(),
+            payment_approved: false,
+        });
+        assert_eq!(wallet_details.address, "0x01");
+        assert_eq!(wallet_details.network, "arbitrum-one");
+        assert_eq!(wallet_details.token_balance, "100");
+        assert_eq!(wallet_details.gas_balance, "200");
+        assert_eq!(wallet_details.payment_vault_address, "0x02");
+        assert_eq!(wallet_details.payment_allowance, "0");
+        assert!(!wallet_details.payment_approved);
+    }
+}
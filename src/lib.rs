@@ -16,7 +16,9 @@ use actix_web::{middleware, middleware::Logger, web, App, HttpServer};
 /*use ant_evm::EvmNetwork::{ArbitrumOne, ArbitrumSepoliaTest};
 use ant_core::data::Wallet;
 use autonomi::Network;*/
-use config::anttp_config::AntTpConfig;
+use config::anttp_config::{AntTpCommand, AntTpConfig};
+use config::content_overrides::ContentOverrides;
+use config::key_store::KeyStore;
 use log::info;
 use once_cell::sync::Lazy;
 use std::{env, io};
@@ -25,7 +27,6 @@ use std::sync::Arc;
 use std::time::Duration;
 use actix_web::http::Method;
 use async_job::Runner;
-use evmlib::Network::{ArbitrumOne, ArbitrumSepoliaTest};
 use evmlib::wallet::Wallet;
 use foyer::{BlockEngineConfig, Compression, DeviceBuilder, FsDeviceBuilder, HybridCache, HybridCacheBuilder, HybridCachePolicy, LfuConfig, PsyncIoEngineConfig, RecoverMode};
 use indexmap::IndexMap;
@@ -34,7 +35,7 @@ use rmcp_actix_web::transport::{StreamableHttpService};
 use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
 #[cfg(not(grpc_disabled))]
 use tokio::sync::oneshot;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 #[cfg(not(grpc_disabled))]
 use tonic::transport::Server;
 use utoipa::OpenApi;
@@ -65,10 +66,20 @@ use crate::client::client_harness::ClientHarness;
 use client::command::executor::Executor;
 /*use crate::client::command::access_checker::update_access_checker_command::UpdateAccessCheckerCommand;
 use crate::client::command::bookmark_resolver::update_bookmark_resolver_command::UpdateBookmarkResolverCommand;*/
-use crate::client::command::command_details::CommandDetails;
-/*#[double]
+use crate::client::command::command_control::CommandControl;
+use crate::client::command::command_details::{CommandDetails, CommandState};
+use crate::client::command::command_events::CommandEvents;
+use crate::client::command::Command;
+use crate::client::command::command_journal::{CommandJournal, CommandRestorer};
+use crate::client::command::scheduler::CommandConcurrency;
+use crate::client::command::chunk::create_chunk_command::{self, CreateChunkCommand};
+use crate::client::command::public_data::create_public_data_command::{self, CreatePublicDataCommand};
+use crate::client::command::prefetch::prefetch_command::{self, PrefetchCommand};
+#[double]
 use crate::service::access_checker::AccessChecker;
 #[double]
+use crate::service::access_list_service::AccessListService;
+/*#[double]
 use crate::service::bookmark_resolver::BookmarkResolver;
 #[double]
 use crate::service::pointer_name_resolver::PointerNameResolver;
@@ -77,6 +88,18 @@ use crate::service::crypto_service::{CryptoService, Crypto, CryptoContent};
 /*use crate::service::key_value_service::KeyValueService;*/
 use crate::service::chunk_service::{Chunk, ChunkService};
 use crate::service::command_service::CommandService;
+use crate::service::command_webhook_service::CommandWebhookService;
+use crate::client::wallet_ledger::WalletLedger;
+use crate::service::prefetch_service::{Prefetch, PrefetchService};
+use crate::service::cache_service::{CacheService, CacheStats};
+use crate::service::health_service::{CacheUsage, CommandQueueStatus, HealthService, Listener, Liveness, Readiness, ServerStatus};
+use crate::service::wallet_service::{WalletDetails, WalletLoad, WalletService, WalletSpend};
+use crate::service::config_service::{ConfigReload, ConfigService};
+use crate::client::client_harness::NetworkStatus;
+use crate::service::bundle_service::{BundleExport, BundleImport, BundleManifest, BundleService};
+use crate::client::negative_cache::NegativeCacheStats;
+use crate::client::cache_integrity::CacheIntegrityStats;
+use crate::client::chunk_fetch_policy::ChunkFetchStats;
 #[double]
 use crate::service::file_service::FileService;
 /*use crate::service::graph_service::GraphService;
@@ -85,6 +108,7 @@ use crate::service::public_archive_service::{PublicArchiveForm, PublicArchiveSer
 use crate::service::archive_service::{ArchiveService, ArchiveForm, PublicArchiveForm, Upload, ArchiveResponse};
 use crate::model::archive::ArchiveType;
 use crate::model::resolve::Resolve;
+use crate::model::quote::Quote;
 use crate::service::tarchive_service::TarchiveService;
 use crate::service::public_data_service::PublicDataService;
 /*use crate::service::register_service::RegisterService;*/
@@ -122,12 +146,25 @@ use crate::grpc::resolver_handler::{ResolverHandler, ResolverServiceServer};
 use crate::grpc::key_value_handler::{KeyValueHandler, KeyValueServiceServer};*/
 #[cfg(not(grpc_disabled))]
 use crate::grpc::crypto_handler::{CryptoHandler, CryptoServiceServer};
+#[cfg(not(grpc_disabled))]
+use crate::grpc::prefetch_handler::{PrefetchHandler, PrefetchServiceServer};
+#[cfg(not(grpc_disabled))]
+use crate::grpc::cache_handler::{CacheHandler, CacheServiceServer};
+#[cfg(not(grpc_disabled))]
+use crate::grpc::health_handler::{HealthHandler, HealthServiceServer};
+#[cfg(not(grpc_disabled))]
+use crate::grpc::wallet_handler::{WalletHandler, WalletServiceServer};
+#[cfg(not(grpc_disabled))]
+use crate::grpc::config_handler::{ConfigHandler, ConfigServiceServer};
 
 static ACTIX_SERVER_HANDLE: Lazy<Mutex<Option<ServerHandle>>> = Lazy::new(|| Mutex::new(None));
 #[cfg(not(grpc_disabled))]
 static TONIC_SERVER_SHUTDOWN_TX: Lazy<Mutex<Option<oneshot::Sender<()>>>> = Lazy::new(|| Mutex::new(None));
+static SHUTDOWN_COMPLETE: Lazy<Notify> = Lazy::new(Notify::new);
 
 const API_BASE: &'static str = "/anttp-0/";
+const COMMAND_JOURNAL_DIRECTORY: &str = "command_journal";
+const COMMAND_WEBHOOK_TIMEOUT_SECS: u64 = 10;
 
 // Wiring instances conflicts with mockall - ignore testing for this function
 #[cfg(not(test))]
@@ -176,6 +213,33 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
             public_data_controller::post_public_data,
             public_data_controller::push_public_data,
             command_controller::get_commands,
+            command_controller::get_command_events,
+            command_controller::get_command,
+            command_controller::delete_command,
+            command_controller::post_command_retry,
+            command_controller::delete_commands,
+            command_controller::get_command_batch,
+            command_controller::post_command_batch_retry,
+            prefetch_controller::post_prefetch,
+            cache_controller::get_cache_stats,
+            cache_controller::post_cache_export,
+            chunk_controller::post_chunk_quote,
+            public_data_controller::post_public_data_quote,
+            tarchive_controller::post_tarchive_quote_root,
+            tarchive_controller::post_tarchive_quote,
+            tarchive_controller::put_tarchive_quote_root,
+            tarchive_controller::put_tarchive_quote,
+            archive_controller::put_archive_quote_root,
+            archive_controller::put_archive_quote,
+            cache_controller::post_cache_import,
+            health_controller::get_health,
+            health_controller::get_ready,
+            health_controller::get_status,
+            config_controller::post_config_reload,
+            wallet_controller::get_wallet,
+            wallet_controller::get_wallet_keystore,
+            wallet_controller::get_wallet_spend,
+            wallet_controller::put_wallet,
             /*pnr_controller::get_pnr,
             pnr_controller::post_mutable_pnr,
             pnr_controller::post_immutable_pnr,
@@ -193,11 +257,16 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
             crypto_controller::post_decrypt*/
         ),
         components(
-            schemas(PublicArchiveForm, ArchiveForm, Upload, ArchiveResponse, Chunk, ArchiveType, Resolve, Crypto, CryptoContent)
+            schemas(PublicArchiveForm, ArchiveForm, Upload, ArchiveResponse, Chunk, ArchiveType, Resolve, Crypto, CryptoContent, Prefetch, CacheStats, NegativeCacheStats, CacheIntegrityStats, ChunkFetchStats, BundleExport, BundleManifest, BundleImport, Liveness, Readiness, ServerStatus, NetworkStatus, CacheUsage, CommandQueueStatus, Listener, ConfigReload, WalletDetails, WalletLoad, WalletSpend, Quote)
         )
     )]
     struct ApiDoc;
 
+    // manage keys in the key store instead of starting the server
+    if let Some(command) = ant_tp_config.command.clone().filter(AntTpCommand::is_key_command) {
+        return run_key_command(command, &ant_tp_config);
+    }
+
     let listen_address = ant_tp_config.listen_address.clone();
     let https_listen_address = ant_tp_config.https_listen_address.clone();
     #[cfg(not(grpc_disabled))]
@@ -205,29 +274,35 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
     let wallet_private_key = ant_tp_config.wallet_private_key.clone();
 
     // initialise safe network connection
-    // todo: enable local with autonomi 2.0
-    let evm_network = match ant_tp_config.evm_network.to_lowercase().as_str() {
-        //"local" => Network::new(true).unwrap(),
-        "arbitrumsepoliatest" => ArbitrumSepoliaTest,
-        _ => ArbitrumOne
-    };
+    let evm_network = ant_tp_config.get_evm_network().map_err(io::Error::other)?;
+    info!("Using EVM network [{}]{}", evm_network, if ant_tp_config.is_local_network() { " on a local network" } else { "" });
 
-    let client_harness_data = Data::new(Mutex::new(ClientHarness::new(evm_network.clone(), ant_tp_config.clone())));
-
-    let evm_wallet_data = if !wallet_private_key.is_empty() {
-        Data::new(Wallet::new_from_private_key(evm_network, wallet_private_key.as_str())
-            .expect("Failed to instantiate EvmWallet."))
+    let evm_wallet = if !wallet_private_key.is_empty() {
+        Wallet::new_from_private_key(evm_network.clone(), wallet_private_key.as_str())
+            .map_err(|e| io::Error::other(format!("Failed to instantiate EvmWallet: {}", e)))?
     } else {
-        Data::new(Wallet::new_with_random_wallet(evm_network))
+        Wallet::new_with_random_wallet(evm_network.clone())
     };
+
+    let client_harness = ClientHarness::new(evm_network, ant_tp_config.clone());
+    client_harness.set_wallet(evm_wallet);
+    let client_harness_data = Data::new(client_harness);
     
     let hybrid_cache_data: Data<HybridCache<String, Vec<u8>>> = Data::new(build_foyer_cache(&ant_tp_config).await);
 
     let command_status_data = Data::new(Mutex::new(IndexMap::<u128, CommandDetails>::with_capacity(ant_tp_config.command_buffer_size * 2)));
-    let command_executor = Executor::start(ant_tp_config.command_buffer_size, command_status_data.clone()).await;
+    let command_journal = CommandJournal::new(Path::new(ant_tp_config.map_cache_directory.as_str()).join(COMMAND_JOURNAL_DIRECTORY))?;
+    let command_concurrency = CommandConcurrency::new(ant_tp_config.command_concurrency, ant_tp_config.command_type_concurrency.iter().cloned().collect());
+    let command_control = CommandControl::new(ant_tp_config.command_buffer_size);
+    let command_events = CommandEvents::new(ant_tp_config.command_buffer_size);
+    CommandWebhookService::new(ant_tp_config.command_webhook_urls.clone(), ant_tp_config.command_webhook_secret.clone(), Duration::from_secs(COMMAND_WEBHOOK_TIMEOUT_SECS))
+        .start(&command_events);
+    let wallet_ledger = WalletLedger::new();
+    wallet_ledger.start(&command_events);
+    let command_executor = Executor::start(ant_tp_config.command_buffer_size, command_concurrency, Duration::from_secs(ant_tp_config.command_dedup_window), command_status_data.clone(), command_journal.clone(), command_control.clone(), command_events.clone()).await;
     let command_executor_data = Data::new(command_executor.clone());
 
-    let caching_client = CachingClient::new(client_harness_data, ant_tp_config.clone(), hybrid_cache_data.clone(), command_executor_data.clone());
+    let caching_client = CachingClient::new(client_harness_data.clone(), ant_tp_config.clone(), hybrid_cache_data.clone(), command_executor_data.clone(), command_journal.clone());
     let caching_client_data = Data::new(caching_client.clone());
 
     let chunk_caching_client = ChunkCachingClient::new(caching_client.clone());
@@ -249,12 +324,29 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
     let access_checker_data = hydrate_access_checker(
         &ant_tp_config, &command_executor, &caching_client, &streaming_client, &bookmark_resolver_data, &pointer_name_resolver_data).await;*/
 
+    let access_checker_data = Data::new(Mutex::new(AccessChecker::new()));
     let resolver_service_data = Data::new(
-        ResolverService::new(archive_caching_client.clone(), /*pointer_caching_client.clone(), register_caching_client.clone(), access_checker_data.clone(), bookmark_resolver_data.clone(), pointer_name_resolver_data.clone(),*/ ant_tp_config.cached_mutable_ttl)
+        ResolverService::new(archive_caching_client.clone(), /*pointer_caching_client.clone(), register_caching_client.clone(),*/ access_checker_data.clone(), /*bookmark_resolver_data.clone(), pointer_name_resolver_data.clone(),*/ ant_tp_config.cached_mutable_ttl)
     );
 
-    // schedule idle disconnects for client_harness
-    Runner::new().add(Box::new(caching_client_data.get_ref().clone())).run().await;
+    let bundle_service_data = Data::new(BundleService::new(
+        chunk_caching_client.clone(),
+        archive_caching_client.clone(),
+        resolver_service_data.get_ref().clone()
+    ));
+
+    // run one-off commands against the cache instead of starting the server
+    if let Some(command) = ant_tp_config.command.clone() {
+        let result = run_command(command, &bundle_service_data, &ant_tp_config).await;
+        hybrid_cache_data.close().await.map_err(io::Error::other)?;
+        return result;
+    }
+
+    // schedule idle disconnects for client_harness and scrubbing of cached chunks
+    Runner::new()
+        .add(Box::new(caching_client_data.get_ref().clone()))
+        .add(Box::new(caching_client.get_cache_integrity().clone()))
+        .run().await;
 
     // define services
     /*let public_archive_service_data = Data::new(PublicArchiveService::new(
@@ -264,6 +356,8 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
         resolver_service_data.get_ref().clone()
     ));*/
     let file_service = FileService::new(chunk_caching_client.clone(), ant_tp_config.download_threads);
+    let access_list_service = AccessListService::new(resolver_service_data.get_ref().clone(), file_service.clone(), access_checker_data.clone());
+    let content_overrides_data = Data::new(ContentOverrides::new(&ant_tp_config.route_overrides, &ant_tp_config.mime_overrides));
     let tarchive_service_data = Data::new(TarchiveService::new(
         PublicDataService::new(public_data_caching_client.clone(), resolver_service_data.get_ref().clone()),
         tarchive_caching_client.clone(),
@@ -271,7 +365,7 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
         resolver_service_data.get_ref().clone(),
         ant_tp_config.clone()
     ));
-    let command_service_data = Data::new(CommandService::new(command_status_data.clone()));
+    let command_service_data = Data::new(CommandService::new(command_status_data.clone(), command_control.clone(), command_executor_data.clone(), command_journal.clone(), command_events.clone()));
     let chunk_service_data = Data::new(ChunkService::new(chunk_caching_client.clone(), resolver_service_data.get_ref().clone()));
     //let graph_service_data = Data::new(GraphService::new(graph_entry_caching_client.clone(), ant_tp_config.clone(), resolver_service_data.get_ref().clone()));
     //let pointer_service_data = Data::new(PointerService::new(pointer_caching_client.clone(), ant_tp_config.clone(), resolver_service_data.get_ref().clone()));
@@ -283,11 +377,89 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
         tarchive_service_data.get_ref().clone(),
         resolver_service_data.get_ref().clone(),
         archive_caching_client.clone(),
-        file_service.clone()
+        file_service.clone(),
+        content_overrides_data.get_ref().clone()
     ));
     //let pnr_service_data = Data::new(PnrService::new(chunk_caching_client.clone(), pointer_service_data.clone()));
     //let key_value_service_data = Data::new(KeyValueService::new(public_data_service_data.clone(), pnr_service_data.clone()));
     let crypto_service_data = Data::new(CryptoService::new(ant_tp_config.clone()));
+    let prefetch_service_data = Data::new(PrefetchService::new(
+        caching_client.clone(),
+        chunk_caching_client.clone(),
+        archive_caching_client.clone(),
+        resolver_service_data.get_ref().clone(),
+        ant_tp_config.download_threads
+    ));
+    let cache_service_data = Data::new(CacheService::new(caching_client.clone()));
+
+    // listeners reported by the status endpoints
+    let mut listeners = vec![Listener::new("http", listen_address.to_string())];
+    if !ant_tp_config.uploads_disabled && !ant_tp_config.mcp_tools_disabled {
+        listeners.push(Listener::new("mcp", format!("{}/mcp-0", listen_address)));
+    }
+    #[cfg(not(grpc_disabled))]
+    if !ant_tp_config.grpc_disabled && !ant_tp_config.uploads_disabled {
+        listeners.push(Listener::new("grpc", grpc_listen_address.to_string()));
+    }
+    let health_service_data = Data::new(HealthService::new(
+        client_harness_data.clone(),
+        hybrid_cache_data.clone(),
+        command_status_data.clone(),
+        command_control.clone(),
+        ant_tp_config.clone(),
+        listeners
+    ));
+    let wallet_service_data = Data::new(WalletService::new(client_harness_data.clone(), ant_tp_config.clone(), wallet_ledger));
+    wallet_service_data.load_api_key_wallets(ant_tp_config.wallet_api_keys.clone()).await.map_err(io::Error::other)?;
+    let config_service_data = Data::new(ConfigService::new(
+        env::args().collect(),
+        ant_tp_config.clone(),
+        caching_client.get_negative_cache().clone(),
+        resolver_service_data.get_ref().clone(),
+        access_list_service.clone(),
+        content_overrides_data.get_ref().clone(),
+        wallet_service_data.clone()
+    ));
+
+    // load the access list in the background, as it is fetched from the network
+    if !ant_tp_config.access_list_address.is_empty() {
+        let access_list_address = ant_tp_config.access_list_address.clone();
+        tokio::spawn(async move {
+            match access_list_service.get_access_list(&access_list_address).await {
+                Ok(access_list) => access_list_service.set_access_list(&access_list).await,
+                Err(e) => log::warn!("Failed to load access list from [{}], allowing all addresses: [{}]", access_list_address, e),
+            }
+        });
+    }
+
+    // reload the configuration on SIGHUP, as well as on request
+    #[cfg(unix)]
+    {
+        let config_service_data = config_service_data.clone();
+        let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                info!("Received SIGHUP, reloading configuration");
+                if let Err(e) = config_service_data.reload().await {
+                    log::error!("Failed to reload configuration: [{}]", e);
+                }
+            }
+        });
+    }
+
+    // replay commands which were waiting or running when AntTP last stopped
+    let command_restorers = create_command_restorers(&client_harness_data, &chunk_caching_client, &archive_caching_client, ant_tp_config.download_threads);
+    for command in command_journal.replay(&command_restorers).await {
+        command_executor.send(command).await.map_err(io::Error::other)?;
+    }
+
+    // warm the cache with addresses listed in the prefetch file
+    if !ant_tp_config.prefetch_file.is_empty() {
+        match prefetch_service_data.prefetch_file(ant_tp_config.prefetch_file.as_str()).await {
+            Ok(prefetches) => info!("Queued [{}] prefetch commands from [{}]", prefetches.len(), ant_tp_config.prefetch_file),
+            Err(e) => log::error!("Failed to queue prefetch commands: [{:?}]", e),
+        }
+    }
 
     // MCP
     let mcp_tool = McpTool::new(
@@ -305,7 +477,9 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
         resolver_service_data.clone(),
         /*key_value_service_data.clone(),*/
         crypto_service_data.clone(),
-        evm_wallet_data.clone()
+        prefetch_service_data.clone(),
+        cache_service_data.clone(),
+        wallet_service_data.clone()
     );
     let mcp_tool_service = StreamableHttpService::builder()
         .service_factory(Arc::new(move || { Ok(mcp_tool.clone()) }))
@@ -319,19 +493,24 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
     if !ant_tp_config.grpc_disabled && !ant_tp_config.uploads_disabled {
         //let pointer_handler = PointerHandler::new(pointer_service_data.clone(), evm_wallet_data.clone());
         //let register_handler = RegisterHandler::new(register_service_data.clone(), evm_wallet_data.clone());
-        let chunk_handler = ChunkHandler::new(chunk_service_data.clone(), evm_wallet_data.clone());
+        let chunk_handler = ChunkHandler::new(chunk_service_data.clone(), wallet_service_data.clone());
         //let graph_handler = GraphHandler::new(graph_service_data.clone(), evm_wallet_data.clone());
         let command_handler = CommandHandler::new(command_service_data.clone());
         //let pnr_handler = PnrHandler::new(pnr_service_data.clone(), evm_wallet_data.clone());
-        let public_data_handler = PublicDataHandler::new(public_data_service_data.clone(), evm_wallet_data.clone());
+        let public_data_handler = PublicDataHandler::new(public_data_service_data.clone(), wallet_service_data.clone());
         //let public_archive_handler = PublicArchiveHandler::new(public_archive_service_data.clone(), evm_wallet_data.clone());
-        let archive_handler = ArchiveHandler::new(archive_service_data.clone(), evm_wallet_data.clone());
-        let tarchive_handler = TarchiveHandler::new(tarchive_service_data.clone(), public_data_service_data.clone(), evm_wallet_data.clone());
+        let archive_handler = ArchiveHandler::new(archive_service_data.clone(), wallet_service_data.clone());
+        let tarchive_handler = TarchiveHandler::new(tarchive_service_data.clone(), public_data_service_data.clone(), wallet_service_data.clone());
         //let private_scratchpad_handler = PrivateScratchpadHandler::new(scratchpad_service_data.clone(), evm_wallet_data.clone());
         //let public_scratchpad_handler = PublicScratchpadHandler::new(scratchpad_service_data.clone(), evm_wallet_data.clone());
         let resolver_handler = ResolverHandler::new(resolver_service_data.clone());
         //let key_value_handler = KeyValueHandler::new(key_value_service_data.clone(), evm_wallet_data.clone());
         let crypto_handler = CryptoHandler::new(crypto_service_data.clone());
+        let prefetch_handler = PrefetchHandler::new(prefetch_service_data.clone());
+        let cache_handler = CacheHandler::new(cache_service_data.clone());
+        let health_handler = HealthHandler::new(health_service_data.clone());
+        let wallet_handler = WalletHandler::new(wallet_service_data.clone());
+        let config_handler = ConfigHandler::new(config_service_data.clone());
 
         let (tx, rx) = oneshot::channel::<()>();
         {
@@ -357,6 +536,11 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
                 .add_service(ResolverServiceServer::new(resolver_handler))
                 //.add_service(KeyValueServiceServer::new(key_value_handler))
                 .add_service(CryptoServiceServer::new(crypto_handler))
+                .add_service(PrefetchServiceServer::new(prefetch_handler))
+                .add_service(CacheServiceServer::new(cache_handler))
+                .add_service(HealthServiceServer::new(health_handler))
+                .add_service(WalletServiceServer::new(wallet_handler))
+                .add_service(ConfigServiceServer::new(config_handler))
                 .serve_with_shutdown(grpc_listen_address, async {
                     rx.await.ok();
                 })
@@ -376,11 +560,16 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
         info!("Tonic (gRPC) listener disabled (not built)");
     }
 
+    let shutdown_command_status_data = command_status_data.clone();
+    let shutdown_hybrid_cache_data = hybrid_cache_data.clone();
     let actix_config = ant_tp_config.clone();
     let actix_server = HttpServer::new(move || {
         let logger = Logger::default();
 
         let mut app = App::new()
+            .wrap(middleware::from_fn(controller::command_schedule))
+            .wrap(middleware::from_fn(controller::command_batch))
+            .wrap(middleware::from_fn(controller::command_wallet))
             .wrap(logger)
             .wrap(middleware::Compress::default()) // enable compression
             .service(
@@ -435,6 +624,46 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
                 format!("{}command", API_BASE).as_str(),
                 web::get().to(command_controller::get_commands)
             )
+            .route(
+                format!("{}command/events", API_BASE).as_str(),
+                web::get().to(command_controller::get_command_events)
+            )
+            .route(
+                format!("{}command/batch/{{id}}", API_BASE).as_str(),
+                web::get().to(command_controller::get_command_batch)
+            )
+            .route(
+                format!("{}command/{{id}}", API_BASE).as_str(),
+                web::get().to(command_controller::get_command)
+            )
+            .route(
+                format!("{}prefetch/{{address}}", API_BASE).as_str(),
+                web::post().to(prefetch_controller::post_prefetch)
+            )
+            .route(
+                format!("{}cache/stats", API_BASE).as_str(),
+                web::get().to(cache_controller::get_cache_stats)
+            )
+            .route(
+                format!("{}health", API_BASE).as_str(),
+                web::get().to(health_controller::get_health)
+            )
+            .route(
+                format!("{}ready", API_BASE).as_str(),
+                web::get().to(health_controller::get_ready)
+            )
+            .route(
+                format!("{}status", API_BASE).as_str(),
+                web::get().to(health_controller::get_status)
+            )
+            .route(
+                format!("{}wallet", API_BASE).as_str(),
+                web::get().to(wallet_controller::get_wallet)
+            )
+            .route(
+                format!("{}wallet/spend", API_BASE).as_str(),
+                web::get().to(wallet_controller::get_wallet_spend)
+            )
             /*.route(
                 format!("{}key_value/{{bucket}}/{{object}}", API_BASE).as_str(),
                 web::get().to(key_value_controller::get_key_value)
@@ -487,18 +716,9 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
                 format!("{}crypto/decrypt", API_BASE).as_str(),
                 web::post().to(crypto_controller::post_decrypt)
             )*/
-            .route(
-                "/{path:.*}",
-                web::get().to(file_controller::get_public_data),
-            )
-            .route(
-                "/{path:.*}",
-                web::head().to(file_controller::head_public_data),
-            )
             .app_data(Data::new(actix_config.clone()))
             .app_data(caching_client_data.clone())
             .app_data(streaming_client_data.clone())
-            .app_data(evm_wallet_data.clone())
             .app_data(hybrid_cache_data.clone())
             .app_data(command_status_data.clone())
             //.app_data(access_checker_data.clone())
@@ -509,6 +729,13 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
             //.app_data(graph_service_data.clone())
             //.app_data(pointer_service_data.clone())
             .app_data(crypto_service_data.clone())
+            .app_data(content_overrides_data.clone())
+            .app_data(prefetch_service_data.clone())
+            .app_data(cache_service_data.clone())
+            .app_data(health_service_data.clone())
+            .app_data(wallet_service_data.clone())
+            .app_data(config_service_data.clone())
+            .app_data(bundle_service_data.clone())
             //.app_data(public_archive_service_data.clone())
             .app_data(tarchive_service_data.clone())
             .app_data(archive_service_data.clone())
@@ -545,6 +772,74 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
                     format!("{}binary/chunk", API_BASE).as_str(),
                     web::post().to(chunk_controller::post_chunk_binary),
                 )
+                .route(
+                    format!("{}command", API_BASE).as_str(),
+                    web::delete().to(command_controller::delete_commands),
+                )
+                .route(
+                    format!("{}command/batch/{{id}}/retry", API_BASE).as_str(),
+                    web::post().to(command_controller::post_command_batch_retry),
+                )
+                .route(
+                    format!("{}command/{{id}}", API_BASE).as_str(),
+                    web::delete().to(command_controller::delete_command),
+                )
+                .route(
+                    format!("{}command/{{id}}/retry", API_BASE).as_str(),
+                    web::post().to(command_controller::post_command_retry),
+                )
+                .route(
+                    format!("{}cache/export", API_BASE).as_str(),
+                    web::post().to(cache_controller::post_cache_export),
+                )
+                .route(
+                    format!("{}cache/import", API_BASE).as_str(),
+                    web::post().to(cache_controller::post_cache_import),
+                )
+                .route(
+                    format!("{}config/reload", API_BASE).as_str(),
+                    web::post().to(config_controller::post_config_reload),
+                )
+                .route(
+                    format!("{}wallet", API_BASE).as_str(),
+                    web::put().to(wallet_controller::put_wallet),
+                )
+                .route(
+                    format!("{}wallet/keystore", API_BASE).as_str(),
+                    web::get().to(wallet_controller::get_wallet_keystore),
+                )
+                .route(
+                    format!("{}quote/chunk", API_BASE).as_str(),
+                    web::post().to(chunk_controller::post_chunk_quote),
+                )
+                .route(
+                    format!("{}quote/binary/public_data", API_BASE).as_str(),
+                    web::post().to(public_data_controller::post_public_data_quote),
+                )
+                .route(
+                    format!("{}quote/multipart/tarchive", API_BASE).as_str(),
+                    web::post().to(tarchive_controller::post_tarchive_quote_root),
+                )
+                .route(
+                    format!("{}quote/multipart/tarchive/{{path:.*}}", API_BASE).as_str(),
+                    web::post().to(tarchive_controller::post_tarchive_quote),
+                )
+                .route(
+                    format!("{}quote/multipart/tarchive/{{address}}", API_BASE).as_str(),
+                    web::put().to(tarchive_controller::put_tarchive_quote_root),
+                )
+                .route(
+                    format!("{}quote/multipart/tarchive/{{address}}/{{path:.*}}", API_BASE).as_str(),
+                    web::put().to(tarchive_controller::put_tarchive_quote),
+                )
+                .route(
+                    format!("{}quote/multipart/archive/{{address}}", API_BASE).as_str(),
+                    web::put().to(archive_controller::put_archive_quote_root),
+                )
+                .route(
+                    format!("{}quote/multipart/archive/{{address}}/{{path:.*}}", API_BASE).as_str(),
+                    web::put().to(archive_controller::put_archive_quote),
+                )
                 /*.route(
                     format!("{}pointer", API_BASE).as_str(),
                     web::post().to(pointer_controller::post_pointer),
@@ -675,6 +970,17 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
                 )*/
         };
 
+        // registered after the routes above, as it would otherwise serve their GET requests
+        app = app
+            .route(
+                "/{path:.*}",
+                web::get().to(file_controller::get_public_data),
+            )
+            .route(
+                "/{path:.*}",
+                web::head().to(file_controller::head_public_data),
+            );
+
         if actix_config.static_file_directory != "" {
             app.service(Files::new(
                 "/static",
@@ -688,11 +994,125 @@ pub async fn run_server(ant_tp_config: AntTpConfig) -> io::Result<()> {
         //.bind_rustls_0_23(https_listen_address, rustls_config())?
         .run();
 
-    let mut guard = ACTIX_SERVER_HANDLE.lock().await;
-    *guard = Some(actix_server.handle());
+    {
+        let mut guard = ACTIX_SERVER_HANDLE.lock().await;
+        *guard = Some(actix_server.handle());
+    }
 
     info!("Starting Actix (HTTP) listener");
-    actix_server.await
+    let result = actix_server.await;
+
+    // actix stops on a signal or stop_server, so shut everything else down once it has
+    shutdown(&command_control, &shutdown_command_status_data, &command_journal, &shutdown_hybrid_cache_data, Duration::from_secs(ant_tp_config.shutdown_grace_period)).await;
+    SHUTDOWN_COMPLETE.notify_one();
+    result
+}
+
+/// Waits for running commands, leaving the rest journaled for the next start, then closes the cache.
+#[cfg(not(test))]
+async fn shutdown(command_control: &CommandControl, command_status: &Data<Mutex<IndexMap<u128, CommandDetails>>>,
+                  command_journal: &CommandJournal, hybrid_cache: &Data<HybridCache<String, Vec<u8>>>, grace_period: Duration) {
+    stop_grpc_server().await;
+
+    info!("Waiting up to [{}] seconds for running commands to finish", grace_period.as_secs());
+    command_control.stop();
+    if tokio::time::timeout(grace_period, command_control.stopped()).await.is_err() {
+        log::warn!("Grace period expired with commands still running");
+    }
+
+    let [completed, failed, aborted, running, waiting] = {
+        let command_status = command_status.lock().await;
+        [CommandState::COMPLETED, CommandState::FAILED, CommandState::ABORTED, CommandState::RUNNING, CommandState::WAITING]
+            .map(|command_state| command_status.values().filter(|command_details| command_details.state() == &command_state).count())
+    };
+    let unfinished = running + waiting;
+    let checkpointed = command_journal.pending().len();
+    if unfinished > checkpointed {
+        log::warn!("[{}] unfinished commands cannot be resumed, as they were not journaled", unfinished - checkpointed);
+    }
+
+    info!("Closing cache...");
+    if let Err(e) = hybrid_cache.close().await {
+        log::error!("Failed to close cache: [{:?}]", e);
+    }
+
+    info!("Shutdown complete: [{}] commands completed, [{}] failed, [{}] aborted, [{}] running, [{}] waiting and [{}] checkpointed to resume on the next start",
+        completed, failed, aborted, running, waiting, checkpointed);
+}
+
+#[cfg(not(test))]
+fn create_command_restorers(client_harness: &Data<ClientHarness>, chunk_caching_client: &ChunkCachingClient,
+                            archive_caching_client: &ArchiveCachingClient, concurrency: usize) -> IndexMap<String, CommandRestorer> {
+    let mut command_restorers: IndexMap<String, CommandRestorer> = IndexMap::new();
+    let local_client_harness = client_harness.clone();
+    command_restorers.insert(create_chunk_command::STRUCT_NAME.to_string(), Box::new(move |id, payload| {
+        Some(Box::new(CreateChunkCommand::restore(id, local_client_harness.clone(), payload)) as Box<dyn Command>)
+    }));
+    let local_client_harness = client_harness.clone();
+    command_restorers.insert(create_public_data_command::STRUCT_NAME.to_string(), Box::new(move |id, payload| {
+        Some(Box::new(CreatePublicDataCommand::restore(id, local_client_harness.clone(), payload)) as Box<dyn Command>)
+    }));
+    let local_chunk_caching_client = chunk_caching_client.clone();
+    let local_archive_caching_client = archive_caching_client.clone();
+    command_restorers.insert(prefetch_command::STRUCT_NAME.to_string(), Box::new(move |id, payload| {
+        PrefetchCommand::restore(id, local_chunk_caching_client.clone(), local_archive_caching_client.clone(), payload, concurrency)
+            .map(|command| Box::new(command) as Box<dyn Command>)
+    }));
+    command_restorers
+}
+
+#[cfg(not(test))]
+async fn run_command(command: AntTpCommand, bundle_service: &BundleService, ant_tp_config: &AntTpConfig) -> io::Result<()> {
+    match command {
+        AntTpCommand::ExportBundle { output, addresses } => {
+            let file = std::fs::File::create(&output)?;
+            let manifest = bundle_service.export_bundle(addresses, file).await.map_err(io::Error::other)?;
+            info!("Exported [{}] chunks with [{}] bytes to [{}]", manifest.chunks.len(), manifest.bytes, output);
+        }
+        AntTpCommand::ImportBundle { input } => {
+            let file = std::fs::File::open(&input)?;
+            let bundle_import = bundle_service.import_bundle(file).await.map_err(io::Error::other)?;
+            info!("Imported [{}] chunks with [{}] bytes from [{}], rejecting [{}] chunks",
+                bundle_import.chunks_imported, bundle_import.bytes, input, bundle_import.chunks_rejected);
+        }
+        key_command => run_key_command(key_command, ant_tp_config)?,
+    }
+    Ok(())
+}
+
+/// Manages keys in the key store, printing public keys to stdout so that they can be scripted.
+fn run_key_command(command: AntTpCommand, ant_tp_config: &AntTpConfig) -> io::Result<()> {
+    if ant_tp_config.key_store_file.is_empty() {
+        return Err(io::Error::other("--key-store-file is required to manage keys"));
+    }
+    let mut key_store = KeyStore::open_or_create(Path::new(&ant_tp_config.key_store_file), &ant_tp_config.key_store_passphrase)
+        .map_err(io::Error::other)?;
+    let stored_key = match command {
+        AntTpCommand::Keygen { key_type, name } => {
+            let stored_key = key_store.generate(&name, key_type).map_err(io::Error::other)?;
+            key_store.save().map_err(io::Error::other)?;
+            info!("Generated [{:?}] key [{}] in [{}]", key_type, name, ant_tp_config.key_store_file);
+            stored_key
+        }
+        AntTpCommand::Import { key_type, private_key, name } => {
+            let private_key = match private_key {
+                Some(private_key) => private_key,
+                None => {
+                    let mut private_key = String::new();
+                    io::stdin().read_line(&mut private_key)?;
+                    private_key
+                }
+            };
+            let stored_key = key_store.import(&name, key_type, &private_key).map_err(io::Error::other)?;
+            key_store.save().map_err(io::Error::other)?;
+            info!("Imported [{:?}] key [{}] into [{}]", key_type, name, ant_tp_config.key_store_file);
+            stored_key
+        }
+        AntTpCommand::ExportPublic { name } => key_store.get(&name).map_err(io::Error::other)?.clone(),
+        command => return Err(io::Error::other(format!("[{:?}] does not manage keys", command))),
+    };
+    println!("{}", stored_key.public_key);
+    Ok(())
 }
 
 /*async fn hydrate_access_checker(ant_tp_config: &AntTpConfig,
@@ -786,25 +1206,31 @@ pub async fn stop_server() -> Result<(), String> {
         guard.take()
     };
 
-    #[cfg(not(grpc_disabled))]
-    {
-        let mut guard = TONIC_SERVER_SHUTDOWN_TX.lock().await;
-        if let Some(tx) = guard.take() {
-            info!("Stopping gRPC server...");
-            let _ = tx.send(());
-        }
-    }
+    stop_grpc_server().await;
 
     if let Some(handle) = actix_handle_opt {
         info!("Stopping Actix server gracefully...");
         handle.stop(true).await;
         info!("Actix server stopped");
+        // wait for commands to be drained or checkpointed and the cache to be closed
+        SHUTDOWN_COMPLETE.notified().await;
         Ok(())
     } else {
         Err("Actix server handle not found or already stopped".to_string())
     }
 }
 
+async fn stop_grpc_server() {
+    #[cfg(not(grpc_disabled))]
+    {
+        let mut guard = TONIC_SERVER_SHUTDOWN_TX.lock().await;
+        if let Some(tx) = guard.take() {
+            info!("Stopping gRPC server...");
+            let _ = tx.send(());
+        }
+    }
+}
+
 /*fn rustls_config() -> rustls::ServerConfig {
     let rcgen::CertifiedKey { cert, signing_key } =
         rcgen::generate_simple_self_signed(["*".to_owned()]).unwrap();
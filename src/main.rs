@@ -1,15 +1,14 @@
 use anttp::config::anttp_config::AntTpConfig;
-
-const DEFAULT_LOGGING: &'static str = "info,anttp=info,saorsa_transport=error,saorsa_core=error,chunk_streamer=info";
+use anttp::config::reloadable_logger::ReloadableLogger;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    // init logging from RUST_LOG env var with info as default
-    env_logger::Builder::from_env(env_logger::Env::default()
-        .default_filter_or(DEFAULT_LOGGING))
-        .format_timestamp_millis()
-        .init();
+    // init logging from RUST_LOG env var with info as default, until the log level is read
+    ReloadableLogger::init("");
     let app_config = AntTpConfig::read_args();
+    if !app_config.log_level.is_empty() {
+        ReloadableLogger::reload(&app_config.log_level);
+    }
 
     anttp::run_server(app_config).await
 }
\ No newline at end of file
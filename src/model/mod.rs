@@ -2,8 +2,9 @@
 pub mod archive;
 pub mod tarchive;
 pub mod path_detail;
-//pub mod access_list;
+pub mod access_list;
 //pub mod bookmark_list;
 //pub mod pnr;
 //pub mod key_value;
-pub mod resolve;
\ No newline at end of file
+pub mod resolve;
+pub mod quote;
\ No newline at end of file
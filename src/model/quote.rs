@@ -0,0 +1,39 @@
+use ant_core::data::XorName;
+use hex::ToHex;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::client::network_backend::ChunksQuote;
+
+/// Estimated cost of uploading data, without uploading it.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct Quote {
+    /// Address the data would be stored at.
+    #[schema(read_only)]
+    pub address: String,
+    /// Distinct chunks after self-encryption, including those already stored.
+    #[schema(read_only)]
+    pub chunks: u64,
+    #[schema(read_only)]
+    pub bytes: u64,
+    /// Chunks which are already stored, so are free.
+    #[schema(read_only)]
+    pub chunks_stored: u64,
+    #[schema(read_only)]
+    pub bytes_stored: u64,
+    /// Quoted storage cost, in atto tokens. Gas is not included.
+    #[schema(read_only)]
+    pub cost: String,
+}
+
+impl Quote {
+    pub fn new(address: XorName, chunks_quote: ChunksQuote) -> Self {
+        Quote {
+            address: address.encode_hex(),
+            chunks: chunks_quote.chunks as u64,
+            bytes: chunks_quote.bytes,
+            chunks_stored: chunks_quote.chunks_stored as u64,
+            bytes_stored: chunks_quote.bytes_stored,
+            cost: chunks_quote.cost.to_string(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-/*use std::collections::HashMap;
+use std::collections::HashMap;
 use log::debug;
 use mockall::mock;
 use crate::model::access_list::AccessList;
@@ -42,7 +42,9 @@ impl Default for AccessChecker {
 
 impl AccessChecker {
 
+    /// Replaces the addresses allowed and denied with those of the access list.
     pub fn update(&mut self, access_list: &AccessList) {
+        self.map.clear();
         for allow_address in access_list.allow() {
             self.map.insert(allow_address.clone(), AccessType::Allow);
         }
@@ -61,7 +63,7 @@ impl AccessChecker {
     }
 
     pub fn is_allowed_default(&self) -> bool {
-        match self.map.get(&"all".to_string()) {
+        match self.map.get("all") {
             Some(AccessType::Deny) => false, // default to deny
             _ => true, // default to allow
         }
@@ -128,6 +130,16 @@ mod tests {
         assert!(!checker.is_allowed(&"denied".to_string()));
     }
 
+    #[test]
+    fn test_update_replaces_access_list() {
+        let mut checker = AccessChecker::new();
+        checker.update(&create_access_list(vec![], vec!["all"]));
+        checker.update(&create_access_list(vec![], vec!["denied"]));
+
+        assert!(!checker.is_allowed(&"denied".to_string()));
+        assert!(checker.is_allowed(&"allowed".to_string()));
+    }
+
     #[test]
     fn test_deny_override_default_allow() {
         let mut checker = AccessChecker::new();
@@ -137,4 +149,4 @@ mod tests {
         assert!(!checker.is_allowed(&"denied".to_string()));
         assert!(checker.is_allowed(&"allowed".to_string()));
     }
-}*/
\ No newline at end of file
+}
\ No newline at end of file
@@ -0,0 +1,148 @@
+use actix_http::header::HeaderMap;
+use actix_web::web::Data;
+use log::info;
+use mockall::mock;
+use mockall_double::double;
+use tokio::sync::Mutex;
+use crate::error::GetError;
+use crate::model::access_list::AccessList;
+#[double]
+use crate::service::access_checker::AccessChecker;
+#[double]
+use crate::service::file_service::FileService;
+#[double]
+use crate::service::resolver_service::ResolverService;
+
+const ACCESS_LIST_FILE: &str = "access_list.json";
+
+/// Loads the access list from the archive at the configured address into the access checker,
+/// which the resolver consults for every request.
+#[derive(Clone)]
+pub struct AccessListService {
+    resolver_service: ResolverService,
+    file_service: FileService,
+    access_checker: Data<Mutex<AccessChecker>>,
+}
+
+mock! {
+    pub AccessListService {
+        pub fn new(resolver_service: ResolverService, file_service: FileService, access_checker: Data<tokio::sync::Mutex<AccessChecker>>) -> Self;
+        pub async fn get_access_list(&self, access_list_address: &str) -> Result<AccessList, GetError>;
+        pub async fn set_access_list(&self, access_list: &AccessList);
+    }
+    impl Clone for AccessListService {
+        fn clone(&self) -> Self;
+    }
+}
+
+impl AccessListService {
+    pub fn new(resolver_service: ResolverService, file_service: FileService, access_checker: Data<Mutex<AccessChecker>>) -> Self {
+        Self { resolver_service, file_service, access_checker }
+    }
+
+    /// Downloads `access_list.json` from the archive at the address. Without an address, all
+    /// addresses are allowed.
+    pub async fn get_access_list(&self, access_list_address: &str) -> Result<AccessList, GetError> {
+        if access_list_address.is_empty() {
+            return Ok(AccessList::default());
+        }
+        let archive = self.resolver_service.resolve(access_list_address, "", &HeaderMap::new()).await
+            .and_then(|resolved_address| resolved_address.archive)
+            .ok_or_else(|| GetError::RecordNotFound(format!("access list archive not found at [{}]", access_list_address)))?;
+        let data_address_offset = archive.find_file(&ACCESS_LIST_FILE.to_string())
+            .ok_or_else(|| GetError::RecordNotFound(format!("[{}] not found in archive [{}]", ACCESS_LIST_FILE, access_list_address)))?;
+        let buf = self.file_service.download_data_bytes(data_address_offset.data_address, data_address_offset.offset, data_address_offset.size).await
+            .map_err(|e| GetError::RecordNotFound(format!("failed to download [{}] from archive [{}]: {}", ACCESS_LIST_FILE, access_list_address, e)))?;
+        serde_json::from_slice(&buf)
+            .map_err(|e| GetError::Decode(format!("invalid [{}] in archive [{}]: {}", ACCESS_LIST_FILE, access_list_address, e)))
+    }
+
+    /// Checks requests against the access list, in place of the previous one.
+    pub async fn set_access_list(&self, access_list: &AccessList) {
+        self.access_checker.lock().await.update(access_list);
+        info!("Applied access list: [{:?}]", access_list);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use bytes::BytesMut;
+    use mockall::predicate::eq;
+    use crate::model::archive::{Archive, ArchiveType, DataAddressOffset};
+    use crate::service::access_checker::MockAccessChecker;
+    use crate::service::file_service::MockFileService;
+    use crate::service::resolver_service::{MockResolverService, ResolvedAddress};
+
+    const ARCHIVE_ADDRESS: &str = "a40e045a6fbed33b27039aa8383c9dbf286e19a7265141c2da3085e0c8571527";
+
+    fn create_archive(files: &[&str]) -> Archive {
+        let data_address_offsets_vec: Vec<DataAddressOffset> = files.iter().map(|path| DataAddressOffset {
+            data_address: [1; 32], path: path.to_string(), offset: 10, size: 20, modified: 0, signature: None,
+        }).collect();
+        let data_address_offsets_map = data_address_offsets_vec.iter()
+            .map(|data_address_offset| (data_address_offset.path.clone(), data_address_offset.clone()))
+            .collect::<HashMap<_, _>>();
+        Archive::new(data_address_offsets_map, data_address_offsets_vec, ArchiveType::Tarchive)
+    }
+
+    fn create_resolver_service(archive: Option<Archive>) -> MockResolverService {
+        let mut mock_resolver_service = MockResolverService::default();
+        mock_resolver_service.expect_resolve()
+            .withf(|hostname, path, _| hostname == ARCHIVE_ADDRESS && path.is_empty())
+            .times(1)
+            .returning(move |_, _, _| Some(ResolvedAddress::new(
+                true, archive.clone(), [2; 32], "".to_string(), false, true, true, 0)));
+        mock_resolver_service
+    }
+
+    #[tokio::test]
+    async fn test_get_access_list_without_address() {
+        let access_list_service = AccessListService::new(
+            MockResolverService::default(), MockFileService::default(), Data::new(Mutex::new(MockAccessChecker::default())));
+        let access_list = access_list_service.get_access_list("").await.unwrap();
+        assert!(access_list.allow().is_empty());
+        assert!(access_list.deny().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_access_list_from_archive() {
+        let mut mock_file_service = MockFileService::default();
+        mock_file_service.expect_download_data_bytes()
+            .with(eq([1; 32]), eq(10), eq(20))
+            .times(1)
+            .returning(|_, _, _| Ok(BytesMut::from(r#"{"allow": ["allowed"], "deny": ["all"]}"#)));
+        let access_list_service = AccessListService::new(
+            create_resolver_service(Some(create_archive(&[ACCESS_LIST_FILE]))), mock_file_service, Data::new(Mutex::new(MockAccessChecker::default())));
+
+        let access_list = access_list_service.get_access_list(ARCHIVE_ADDRESS).await.unwrap();
+        assert_eq!(access_list.allow(), &vec!["allowed".to_string()]);
+        assert_eq!(access_list.deny(), &vec!["all".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_get_access_list_missing_from_archive() {
+        let access_list_service = AccessListService::new(
+            create_resolver_service(Some(create_archive(&["index.html"]))), MockFileService::default(), Data::new(Mutex::new(MockAccessChecker::default())));
+        assert!(matches!(access_list_service.get_access_list(ARCHIVE_ADDRESS).await, Err(GetError::RecordNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_access_list_invalid() {
+        let mut mock_file_service = MockFileService::default();
+        mock_file_service.expect_download_data_bytes().returning(|_, _, _| Ok(BytesMut::from("not json")));
+        let access_list_service = AccessListService::new(
+            create_resolver_service(Some(create_archive(&[ACCESS_LIST_FILE]))), mock_file_service, Data::new(Mutex::new(MockAccessChecker::default())));
+        assert!(matches!(access_list_service.get_access_list(ARCHIVE_ADDRESS).await, Err(GetError::Decode(_))));
+    }
+
+    #[tokio::test]
+    async fn test_set_access_list() {
+        let mut mock_access_checker = MockAccessChecker::default();
+        mock_access_checker.expect_update().times(1).return_const(());
+        let access_list_service = AccessListService::new(
+            MockResolverService::default(), MockFileService::default(), Data::new(Mutex::new(mock_access_checker)));
+        access_list_service.set_access_list(&AccessList::default()).await;
+    }
+}
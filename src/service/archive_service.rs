@@ -6,7 +6,7 @@ use crate::service::resolver_service::ResolverService;
 use actix_multipart::form::MultipartForm;
 use actix_multipart::form::tempfile::TempFile;
 use actix_web::HttpRequest;
-use ant_core::data::{Wallet, XorName};
+use ant_core::data::XorName;
 use bytes::Bytes;
 use hex::FromHex;
 use log::{debug, info};
@@ -19,8 +19,10 @@ pub use crate::model::archive::ArchiveType;
 use crate::client::ArchiveCachingClient;
 use mockall_double::double;
 use crate::config::app_config::AppConfig;
+use crate::config::content_overrides::ContentOverrides;
 use crate::error::CreateError;
 use crate::model::archive::Archive;
+use crate::model::quote::Quote;
 use crate::service::archive_helper::{ArchiveHelper, ArchiveInfo};
 #[double]
 use crate::service::file_service::FileService;
@@ -30,11 +32,14 @@ use crate::service::resolver_service::ResolvedAddress;
 pub struct Upload {
     #[schema(read_only)]
     pub address: Option<String>,
+    /// Batch of the commands queued by the upload, to follow their progress
+    #[schema(read_only)]
+    pub batch_id: Option<String>,
 }
 
 impl Upload {
     pub fn new(address: Option<String>) -> Self {
-        Upload { address }
+        Upload { address, batch_id: None }
     }
 }
 
@@ -85,17 +90,19 @@ pub struct ArchiveService {
     tarchive_service: TarchiveService,
     resolver_service: ResolverService,
     archive_caching_client: ArchiveCachingClient,
-    file_service: FileService
+    file_service: FileService,
+    content_overrides: ContentOverrides,
 }
 
 impl ArchiveService {
-    pub fn new(/*public_archive_service: PublicArchiveService,*/ tarchive_service: TarchiveService, resolver_service: ResolverService, archive_caching_client: ArchiveCachingClient, file_service: FileService) -> Self {
+    pub fn new(/*public_archive_service: PublicArchiveService,*/ tarchive_service: TarchiveService, resolver_service: ResolverService, archive_caching_client: ArchiveCachingClient, file_service: FileService, content_overrides: ContentOverrides) -> Self {
         Self {
             /*public_archive_service,*/
             tarchive_service,
             resolver_service,
             archive_caching_client,
-            file_service
+            file_service,
+            content_overrides,
         }
     }
 
@@ -133,7 +140,6 @@ impl ArchiveService {
         address: String,
         target_path: Option<String>,
         form: MultipartForm<ArchiveForm>,
-        wallet: Wallet,
         store_type: StoreType,
     ) -> Result<ArchiveResponse, ArchiveError> {
         let address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
@@ -151,14 +157,30 @@ impl ArchiveService {
             },
             ArchiveType::Tarchive => {
                 let tarchive_form = MultipartForm(PublicArchiveForm { files });
-                self.tarchive_service.update_tarchive(address, target_path, tarchive_form, wallet, store_type).await
+                self.tarchive_service.update_tarchive(address, target_path, tarchive_form, store_type).await
                     .map(|res| ArchiveResponse::new(vec![], "".to_string(), res.address.unwrap_or_default()))
                     .map_err(ArchiveError::from)
             },
         }
     }
 
-    pub async fn truncate_archive(&self, address: String, path: String, wallet: Wallet, store_type: StoreType) -> Result<Upload, ArchiveError> {
+    /// Quotes updating the archive with files from the form, without uploading it.
+    pub async fn quote_archive_update(&self, address: String, target_path: Option<String>, form: MultipartForm<ArchiveForm>) -> Result<Quote, ArchiveError> {
+        let address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
+        let archive_address = XorName::from_hex(address.as_str())?;
+        let archive = self.archive_caching_client.archive_get(archive_address).await?;
+        let files = form.into_inner().files;
+
+        match archive.archive_type {
+            ArchiveType::Public => Err(ArchiveError::CreateError(CreateError::InvalidData("Not implemented".to_string()))),
+            ArchiveType::Tarchive => {
+                let tarchive_form = MultipartForm(PublicArchiveForm { files });
+                self.tarchive_service.quote_tarchive_update(address, target_path, tarchive_form).await.map_err(ArchiveError::from)
+            },
+        }
+    }
+
+    pub async fn truncate_archive(&self, address: String, path: String, store_type: StoreType) -> Result<Upload, ArchiveError> {
         let address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
         let archive_address = XorName::from_hex(address.as_str())?;
         let archive = self.archive_caching_client.archive_get(archive_address).await?;
@@ -166,11 +188,11 @@ impl ArchiveService {
             ArchiveType::Public => /*self.public_archive_service.truncate_public_archive(address, path, wallet, store_type).await.map(|u| Upload { address: u.address }).map_err(ArchiveError::from)*/
                 Err(ArchiveError::CreateError(CreateError::InvalidData("Not implemented".to_string()))),
             ArchiveType::Tarchive =>
-                self.tarchive_service.truncate_tarchive(address, path, wallet, store_type).await.map(|u| Upload { address: u.address }).map_err(ArchiveError::from),
+                self.tarchive_service.truncate_tarchive(address, path, store_type).await.map_err(ArchiveError::from),
         }
     }
 
-    pub async fn push_archive(&self, address: String, wallet: Wallet, store_type: StoreType) -> Result<Upload, ArchiveError> {
+    pub async fn push_archive(&self, address: String, store_type: StoreType) -> Result<Upload, ArchiveError> {
         let address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
         let archive_address = XorName::from_hex(address.as_str())?;
         let archive = self.archive_caching_client.archive_get(archive_address).await?;
@@ -178,11 +200,11 @@ impl ArchiveService {
             ArchiveType::Public => /*self.public_archive_service.push_public_archive(address, wallet, store_type).await.map(|u| Upload { address: u.address }).map_err(ArchiveError::from)*/
                 Err(ArchiveError::CreateError(CreateError::InvalidData("Not implemented".to_string()))),
             ArchiveType::Tarchive =>
-                self.tarchive_service.push_tarchive(address, wallet, store_type).await.map(|u| Upload { address: u.address }).map_err(ArchiveError::from),
+                self.tarchive_service.push_tarchive(address, store_type).await.map_err(ArchiveError::from),
         }
     }
 
-    pub async fn create_public_archive(&self, target_path: Option<String>, form: MultipartForm<ArchiveForm>, wallet: Wallet, store_type: StoreType) -> Result<ArchiveResponse, ArchiveError> {
+    pub async fn create_public_archive(&self, target_path: Option<String>, form: MultipartForm<ArchiveForm>, store_type: StoreType) -> Result<ArchiveResponse, ArchiveError> {
         let files = form.into_inner().files;
         let public_form = MultipartForm(PublicArchiveForm { files });
         /*self.public_archive_service.create_public_archive(target_path, public_form, wallet, store_type).await
@@ -191,18 +213,21 @@ impl ArchiveService {
         Err(ArchiveError::CreateError(CreateError::InvalidData("Not implemented".to_string())))
     }
 
-    pub async fn create_tarchive(&self, target_path: Option<String>, form: MultipartForm<ArchiveForm>, wallet: Wallet, store_type: StoreType) -> Result<Upload, ArchiveError> {
+    pub async fn create_tarchive(&self, target_path: Option<String>, form: MultipartForm<ArchiveForm>, store_type: StoreType) -> Result<Upload, ArchiveError> {
         let files = form.into_inner().files;
         let tarchive_form = MultipartForm(PublicArchiveForm { files });
-        self.tarchive_service.create_tarchive(target_path, tarchive_form, wallet, store_type).await.map(|u| Upload { address: u.address }).map_err(ArchiveError::from)
+        self.tarchive_service.create_tarchive(target_path, tarchive_form, store_type).await.map_err(ArchiveError::from)
     }
 
     pub async fn get_archive_info(&self, resolved_address: &ResolvedAddress, request: &HttpRequest) -> ArchiveInfo {
         let archive = resolved_address.archive.clone().expect("Archive not found");
         // load app_config from archive and resolve route
         let app_config = self.get_app_config(&archive, &resolved_address.xor_name).await;
-        // resolve route
-        let (resolved_route_path, has_route_map) = app_config.resolve_route(&resolved_address.file_path);
+        // resolve route, with the archive's own routes taking precedence over the configured ones
+        let (resolved_route_path, has_route_map) = match app_config.resolve_route(&resolved_address.file_path) {
+            (_, false) => self.content_overrides.resolve_route(&resolved_address.file_path),
+            archive_route => archive_route,
+        };
 
         debug!("Get data for archive_addr [{}], archive_file_name [{}]", hex::encode(resolved_address.xor_name), resolved_route_path);
 
@@ -0,0 +1,374 @@
+use std::io::{Read, Write};
+use ant_core::data::{DataChunk, DataMap, XorName, MAX_CHUNK_SIZE};
+use bytes::Bytes;
+use hex::FromHex;
+use indexmap::IndexSet;
+use log::{debug, info, warn};
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use tar::{Archive, Builder, Header};
+use tokio::sync::mpsc;
+use utoipa::ToSchema;
+#[double]
+use crate::client::ArchiveCachingClient;
+#[double]
+use crate::client::ChunkCachingClient;
+use crate::controller::StoreType;
+use crate::error::{CreateError, GetError};
+use crate::error::chunk_error::ChunkError;
+#[double]
+use crate::service::resolver_service::ResolverService;
+
+const BUNDLE_VERSION: u32 = 1;
+const BUNDLE_MANIFEST_PATH: &str = "manifest.json";
+const BUNDLE_CHUNKS_PATH: &str = "chunks/";
+const BUNDLE_ENTRY_BUFFER_SIZE: usize = 16;
+// the manifest lists every chunk in the bundle, so may be larger than a chunk
+const BUNDLE_MANIFEST_MAX_SIZE: u64 = 16 * MAX_CHUNK_SIZE as u64;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct BundleExport {
+    pub addresses: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct BundleManifest {
+    #[schema(read_only)]
+    pub version: u32,
+    #[schema(read_only)]
+    pub addresses: Vec<String>,
+    #[schema(read_only)]
+    pub chunks: Vec<String>,
+    #[schema(read_only)]
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default)]
+pub struct BundleImport {
+    #[schema(read_only)]
+    pub addresses: Vec<String>,
+    #[schema(read_only)]
+    pub chunks_imported: usize,
+    #[schema(read_only)]
+    pub chunks_rejected: usize,
+    #[schema(read_only)]
+    pub bytes: u64,
+}
+
+/// Exports data maps, archives and all of their chunks to a portable bundle and imports them again.
+///
+/// A bundle is a tar of content addressed chunks (`chunks/{address}`) plus a `manifest.json`.
+/// The tar is written and read on a blocking thread, exchanging entries with the async chunk
+/// retrieval through a bounded channel, so that large bundles neither block the executor nor
+/// have to fit in memory.
+pub struct BundleService {
+    chunk_caching_client: ChunkCachingClient,
+    archive_caching_client: ArchiveCachingClient,
+    resolver_service: ResolverService,
+}
+
+impl BundleService {
+    pub fn new(chunk_caching_client: ChunkCachingClient, archive_caching_client: ArchiveCachingClient, resolver_service: ResolverService) -> Self {
+        BundleService { chunk_caching_client, archive_caching_client, resolver_service }
+    }
+
+    pub async fn export_bundle<W: Write + Send + 'static>(&self, addresses: Vec<String>, writer: W) -> Result<BundleManifest, ChunkError> {
+        let mut xor_names = IndexSet::new();
+        for address in addresses {
+            let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
+            match XorName::from_hex(resolved_address.as_str()) {
+                Ok(xor_name) => xor_names.insert(xor_name),
+                Err(e) => return Err(ChunkError::GetError(GetError::BadAddress(e.to_string())))
+            };
+        }
+
+        let (entry_sender, mut entry_receiver) = mpsc::channel::<(String, Bytes)>(BUNDLE_ENTRY_BUFFER_SIZE);
+        let join_handle = tokio::task::spawn_blocking(move || {
+            let mut builder = Builder::new(writer);
+            while let Some((path, content)) = entry_receiver.blocking_recv() {
+                Self::append_entry(&mut builder, &path, &content)?;
+            }
+            builder.finish().map_err(|e| ChunkError::CreateError(CreateError::TemporaryStorage(e.to_string())))
+        });
+
+        let manifest = self.send_export_entries(&xor_names, entry_sender).await;
+        match join_handle.await {
+            Ok(Ok(())) => {}
+            // the writer failing stops the entries being received, so report it over the send error
+            Ok(Err(e)) => return Err(e),
+            Err(e) => return Err(ChunkError::CreateError(CreateError::TemporaryStorage(e.to_string()))),
+        }
+        let manifest = manifest?;
+        info!("exported [{}] chunks with [{}] bytes to bundle", manifest.chunks.len(), manifest.bytes);
+        Ok(manifest)
+    }
+
+    /// Retrieves the chunks to export, sending them to be written followed by the manifest.
+    async fn send_export_entries(&self, xor_names: &IndexSet<XorName>, entry_sender: mpsc::Sender<(String, Bytes)>) -> Result<BundleManifest, ChunkError> {
+        let mut exported_addresses = IndexSet::new();
+        let mut bytes = 0;
+        for data_address in self.get_data_addresses(xor_names).await {
+            let data_map_chunk = self.chunk_caching_client.chunk_get_internal(&data_address).await?;
+            let mut chunk_addresses = IndexSet::new();
+            match rmp_serde::from_slice::<DataMap>(&data_map_chunk.content) {
+                Ok(data_map) => data_map.infos().iter().for_each(|info| { chunk_addresses.insert(info.dst_hash.0); }),
+                Err(_) => debug!("chunk at [{}] is not a data map - exporting chunk only", hex::encode(data_address)),
+            }
+            bytes += Self::send_chunk(&entry_sender, &mut exported_addresses, data_map_chunk).await?;
+            for chunk_address in chunk_addresses {
+                if !exported_addresses.contains(&chunk_address) {
+                    let chunk = self.chunk_caching_client.chunk_get_internal(&chunk_address).await?;
+                    bytes += Self::send_chunk(&entry_sender, &mut exported_addresses, chunk).await?;
+                }
+            }
+        }
+
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            addresses: xor_names.iter().map(hex::encode).collect(),
+            chunks: exported_addresses.iter().map(hex::encode).collect(),
+            bytes,
+        };
+        match serde_json::to_vec_pretty(&manifest) {
+            Ok(manifest_bytes) => Self::send_entry(&entry_sender, BUNDLE_MANIFEST_PATH.to_string(), Bytes::from(manifest_bytes)).await?,
+            Err(e) => return Err(ChunkError::CreateError(CreateError::Serialization(e.to_string())))
+        }
+        Ok(manifest)
+    }
+
+    pub async fn import_bundle<R: Read + Send + 'static>(&self, reader: R) -> Result<BundleImport, ChunkError> {
+        let (entry_sender, mut entry_receiver) = mpsc::channel::<(String, Vec<u8>)>(BUNDLE_ENTRY_BUFFER_SIZE);
+        let join_handle = tokio::task::spawn_blocking(move || {
+            let mut archive = Archive::new(reader);
+            let entries = match archive.entries() {
+                Ok(entries) => entries,
+                Err(e) => return Err(ChunkError::CreateError(CreateError::InvalidData(format!("Failed to read bundle: {}", e))))
+            };
+            for entry in entries {
+                match Self::read_entry(entry) {
+                    Ok(path_content) => if entry_sender.blocking_send(path_content).is_err() {
+                        // the import stopped, so there is no need to read further
+                        break;
+                    },
+                    Err(e) => return Err(ChunkError::CreateError(CreateError::InvalidData(format!("Failed to read bundle entry: {}", e))))
+                }
+            }
+            Ok(())
+        });
+
+        let bundle_import = self.import_entries(&mut entry_receiver).await;
+        // stop the reader before waiting for it, in case the import failed part way through
+        drop(entry_receiver);
+        match join_handle.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => return Err(e),
+            Err(e) => return Err(ChunkError::CreateError(CreateError::InvalidData(e.to_string()))),
+        }
+        let bundle_import = bundle_import?;
+        info!("imported [{}] chunks with [{}] bytes from bundle, rejecting [{}] chunks",
+            bundle_import.chunks_imported, bundle_import.bytes, bundle_import.chunks_rejected);
+        Ok(bundle_import)
+    }
+
+    async fn import_entries(&self, entry_receiver: &mut mpsc::Receiver<(String, Vec<u8>)>) -> Result<BundleImport, ChunkError> {
+        let mut bundle_import = BundleImport::default();
+        while let Some((path, content)) = entry_receiver.recv().await {
+            if path == BUNDLE_MANIFEST_PATH {
+                match serde_json::from_slice::<BundleManifest>(&content) {
+                    Ok(manifest) => bundle_import.addresses = manifest.addresses,
+                    Err(e) => warn!("Failed to parse bundle manifest: {}", e),
+                }
+            } else if let Some(chunk_address_hex) = path.strip_prefix(BUNDLE_CHUNKS_PATH) {
+                let chunk = DataChunk::from_content(Bytes::from(content));
+                if hex::encode(chunk.address) == chunk_address_hex {
+                    self.chunk_caching_client.chunk_put(&chunk, StoreType::Disk).await?;
+                    bundle_import.chunks_imported += 1;
+                    bundle_import.bytes += chunk.content.len() as u64;
+                } else {
+                    warn!("chunk [{}] in bundle does not match its content hash - rejecting", chunk_address_hex);
+                    bundle_import.chunks_rejected += 1;
+                }
+            } else {
+                debug!("ignoring unexpected bundle entry [{}]", path);
+            }
+        }
+        Ok(bundle_import)
+    }
+
+    async fn get_data_addresses(&self, xor_names: &IndexSet<XorName>) -> IndexSet<XorName> {
+        let mut data_addresses = IndexSet::new();
+        for xor_name in xor_names {
+            data_addresses.insert(*xor_name);
+            if let Ok(archive) = self.archive_caching_client.archive_get(*xor_name).await {
+                debug!("exporting [{}] files from archive at [{}]", archive.vec().len(), hex::encode(xor_name));
+                archive.vec().iter().for_each(|data_address_offset| { data_addresses.insert(data_address_offset.data_address); });
+            }
+        }
+        data_addresses
+    }
+
+    async fn send_chunk(entry_sender: &mpsc::Sender<(String, Bytes)>, exported_addresses: &mut IndexSet<XorName>, chunk: DataChunk) -> Result<u64, ChunkError> {
+        if !exported_addresses.insert(chunk.address) {
+            return Ok(0);
+        }
+        let bytes = chunk.content.len() as u64;
+        Self::send_entry(entry_sender, format!("{}{}", BUNDLE_CHUNKS_PATH, hex::encode(chunk.address)), chunk.content).await?;
+        Ok(bytes)
+    }
+
+    async fn send_entry(entry_sender: &mpsc::Sender<(String, Bytes)>, path: String, content: Bytes) -> Result<(), ChunkError> {
+        entry_sender.send((path, content)).await
+            .map_err(|_| ChunkError::CreateError(CreateError::TemporaryStorage("bundle writer stopped".to_string())))
+    }
+
+    fn append_entry<W: Write>(builder: &mut Builder<W>, path: &str, content: &[u8]) -> Result<(), ChunkError> {
+        let mut header = Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        match builder.append_data(&mut header, path, content) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(ChunkError::CreateError(CreateError::TemporaryStorage(e.to_string())))
+        }
+    }
+
+    fn read_entry<R: Read>(entry: std::io::Result<tar::Entry<R>>) -> std::io::Result<(String, Vec<u8>)> {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().to_string();
+        // check the size before reading, so that a crafted bundle cannot exhaust memory
+        let max_size = if path == BUNDLE_MANIFEST_PATH { BUNDLE_MANIFEST_MAX_SIZE } else { MAX_CHUNK_SIZE as u64 };
+        if entry.size() > max_size {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData,
+                format!("entry [{}] of [{}] bytes exceeds the maximum of [{}] bytes", path, entry.size(), max_size)));
+        }
+        let mut content = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut content)?;
+        Ok((path, content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+    use crate::client::{MockArchiveCachingClient, MockChunkCachingClient};
+    use crate::error::archive_error::ArchiveError;
+    use crate::service::resolver_service::MockResolverService;
+
+    fn create_archive_client_without_archive() -> MockArchiveCachingClient {
+        let mut mock_archive_client = MockArchiveCachingClient::default();
+        mock_archive_client
+            .expect_archive_get()
+            .returning(|_| Err(ArchiveError::GetError(GetError::RecordNotFound("not an archive".to_string()))));
+        mock_archive_client
+    }
+
+    fn create_resolver() -> MockResolverService {
+        let mut mock_resolver = MockResolverService::default();
+        mock_resolver
+            .expect_resolve_name()
+            .returning(|_| None);
+        mock_resolver
+    }
+
+    async fn export_raw_chunk(content: &'static str) -> (BundleManifest, Vec<u8>) {
+        let address = DataChunk::from_content(Bytes::from(content)).address;
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_get_internal()
+            .times(1)
+            .returning(move |_| Ok(DataChunk::from_content(Bytes::from(content))));
+
+        let service = BundleService::new(mock_chunk_client, create_archive_client_without_archive(), create_resolver());
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        let manifest = service.export_bundle(vec![hex::encode(address)], temp_file.reopen().unwrap()).await.unwrap();
+        (manifest, std::fs::read(temp_file.path()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_export_bundle_raw_chunk() {
+        let (manifest, bundle) = export_raw_chunk("raw").await;
+
+        assert_eq!(manifest.version, BUNDLE_VERSION);
+        assert_eq!(manifest.chunks.len(), 1);
+        assert_eq!(manifest.bytes, 3);
+        assert!(!bundle.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_bundle_bad_address_error() {
+        let service = BundleService::new(MockChunkCachingClient::default(), MockArchiveCachingClient::default(), create_resolver());
+        let result = service.export_bundle(vec!["invalid_address".to_string()], Vec::new()).await;
+
+        match result.unwrap_err() {
+            ChunkError::GetError(GetError::BadAddress(_)) => (),
+            _ => panic!("Expected BadAddress error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_bundle_round_trip() {
+        let (manifest, bundle) = export_raw_chunk("raw").await;
+
+        let imported_chunks = Arc::new(Mutex::new(Vec::new()));
+        let local_imported_chunks = imported_chunks.clone();
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_put()
+            .withf(|_, store_type| *store_type == StoreType::Disk)
+            .times(1)
+            .returning(move |chunk, _| {
+                local_imported_chunks.lock().unwrap().push(chunk.clone());
+                Ok(chunk.address)
+            });
+
+        let service = BundleService::new(mock_chunk_client, MockArchiveCachingClient::default(), create_resolver());
+        let bundle_import = service.import_bundle(Cursor::new(bundle)).await.unwrap();
+
+        assert_eq!(bundle_import.addresses, manifest.addresses);
+        assert_eq!(bundle_import.chunks_imported, 1);
+        assert_eq!(bundle_import.chunks_rejected, 0);
+        assert_eq!(imported_chunks.lock().unwrap()[0].content, Bytes::from("raw"));
+    }
+
+    #[tokio::test]
+    async fn test_import_bundle_rejects_hash_mismatch() {
+        let mut builder = Builder::new(Vec::new());
+        let address = DataChunk::from_content(Bytes::from("raw")).address;
+        BundleService::append_entry(&mut builder, format!("{}{}", BUNDLE_CHUNKS_PATH, hex::encode(address)).as_str(), "tampered".as_bytes()).unwrap();
+        let bundle = builder.into_inner().unwrap();
+
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_put()
+            .times(0);
+
+        let service = BundleService::new(mock_chunk_client, MockArchiveCachingClient::default(), create_resolver());
+        let bundle_import = service.import_bundle(Cursor::new(bundle)).await.unwrap();
+
+        assert_eq!(bundle_import.chunks_imported, 0);
+        assert_eq!(bundle_import.chunks_rejected, 1);
+    }
+
+    #[tokio::test]
+    async fn test_import_bundle_rejects_oversized_entry() {
+        let mut builder = Builder::new(Vec::new());
+        let content = vec![0u8; MAX_CHUNK_SIZE + 1];
+        let address = DataChunk::from_content(Bytes::from(content.clone())).address;
+        BundleService::append_entry(&mut builder, format!("{}{}", BUNDLE_CHUNKS_PATH, hex::encode(address)).as_str(), &content).unwrap();
+        let bundle = builder.into_inner().unwrap();
+
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client
+            .expect_chunk_put()
+            .times(0);
+
+        let service = BundleService::new(mock_chunk_client, MockArchiveCachingClient::default(), create_resolver());
+
+        match service.import_bundle(Cursor::new(bundle)).await.unwrap_err() {
+            ChunkError::CreateError(CreateError::InvalidData(_)) => (),
+            _ => panic!("Expected InvalidData error"),
+        }
+    }
+}
\ No newline at end of file
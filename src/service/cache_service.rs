@@ -0,0 +1,80 @@
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+#[double]
+use crate::client::CachingClient;
+use crate::client::cache_integrity::CacheIntegrityStats;
+use crate::client::chunk_fetch_policy::ChunkFetchStats;
+use crate::client::negative_cache::NegativeCacheStats;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct CacheStats {
+    #[schema(read_only)]
+    pub negative_cache: NegativeCacheStats,
+    #[schema(read_only)]
+    pub integrity: CacheIntegrityStats,
+    #[schema(read_only)]
+    pub chunk_fetch: ChunkFetchStats,
+}
+
+impl CacheStats {
+    pub fn new(negative_cache: NegativeCacheStats, integrity: CacheIntegrityStats, chunk_fetch: ChunkFetchStats) -> Self {
+        CacheStats { negative_cache, integrity, chunk_fetch }
+    }
+}
+
+pub struct CacheService {
+    caching_client: CachingClient,
+}
+
+impl CacheService {
+    pub fn new(caching_client: CachingClient) -> Self {
+        CacheService { caching_client }
+    }
+
+    pub fn get_cache_stats(&self) -> CacheStats {
+        CacheStats::new(
+            self.caching_client.get_negative_cache().stats(),
+            self.caching_client.get_cache_integrity().stats(),
+            self.caching_client.get_chunk_fetch_policy().stats()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::web::Data;
+    use foyer::HybridCacheBuilder;
+    use crate::client::MockCachingClient;
+    use crate::client::cache_integrity::CacheIntegrity;
+    use crate::client::chunk_fetch_policy::ChunkFetchPolicy;
+    use std::time::Duration;
+    use crate::client::negative_cache::NegativeCache;
+
+    #[tokio::test]
+    async fn test_get_cache_stats() {
+        let hybrid_cache = HybridCacheBuilder::new().memory(1024).storage().build().await.unwrap();
+        let hybrid_cache_data = Data::new(hybrid_cache);
+        let negative_cache = NegativeCache::new(hybrid_cache_data.clone(), 60);
+        negative_cache.insert("ncabc".to_string(), "not found".to_string());
+        negative_cache.get(&"ncabc".to_string());
+
+        let mut mock_caching_client = MockCachingClient::default();
+        mock_caching_client
+            .expect_get_negative_cache()
+            .return_const(negative_cache);
+        mock_caching_client
+            .expect_get_cache_integrity()
+            .return_const(CacheIntegrity::new(hybrid_cache_data, 1));
+        mock_caching_client
+            .expect_get_chunk_fetch_policy()
+            .return_const(ChunkFetchPolicy::new(Duration::from_secs(30), 2, Duration::from_millis(500), None));
+
+        let cache_stats = CacheService::new(mock_caching_client).get_cache_stats();
+        assert_eq!(cache_stats.negative_cache.inserts, 1);
+        assert_eq!(cache_stats.negative_cache.hits, 1);
+        assert_eq!(cache_stats.integrity.corrupted, 0);
+        assert_eq!(cache_stats.chunk_fetch.fetches, 0);
+    }
+}
@@ -12,6 +12,7 @@ use crate::client::ChunkCachingClient;
 use crate::error::{CreateError, GetError};
 use crate::error::chunk_error::ChunkError;
 use crate::controller::StoreType;
+use crate::model::quote::Quote;
 #[double]
 use crate::service::resolver_service::ResolverService;
 
@@ -62,6 +63,20 @@ impl ChunkService {
         Ok(Chunk::new(None, Some(chunk_address.encode_hex())))
     }
 
+    /// Quotes uploading the chunk, without uploading it.
+    pub async fn quote_chunk(&self, chunk: Chunk) -> Result<Quote, ChunkError> {
+        let content = match chunk.content {
+            Some(content) => content,
+            None => return Err(ChunkError::CreateError(CreateError::InvalidData("Empty chunk payload".to_string())))
+        };
+        let decoded_content = BASE64_STANDARD.decode(content)
+            .map_err(|e| ChunkError::CreateError(CreateError::InvalidData(format!("Invalid base64 chunk payload: {}", e))))?;
+        let chunk_data = DataChunk::from_content(Bytes::from(decoded_content));
+        let chunks_quote = self.chunk_caching_client.chunk_quote(&chunk_data).await?;
+        info!("Quoted [{}] for chunk at [{}]", chunks_quote.cost, hex::encode(chunk_data.address));
+        Ok(Quote::new(chunk_data.address, chunks_quote))
+    }
+
     pub async fn get_chunk_binary(&self, address: String) -> Result<DataChunk, ChunkError> {
         let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
         match XorName::from_hex(resolved_address.as_str()) {
@@ -79,6 +94,13 @@ impl ChunkService {
         }
     }
 
+    pub async fn bypass_negative_cache(&self, address: &String) {
+        let resolved_address = self.resolver_service.resolve_name(address).await.unwrap_or(address.clone());
+        if let Ok(chunk_address) = XorName::from_hex(resolved_address.as_str()) {
+            self.chunk_caching_client.chunk_negative_cache_bypass(&chunk_address);
+        }
+    }
+
     pub async fn get_chunk(&self, address: String) -> Result<Chunk, ChunkError> {
         let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
         match XorName::from_hex(resolved_address.as_str()) {
@@ -101,7 +123,9 @@ impl ChunkService {
 mod tests {
     use super::*;
     use mockall::predicate::*;
+    use ant_core::data::U256;
     use crate::client::MockChunkCachingClient;
+    use crate::client::network_backend::ChunksQuote;
     use crate::service::resolver_service::MockResolverService;
 
     fn create_test_service(mock_client: MockChunkCachingClient, mock_resolver: MockResolverService) -> ChunkService {
@@ -168,6 +192,39 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_quote_chunk_success() {
+        let mut mock_client = MockChunkCachingClient::default();
+        let mock_resolver = MockResolverService::default();
+        let chunk_input = Chunk::new(Some(BASE64_STANDARD.encode("test content")), None);
+        let expected_address = DataChunk::from_content(Bytes::from("test content")).address;
+
+        mock_client
+            .expect_chunk_quote()
+            .withf(move |chunk| chunk.address == expected_address)
+            .times(1)
+            .returning(|_| Ok(ChunksQuote { chunks: 1, bytes: 12, chunks_stored: 0, bytes_stored: 0, cost: U256::from(12) }));
+
+        let service = create_test_service(mock_client, mock_resolver);
+        let quote = service.quote_chunk(chunk_input).await.unwrap();
+
+        assert_eq!(quote.address, hex::encode(expected_address));
+        assert_eq!(quote.chunks, 1);
+        assert_eq!(quote.bytes, 12);
+        assert_eq!(quote.cost, "12");
+    }
+
+    #[tokio::test]
+    async fn test_quote_chunk_invalid_payload_error() {
+        let mock_client = MockChunkCachingClient::default();
+        let mock_resolver = MockResolverService::default();
+
+        let service = create_test_service(mock_client, mock_resolver);
+        let result = service.quote_chunk(Chunk::new(Some("not base64!".to_string()), None)).await;
+
+        assert!(matches!(result, Err(ChunkError::CreateError(CreateError::InvalidData(_)))));
+    }
+
     #[tokio::test]
     async fn test_get_chunk_binary_success() {
         let mut mock_client = MockChunkCachingClient::default();
@@ -332,4 +389,27 @@ mod tests {
             _ => panic!("Expected BadAddress error"),
         }
     }
+
+    #[tokio::test]
+    async fn test_bypass_negative_cache() {
+        let mut mock_client = MockChunkCachingClient::default();
+        let mut mock_resolver = MockResolverService::default();
+        let address_hex = "0000000000000000000000000000000000000000000000000000000000000000";
+        let chunk_address = XorName::from_hex(address_hex).unwrap();
+
+        mock_resolver
+            .expect_resolve_name()
+            .with(eq(address_hex.to_string()))
+            .times(1)
+            .returning(|_| None);
+
+        mock_client
+            .expect_chunk_negative_cache_bypass()
+            .with(eq(chunk_address))
+            .times(1)
+            .returning(|_| ());
+
+        let service = create_test_service(mock_client, mock_resolver);
+        service.bypass_negative_cache(&address_hex.to_string()).await;
+    }
 }
\ No newline at end of file
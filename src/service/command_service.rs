@@ -4,7 +4,19 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
 use utoipa::ToSchema;
-use crate::client::command::command_details::CommandDetails;
+use std::pin::Pin;
+use async_stream::stream;
+use futures_core::Stream;
+use log::{info, warn};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::Sender;
+use crate::client::command::Command as ClientCommand;
+use crate::client::command::command_control::CommandControl;
+use crate::client::command::command_events::CommandEvents;
+use crate::client::command::command_details::{CommandAttempt, CommandDetails, CommandState};
+use crate::client::command::command_journal::CommandJournal;
+use crate::error::command_error::CommandError;
+use crate::error::{GetError, UpdateError};
 
 #[derive(ToSchema, Serialize, Deserialize, Debug, Clone)]
 pub struct Command {
@@ -15,12 +27,47 @@ pub struct Command {
     pub waiting_at: u128,
     pub running_at: Option<u128>,
     pub terminated_at: Option<u128>,
+    pub attempts: Vec<Attempt>,
+    pub error: Option<String>,
+    pub result_address: Option<String>,
+    pub bytes_uploaded: Option<u64>,
+    pub storage_cost: Option<String>,
+    pub gas_cost: Option<String>,
+    pub duplicate_of: Option<String>,
+    pub priority: i32,
+    pub not_before: Option<u128>,
+    pub queue_position: Option<usize>,
+    pub eta: Option<u128>,
+    pub batch_id: Option<String>,
+    pub wallet: Option<String>,
 }
 
 impl Command {
     pub fn new(id: String, name: String, properties: Vec<Property>, state: String, waiting_at: u128,
                running_at: Option<u128>, terminated_at: Option<u128>) -> Self {
-        Self { id, name, properties, state, waiting_at, running_at, terminated_at }
+        Self {
+            id, name, properties, state, waiting_at, running_at, terminated_at,
+            attempts: Vec::new(), error: None, result_address: None, bytes_uploaded: None, storage_cost: None, gas_cost: None,
+            duplicate_of: None, priority: 0, not_before: None, queue_position: None, eta: None, batch_id: None,
+            wallet: None
+        }
+    }
+}
+
+#[derive(ToSchema, Serialize, Deserialize, Debug, Clone)]
+pub struct Attempt {
+    pub started_at: u128,
+    pub error: Option<String>,
+    pub backoff_seconds: Option<u64>,
+}
+
+impl From<&CommandAttempt> for Attempt {
+    fn from(command_attempt: &CommandAttempt) -> Self {
+        Self {
+            started_at: command_attempt.started_at,
+            error: command_attempt.error.clone(),
+            backoff_seconds: command_attempt.backoff_seconds,
+        }
     }
 }
 
@@ -39,29 +86,277 @@ impl Property {
 #[derive(utoipa::ToResponse, Serialize, Deserialize, Debug, Clone)]
 pub struct CommandList(pub Vec<Command>);
 
-#[derive(Debug)]
+/// Aggregate progress of a batch of related commands.
+///
+/// Duplicates of other commands count as running until their original completes, as their data
+/// is uploaded by the original. They count as aborted if the original does not complete.
+#[derive(ToSchema, Serialize, Deserialize, Debug, Clone)]
+pub struct Batch {
+    pub id: String,
+    pub state: String,
+    pub total: usize,
+    pub waiting: usize,
+    pub running: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub aborted: usize,
+    pub percent_complete: u8,
+    pub bytes_uploaded: u64,
+    pub commands: Vec<Command>,
+}
+
+impl Batch {
+    fn new(id: String, command_details_list: Vec<&CommandDetails>, commands_map: &IndexMap<u128, CommandDetails>) -> Self {
+        let states: Vec<CommandState> = command_details_list.iter()
+            .map(|command_details| Self::progress_state(command_details, commands_map))
+            .collect();
+        let count = |command_state: CommandState| states.iter()
+            .filter(|state| **state == command_state)
+            .count();
+        let total = command_details_list.len();
+        let waiting = count(CommandState::WAITING);
+        let running = count(CommandState::RUNNING);
+        let completed = count(CommandState::COMPLETED);
+        let failed = count(CommandState::FAILED);
+        let aborted = count(CommandState::ABORTED);
+        let state = if running > 0 || (waiting > 0 && waiting < total) {
+            CommandState::RUNNING
+        } else if waiting > 0 {
+            CommandState::WAITING
+        } else if failed > 0 {
+            CommandState::FAILED
+        } else if aborted > 0 {
+            CommandState::ABORTED
+        } else {
+            CommandState::COMPLETED
+        };
+        let percent_complete = ((total - waiting - running) * 100).checked_div(total).unwrap_or(0) as u8;
+        let bytes_uploaded = command_details_list.iter()
+            .filter_map(|command_details| command_details.receipt().bytes_uploaded)
+            .sum();
+        let commands = command_details_list.into_iter().map(Command::from).collect();
+        Self { id, state: state.to_string(), total, waiting, running, completed, failed, aborted, percent_complete, bytes_uploaded, commands }
+    }
+
+    /// State of the command for batch progress, following duplicates to their original.
+    fn progress_state(command_details: &CommandDetails, commands_map: &IndexMap<u128, CommandDetails>) -> CommandState {
+        match command_details.duplicate_of() {
+            Some(original_id) if *command_details.state() == CommandState::ABORTED => match commands_map.get(&original_id).map(CommandDetails::state) {
+                Some(CommandState::WAITING) | Some(CommandState::RUNNING) => CommandState::RUNNING,
+                Some(CommandState::FAILED) | Some(CommandState::ABORTED) => CommandState::ABORTED,
+                // the original completed, or completed long enough ago to have left the map
+                Some(CommandState::COMPLETED) | None => CommandState::COMPLETED,
+            },
+            _ => command_details.state().clone(),
+        }
+    }
+}
+
 pub struct CommandService {
     commands_map: Data<Mutex<IndexMap<u128, CommandDetails>>>,
+    command_control: CommandControl,
+    command_executor: Data<Sender<Box<dyn ClientCommand>>>,
+    command_journal: CommandJournal,
+    command_events: CommandEvents,
+}
+
+impl From<&CommandDetails> for Command {
+    fn from(command_details: &CommandDetails) -> Self {
+        let mut properties = Vec::<Property>::with_capacity(command_details.properties().len());
+        command_details.properties().iter().for_each(|(k, v)|properties.push(Property::new(k.clone(), v.clone())));
+        let receipt = command_details.receipt().clone();
+        Command {
+            attempts: command_details.attempts().iter().map(Attempt::from).collect(),
+            error: command_details.error().cloned(),
+            result_address: receipt.result_address,
+            bytes_uploaded: receipt.bytes_uploaded,
+            storage_cost: receipt.storage_cost,
+            gas_cost: receipt.gas_cost,
+            duplicate_of: command_details.duplicate_of().map(|duplicate_of| duplicate_of.to_string()),
+            priority: command_details.priority(),
+            not_before: command_details.not_before(),
+            queue_position: command_details.queue_position(),
+            eta: command_details.eta(),
+            batch_id: command_details.batch_id().cloned(),
+            wallet: command_details.wallet().cloned(),
+            ..Command::new(command_details.id().to_string(), command_details.name().clone(), properties, command_details.state().to_string(),
+                           command_details.waiting_at(), command_details.running_at(), command_details.terminated_at())
+        }
+    }
 }
 
 impl CommandService {
-    pub fn new(commands_map: Data<Mutex<IndexMap<u128, CommandDetails>>>) -> Self {
-        Self { commands_map }
+    pub fn new(commands_map: Data<Mutex<IndexMap<u128, CommandDetails>>>, command_control: CommandControl,
+               command_executor: Data<Sender<Box<dyn ClientCommand>>>, command_journal: CommandJournal,
+               command_events: CommandEvents) -> Self {
+        Self { commands_map, command_control, command_executor, command_journal, command_events }
     }
 
     pub async fn get_commands(&self) -> Result<CommandList, Error> {
         let commands_map = self.commands_map.get_ref().lock().await;
-        let mut commands = Vec::<Command>::with_capacity(commands_map.len());
-
-        commands_map.values().for_each(|v|{
-            let mut properties = Vec::<Property>::with_capacity(v.properties().len());
-            v.properties().iter().for_each(|(k, v)|properties.push(Property::new(k.clone(), v.clone())));
-            commands.push(
-                Command::new(v.id().to_string(), v.name().clone(), properties, v.state().to_string(),
-                             v.waiting_at(), v.running_at(), v.terminated_at())
-            )
+        Ok(CommandList(commands_map.values().map(Command::from).collect()))
+    }
+
+    pub async fn get_command(&self, id: String) -> Result<Command, CommandError> {
+        let id = Self::parse_id(&id)?;
+        match self.commands_map.get_ref().lock().await.get(&id) {
+            Some(command_details) => Ok(Command::from(command_details)),
+            None => Err(GetError::RecordNotFound(format!("command [{}] not found", id)).into()),
+        }
+    }
+
+    pub async fn cancel_command(&self, id: String) -> Result<Command, CommandError> {
+        let id = Self::parse_id(&id)?;
+        let mut commands_map = self.commands_map.get_ref().lock().await;
+        let command_details = commands_map.get_mut(&id)
+            .ok_or(GetError::RecordNotFound(format!("command [{}] not found", id)))?;
+        match command_details.state().clone() {
+            CommandState::WAITING => {
+                // report as aborted now, as the executor drops it from its queue
+                self.command_control.cancel(id);
+                self.command_journal.update(id, &CommandState::ABORTED).await;
+                command_details.set_state(CommandState::ABORTED);
+                self.command_events.publish(command_details.clone());
+            },
+            CommandState::RUNNING => self.command_control.cancel(id),
+            state => return Err(UpdateError::InvalidData(format!("command [{}] is [{}] and cannot be cancelled", id, state)).into()),
+        }
+        info!("cancelled command [{}]", id);
+        Ok(Command::from(&*command_details))
+    }
+
+    pub async fn retry_command(&self, id: String) -> Result<Command, CommandError> {
+        let id = Self::parse_id(&id)?;
+        let command_details = self.get_command_details(id).await?;
+        if command_details.state() != &CommandState::FAILED {
+            return Err(UpdateError::InvalidData(format!("command [{}] is [{}] and cannot be retried", id, command_details.state())).into());
+        }
+        let command = self.command_control.take_failed(id)
+            .ok_or(GetError::RecordNotFound(format!("failed command [{}] is no longer available to retry", id)))?;
+        let retried_command_details = CommandDetails::new(&command);
+        if let Err(e) = self.command_journal.append(command.as_ref()).await {
+            // keep it available to retry again
+            self.command_control.insert_failed(command);
+            return Err(e.into());
+        }
+        self.commands_map.get_ref().lock().await.insert(id, retried_command_details.clone());
+        self.command_executor.send(command).await?;
+        info!("retrying command [{}]", id);
+        Ok(Command::from(&retried_command_details))
+    }
+
+    pub async fn get_batch(&self, id: String) -> Result<Batch, CommandError> {
+        let commands_map = self.commands_map.get_ref().lock().await;
+        let command_details_list: Vec<&CommandDetails> = commands_map.values()
+            .filter(|command_details| command_details.batch_id() == Some(&id))
+            .collect();
+        if command_details_list.is_empty() {
+            return Err(GetError::RecordNotFound(format!("command batch [{}] not found", id)).into());
+        }
+        Ok(Batch::new(id, command_details_list, &commands_map))
+    }
+
+    /// Retries every failed command in the batch which is still available to retry.
+    pub async fn retry_batch(&self, id: String) -> Result<Batch, CommandError> {
+        let failed_ids: Vec<String> = self.get_batch(id.clone()).await?.commands.into_iter()
+            .filter(|command| command.state == CommandState::FAILED.to_string())
+            .map(|command| command.id)
+            .collect();
+        for failed_id in failed_ids {
+            if let Err(e) = self.retry_command(failed_id.clone()).await {
+                warn!("failed to retry command [{}] in batch [{}]: [{}]", failed_id, id, e);
+            }
+        }
+        info!("retried failed commands in batch [{}]", id);
+        self.get_batch(id).await
+    }
+
+    pub async fn purge_commands(&self) -> Result<CommandList, CommandError> {
+        let mut commands_map = self.commands_map.get_ref().lock().await;
+        let mut purged_commands = Vec::<Command>::new();
+        commands_map.retain(|_, command_details| match command_details.state() {
+            CommandState::WAITING | CommandState::RUNNING => true,
+            _ => {
+                purged_commands.push(Command::from(&*command_details));
+                false
+            }
         });
+        let purged_ids: Vec<u128> = purged_commands.iter().filter_map(|command| command.id.parse::<u128>().ok()).collect();
+        self.command_control.remove_failed(&purged_ids);
+        info!("purged [{}] terminated commands", purged_commands.len());
+        Ok(CommandList(purged_commands))
+    }
+
+    /// Streams commands as they change state or report progress, until the client disconnects.
+    pub fn watch_commands(&self) -> Pin<Box<dyn Stream<Item = Command> + Send>> {
+        let mut receiver = self.command_events.subscribe();
+        Box::pin(stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(command_details) => yield Command::from(&command_details),
+                    Err(RecvError::Lagged(skipped)) => warn!("command watcher lagged, skipping [{}] events", skipped),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    async fn get_command_details(&self, id: u128) -> Result<CommandDetails, CommandError> {
+        self.commands_map.get_ref().lock().await.get(&id).cloned()
+            .ok_or(GetError::RecordNotFound(format!("command [{}] not found", id)).into())
+    }
+
+    fn parse_id(id: &String) -> Result<u128, CommandError> {
+        id.parse::<u128>().map_err(|e| GetError::BadAddress(format!("invalid command id [{}]: {}", id, e)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::command::test_command::IdCommand;
+
+    fn create_command_details(id: u128, command_state: CommandState, duplicate_of: Option<u128>) -> CommandDetails {
+        let mut command_details = CommandDetails::new(&IdCommand::boxed(id));
+        command_details.set_state(command_state);
+        if let Some(original_id) = duplicate_of {
+            command_details.set_duplicate_of(original_id);
+        }
+        command_details
+    }
+
+    fn create_batch(commands_map: &IndexMap<u128, CommandDetails>, ids: &[u128]) -> Batch {
+        Batch::new("batch".to_string(), ids.iter().map(|id| commands_map.get(id).unwrap()).collect(), commands_map)
+    }
+
+    #[test]
+    fn test_batch_counts_duplicates_by_original_state() {
+        let mut commands_map = IndexMap::new();
+        commands_map.insert(1, create_command_details(1, CommandState::RUNNING, None));
+        commands_map.insert(2, create_command_details(2, CommandState::ABORTED, Some(1)));
+        commands_map.insert(3, create_command_details(3, CommandState::ABORTED, Some(99)));
+
+        let batch = create_batch(&commands_map, &[2, 3]);
+        assert_eq!((batch.running, batch.completed, batch.aborted), (1, 1, 0));
+        assert_eq!(batch.state, CommandState::RUNNING.to_string());
+
+        commands_map.get_mut(&1).unwrap().set_state(CommandState::COMPLETED);
+        let batch = create_batch(&commands_map, &[1, 2, 3]);
+        assert_eq!((batch.running, batch.completed, batch.aborted), (0, 3, 0));
+        assert_eq!(batch.state, CommandState::COMPLETED.to_string());
+
+        commands_map.get_mut(&1).unwrap().set_state(CommandState::FAILED);
+        let batch = create_batch(&commands_map, &[2]);
+        assert_eq!((batch.completed, batch.aborted), (0, 1));
+    }
+
+    #[test]
+    fn test_batch_counts_duplicate_not_yet_aborted_as_waiting() {
+        let mut commands_map = IndexMap::new();
+        commands_map.insert(1, create_command_details(1, CommandState::COMPLETED, None));
+        commands_map.insert(2, create_command_details(2, CommandState::WAITING, Some(1)));
 
-        Ok(CommandList(commands))
+        let batch = create_batch(&commands_map, &[2]);
+        assert_eq!((batch.waiting, batch.completed, batch.aborted), (1, 0, 0));
     }
 }
\ No newline at end of file
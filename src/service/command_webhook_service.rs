@@ -0,0 +1,223 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use hmac::{Hmac, Mac};
+use log::{debug, info, warn};
+use sha2::Sha256;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::sleep;
+use crate::client::command::command_details::{CommandDetails, CommandState};
+use crate::client::command::command_events::CommandEvents;
+use crate::service::command_service::Command;
+
+pub const SIGNATURE_HEADER: &str = "x-anttp-signature";
+pub const TIMESTAMP_HEADER: &str = "x-anttp-timestamp";
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+const DELIVERY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Posts commands to webhook URLs when they complete or fail.
+///
+/// Each post is delivered in the background, so a slow webhook cannot hold up the others, and
+/// is retried with backoff on connection or server errors. The unix time of each attempt is sent
+/// in the `x-anttp-timestamp` header. When a secret is configured, `<timestamp>.<body>` is signed
+/// with HMAC-SHA256 and the hex digest is sent in the `x-anttp-signature` header as
+/// `sha256=<digest>`, so receivers can reject replayed posts.
+#[derive(Clone)]
+pub struct CommandWebhookService {
+    urls: Vec<String>,
+    secret: String,
+    http_client: reqwest::Client,
+}
+
+impl CommandWebhookService {
+    pub fn new(urls: Vec<String>, secret: String, timeout: Duration) -> Self {
+        let http_client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .unwrap_or_default();
+        Self { urls, secret, http_client }
+    }
+
+    pub fn start(self, command_events: &CommandEvents) {
+        if self.urls.is_empty() {
+            return;
+        }
+        if self.secret.is_empty() {
+            warn!("command webhook secret is not set - webhooks will not be signed");
+        }
+        info!("posting command completions to [{}] webhooks", self.urls.len());
+        let mut receiver = command_events.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(command_details) => self.notify(&command_details),
+                    Err(RecvError::Lagged(skipped)) => warn!("command webhooks lagged, skipping [{}] events", skipped),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    fn notify(&self, command_details: &CommandDetails) {
+        if *command_details.state() != CommandState::COMPLETED && *command_details.state() != CommandState::FAILED {
+            return;
+        }
+        let body = match serde_json::to_vec(&Command::from(command_details)) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!("failed to serialize command [{}] for webhooks: [{:?}]", command_details.id(), e);
+                return;
+            }
+        };
+        for url in self.urls.iter() {
+            tokio::spawn(self.clone().deliver(url.clone(), body.clone(), command_details.id()));
+        }
+    }
+
+    async fn deliver(self, url: String, body: Vec<u8>, command_id: u128) {
+        let mut backoff = DELIVERY_BACKOFF;
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let mut request = self.http_client.post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .body(body.clone());
+            if !self.secret.is_empty() {
+                request = request.header(SIGNATURE_HEADER, format!("sha256={}", Self::sign(&self.secret, &Self::signed_payload(timestamp, &body))));
+            }
+            match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    debug!("posted command [{}] to webhook [{}]", command_id, url);
+                    return;
+                },
+                Ok(response) if !response.status().is_server_error() && response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS => {
+                    warn!("webhook [{}] rejected command [{}] with status [{}]", url, command_id, response.status());
+                    return;
+                },
+                Ok(response) =>
+                    warn!("webhook [{}] failed command [{}] with status [{}] on attempt [{}]", url, command_id, response.status(), attempt),
+                Err(e) =>
+                    warn!("failed to post command [{}] to webhook [{}] on attempt [{}]: [{:?}]", command_id, url, attempt, e),
+            }
+            if attempt < MAX_DELIVERY_ATTEMPTS {
+                sleep(backoff).await;
+                backoff *= 2;
+            }
+        }
+        warn!("giving up posting command [{}] to webhook [{}] after [{}] attempts", command_id, url, MAX_DELIVERY_ATTEMPTS);
+    }
+
+    /// The timestamp is signed with the body, so a captured post cannot be replayed later.
+    pub fn signed_payload(timestamp: u64, body: &[u8]) -> Vec<u8> {
+        let mut payload = format!("{}.", timestamp).into_bytes();
+        payload.extend_from_slice(body);
+        payload
+    }
+
+    pub fn sign(secret: &str, payload: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+    use bytes::Bytes;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+    use crate::client::command::test_command::IdCommand;
+
+    type WebhookPost = (Option<String>, Option<String>, Bytes);
+
+    // stand-in for a webhook receiver, failing the first posts, then forwarding the signature
+    // and timestamp headers and the body of each post
+    fn start_webhook(sender: UnboundedSender<WebhookPost>, failures: usize) -> String {
+        let posts = Arc::new(AtomicUsize::new(0));
+        let server = HttpServer::new(move || {
+            let sender = sender.clone();
+            let posts = posts.clone();
+            App::new().route("/webhook", web::post().to(move |request: HttpRequest, body: Bytes| {
+                let sender = sender.clone();
+                let posts = posts.clone();
+                async move {
+                    if posts.fetch_add(1, Ordering::SeqCst) < failures {
+                        return HttpResponse::ServiceUnavailable().finish();
+                    }
+                    let header = |name: &str| request.headers().get(name).map(|value| value.to_str().unwrap().to_string());
+                    sender.send((header(SIGNATURE_HEADER), header(TIMESTAMP_HEADER), body)).unwrap();
+                    HttpResponse::Ok().finish()
+                }
+            }))
+        })
+            .workers(1)
+            .bind("127.0.0.1:0")
+            .unwrap();
+        let url = format!("http://{}/webhook", server.addrs()[0]);
+        actix_web::rt::spawn(server.run());
+        url
+    }
+
+    fn create_command_details(id: u128, command_state: CommandState) -> CommandDetails {
+        let mut command_details = CommandDetails::new(&IdCommand::boxed(id));
+        command_details.set_state(command_state);
+        command_details
+    }
+
+    #[actix_web::test]
+    async fn test_completed_commands_are_posted_and_signed() {
+        let (sender, mut receiver) = unbounded_channel();
+        let command_events = CommandEvents::new(10);
+        CommandWebhookService::new(vec![start_webhook(sender, 0)], "secret".to_string(), Duration::from_secs(5))
+            .start(&command_events);
+
+        command_events.publish(create_command_details(1, CommandState::RUNNING));
+        command_events.publish(create_command_details(2, CommandState::COMPLETED));
+
+        let (signature, timestamp, body) = receiver.recv().await.unwrap();
+        let command: Command = serde_json::from_slice(&body).unwrap();
+        assert_eq!(command.id, "2");
+        assert_eq!(command.state, "COMPLETED");
+        let timestamp = timestamp.unwrap().parse::<u64>().unwrap();
+        let signed_payload = CommandWebhookService::signed_payload(timestamp, &body);
+        assert_eq!(signature, Some(format!("sha256={}", CommandWebhookService::sign("secret", &signed_payload))));
+    }
+
+    #[actix_web::test]
+    async fn test_failed_posts_are_retried() {
+        let (sender, mut receiver) = unbounded_channel();
+        let command_events = CommandEvents::new(10);
+        CommandWebhookService::new(vec![start_webhook(sender, 1)], "".to_string(), Duration::from_secs(5))
+            .start(&command_events);
+
+        command_events.publish(create_command_details(4, CommandState::COMPLETED));
+
+        let (_, _, body) = receiver.recv().await.unwrap();
+        let command: Command = serde_json::from_slice(&body).unwrap();
+        assert_eq!(command.id, "4");
+    }
+
+    #[actix_web::test]
+    async fn test_unsigned_without_secret() {
+        let (sender, mut receiver) = unbounded_channel();
+        let command_events = CommandEvents::new(10);
+        CommandWebhookService::new(vec![start_webhook(sender, 0)], "".to_string(), Duration::from_secs(5))
+            .start(&command_events);
+
+        command_events.publish(create_command_details(3, CommandState::FAILED));
+
+        let (signature, timestamp, _) = receiver.recv().await.unwrap();
+        assert_eq!(signature, None);
+        assert!(timestamp.is_some());
+    }
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            CommandWebhookService::sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
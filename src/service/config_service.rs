@@ -0,0 +1,283 @@
+use std::env;
+use actix_web::web::Data;
+use log::{info, warn};
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+use crate::client::negative_cache::NegativeCache;
+use crate::config::anttp_config::AntTpConfig;
+use crate::config::content_overrides::ContentOverrides;
+use crate::config::reloadable_logger::ReloadableLogger;
+use crate::error::UpdateError;
+#[double]
+use crate::service::access_list_service::AccessListService;
+#[double]
+use crate::service::resolver_service::ResolverService;
+use crate::service::wallet_service::WalletService;
+
+/// Settings applied by a configuration reload.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ConfigReload {
+    #[schema(read_only)]
+    pub cached_mutable_ttl: u64,
+    #[schema(read_only)]
+    pub negative_cache_ttl: u64,
+    #[schema(read_only)]
+    pub log_level: String,
+    #[schema(read_only)]
+    pub access_list_address: String,
+    /// Whether other settings changed, which only apply after a restart.
+    #[schema(read_only)]
+    pub restart_required: bool,
+}
+
+/// Re-reads the configuration from the command line, environment and config file, applying the
+/// settings which are safe to change while running.
+///
+/// The TTLs, log level, access list, route and MIME overrides and the wallets which API keys
+/// select are reloaded. Listeners, the cache and the network keep their startup settings until a
+/// restart.
+#[derive(Clone)]
+pub struct ConfigService {
+    args: Vec<String>,
+    ant_tp_config: Data<Mutex<AntTpConfig>>,
+    negative_cache: NegativeCache,
+    resolver_service: ResolverService,
+    access_list_service: AccessListService,
+    content_overrides: ContentOverrides,
+    wallet_service: Data<WalletService>,
+}
+
+impl ConfigService {
+    pub fn new(args: Vec<String>,
+               ant_tp_config: AntTpConfig,
+               negative_cache: NegativeCache,
+               resolver_service: ResolverService,
+               access_list_service: AccessListService,
+               content_overrides: ContentOverrides,
+               wallet_service: Data<WalletService>,
+    ) -> Self {
+        Self { args, ant_tp_config: Data::new(Mutex::new(ant_tp_config)), negative_cache, resolver_service, access_list_service, content_overrides, wallet_service }
+    }
+
+    pub async fn reload(&self) -> Result<ConfigReload, UpdateError> {
+        self.reload_with_vars(env::vars().collect()).await
+    }
+
+    async fn reload_with_vars(&self, vars: Vec<(String, String)>) -> Result<ConfigReload, UpdateError> {
+        // one reload at a time, so that the applied settings match the last configuration read
+        let mut ant_tp_config = self.ant_tp_config.lock().await;
+        let args = self.args.clone();
+        // parsed on a blocking thread, as opening the key store derives its key with PBKDF2
+        let new_config = tokio::task::spawn_blocking(move || AntTpConfig::try_parse_layered(args, vars))
+            .await
+            .map_err(|e| UpdateError::TemporaryStorage(format!("failed to reload configuration: {}", e)))?
+            .map_err(|e| UpdateError::InvalidData(format!("failed to reload configuration: {}", e)))?;
+
+        // fetch the access list and load the wallets before applying anything, so that a failure changes nothing
+        let access_list = if new_config.access_list_address != ant_tp_config.access_list_address {
+            Some(self.access_list_service.get_access_list(&new_config.access_list_address).await
+                .map_err(|e| UpdateError::InvalidData(format!("failed to reload access list: {}", e)))?)
+        } else {
+            None
+        };
+        if new_config.wallet_api_keys != ant_tp_config.wallet_api_keys {
+            self.wallet_service.load_api_key_wallets(new_config.wallet_api_keys.clone()).await
+                .map_err(|e| UpdateError::InvalidData(format!("failed to reload API key wallets: {}", e)))?;
+        }
+
+        if let Some(access_list) = access_list {
+            self.access_list_service.set_access_list(&access_list).await;
+        }
+        self.content_overrides.update(&new_config.route_overrides, &new_config.mime_overrides);
+        self.resolver_service.set_ttl_default(new_config.cached_mutable_ttl);
+        self.negative_cache.set_ttl(new_config.negative_cache_ttl);
+        ReloadableLogger::reload(&new_config.log_level);
+
+        let mut applied_config = ant_tp_config.clone();
+        applied_config.cached_mutable_ttl = new_config.cached_mutable_ttl;
+        applied_config.negative_cache_ttl = new_config.negative_cache_ttl;
+        applied_config.log_level = new_config.log_level.clone();
+        applied_config.access_list_address = new_config.access_list_address.clone();
+        applied_config.route_overrides = new_config.route_overrides.clone();
+        applied_config.mime_overrides = new_config.mime_overrides.clone();
+        applied_config.wallet_api_keys = new_config.wallet_api_keys.clone();
+        let restart_required = applied_config != new_config;
+        if restart_required {
+            warn!("Some configuration changes apply after a restart");
+        }
+        info!("Reloaded configuration: cached mutable TTL [{}], negative cache TTL [{}], log level [{}], access list [{}]",
+            new_config.cached_mutable_ttl, new_config.negative_cache_ttl, new_config.log_level, new_config.access_list_address);
+
+        let config_reload = ConfigReload {
+            cached_mutable_ttl: new_config.cached_mutable_ttl,
+            negative_cache_ttl: new_config.negative_cache_ttl,
+            log_level: new_config.log_level,
+            access_list_address: new_config.access_list_address,
+            restart_required,
+        };
+        // keep the startup settings of everything else, so that changes are reported until a restart
+        *ant_tp_config = applied_config;
+        Ok(config_reload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use ant_core::data::Wallet;
+    use evmlib::Network::ArbitrumOne;
+    use foyer::HybridCacheBuilder;
+    use mockall::predicate::eq;
+    use crate::client::client_harness::ClientHarness;
+    use crate::client::command::command_wallet::CommandWallet;
+    use crate::config::key_store::{KeyStore, KeyType};
+    use crate::error::GetError;
+    use crate::model::access_list::AccessList;
+    use crate::service::access_list_service::MockAccessListService;
+    use crate::service::resolver_service::MockResolverService;
+    use crate::client::wallet_ledger::WalletLedger;
+
+    async fn create_negative_cache() -> NegativeCache {
+        let hybrid_cache = HybridCacheBuilder::new().memory(1024).storage().build().await.unwrap();
+        NegativeCache::new(Data::new(hybrid_cache), 0)
+    }
+
+    fn to_strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn create_resolver_service() -> MockResolverService {
+        let mut mock_resolver_service = MockResolverService::default();
+        mock_resolver_service.expect_set_ttl_default().return_const(());
+        mock_resolver_service
+    }
+
+    async fn create_config_service(args: Vec<String>, resolver_service: MockResolverService, access_list_service: MockAccessListService) -> ConfigService {
+        let ant_tp_config = AntTpConfig::try_parse_layered(args.clone(), vec![]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let wallet_service = Data::new(WalletService::new(client_harness, ant_tp_config.clone(), WalletLedger::new()));
+        let content_overrides = ContentOverrides::new(&ant_tp_config.route_overrides, &ant_tp_config.mime_overrides);
+        ConfigService::new(args, ant_tp_config, create_negative_cache().await, resolver_service, access_list_service, content_overrides, wallet_service)
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_ttls() {
+        let directory = tempfile::tempdir().unwrap();
+        let config_file = directory.path().join("anttp.toml");
+        fs::write(&config_file, "cached-mutable-ttl = 30\nnegative-cache-ttl = 0\n").unwrap();
+        let args = to_strings(&["anttp", "--config", config_file.to_str().unwrap()]);
+
+        let mut mock_resolver_service = MockResolverService::default();
+        mock_resolver_service.expect_set_ttl_default().with(eq(90)).times(1).return_const(());
+        let config_service = create_config_service(args, mock_resolver_service, MockAccessListService::default()).await;
+
+        fs::write(&config_file, "cached-mutable-ttl = 90\nnegative-cache-ttl = 60\n").unwrap();
+        let config_reload = config_service.reload_with_vars(vec![]).await.unwrap();
+        assert_eq!(config_reload, ConfigReload {
+            cached_mutable_ttl: 90, negative_cache_ttl: 60, log_level: "".to_string(), access_list_address: "".to_string(), restart_required: false
+        });
+
+        config_service.negative_cache.insert("ncabc".to_string(), "not found".to_string());
+        assert_eq!(config_service.negative_cache.get(&"ncabc".to_string()), Some("not found".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_reload_reports_restart_required() {
+        let config_service = create_config_service(to_strings(&["anttp"]), create_resolver_service(), MockAccessListService::default()).await;
+
+        let vars = vec![("ANTTP_IDLE_DISCONNECT".to_string(), "5".to_string())];
+        assert!(config_service.reload_with_vars(vars.clone()).await.unwrap().restart_required);
+        // still required, as the setting is not applied until a restart
+        assert!(config_service.reload_with_vars(vars).await.unwrap().restart_required);
+        assert!(!config_service.reload_with_vars(vec![]).await.unwrap().restart_required);
+    }
+
+    #[tokio::test]
+    async fn test_reload_with_process_environment() {
+        let mut mock_resolver_service = MockResolverService::default();
+        mock_resolver_service.expect_set_ttl_default().with(eq(5)).times(1).return_const(());
+        let config_service = create_config_service(to_strings(&["anttp", "--negative-cache-ttl", "45"]), mock_resolver_service, MockAccessListService::default()).await;
+
+        assert_eq!(config_service.reload().await.unwrap().negative_cache_ttl, 45);
+    }
+
+    #[tokio::test]
+    async fn test_reload_rejects_invalid_config() {
+        let config_service = create_config_service(to_strings(&["anttp"]), MockResolverService::default(), MockAccessListService::default()).await;
+
+        let vars = vec![("ANTTP_NEGATIVE_CACHE_TTL".to_string(), "soon".to_string())];
+        assert!(matches!(config_service.reload_with_vars(vars).await, Err(UpdateError::InvalidData(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_access_list() {
+        let address = "a40e045a6fbed33b27039aa8383c9dbf286e19a7265141c2da3085e0c8571527";
+        let mut mock_access_list_service = MockAccessListService::default();
+        mock_access_list_service.expect_get_access_list()
+            .with(eq(address))
+            .times(1)
+            .returning(|_| Ok(AccessList::default()));
+        mock_access_list_service.expect_set_access_list().times(1).return_const(());
+        let config_service = create_config_service(to_strings(&["anttp"]), create_resolver_service(), mock_access_list_service).await;
+
+        let vars = vec![("ANTTP_ACCESS_LIST_ADDRESS".to_string(), address.to_string())];
+        let config_reload = config_service.reload_with_vars(vars.clone()).await.unwrap();
+        assert_eq!(config_reload.access_list_address, address);
+        assert!(!config_reload.restart_required);
+        // unchanged, so not fetched again
+        config_service.reload_with_vars(vars).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_settings_when_access_list_fails() {
+        let mut mock_access_list_service = MockAccessListService::default();
+        mock_access_list_service.expect_get_access_list()
+            .returning(|_| Err(GetError::RecordNotFound("missing".to_string())));
+        // neither the access list nor the TTLs are applied
+        let config_service = create_config_service(to_strings(&["anttp"]), MockResolverService::default(), mock_access_list_service).await;
+
+        let vars = vec![
+            ("ANTTP_ACCESS_LIST_ADDRESS".to_string(), "missing".to_string()),
+            ("ANTTP_CACHED_MUTABLE_TTL".to_string(), "5".to_string()),
+        ];
+        assert!(matches!(config_service.reload_with_vars(vars).await, Err(UpdateError::InvalidData(_))));
+        assert_eq!(config_service.ant_tp_config.lock().await.access_list_address, "");
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_content_overrides() {
+        let config_service = create_config_service(to_strings(&["anttp"]), create_resolver_service(), MockAccessListService::default()).await;
+
+        let vars = vec![
+            ("ANTTP_ROUTE_OVERRIDES".to_string(), "blog/*=index.html".to_string()),
+            ("ANTTP_MIME_OVERRIDES".to_string(), "md=text/markdown".to_string()),
+        ];
+        assert!(!config_service.reload_with_vars(vars).await.unwrap().restart_required);
+        assert_eq!(config_service.content_overrides.resolve_route(&"blog/post".to_string()), ("index.html".to_string(), true));
+        assert!(config_service.content_overrides.mime_types().contains_key("md"));
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_wallet_api_keys() {
+        let directory = tempfile::tempdir().unwrap();
+        let key_store_file = directory.path().join("keys.json");
+        let mut key_store = KeyStore::open_or_create(&key_store_file, "passphrase").unwrap().with_iterations(1_000);
+        key_store.import("team-a", KeyType::Evm, &Wallet::random_private_key()).unwrap();
+        key_store.save().unwrap();
+        let args = to_strings(&["anttp", "--fake-network", "--key-store-file", key_store_file.to_str().unwrap(), "--key-store-passphrase", "passphrase"]);
+        let config_service = create_config_service(args, create_resolver_service(), MockAccessListService::default()).await;
+        assert!(config_service.wallet_service.get_command_wallet(Some("key-a")).is_err());
+
+        let vars = vec![("ANTTP_WALLET_API_KEYS".to_string(), "key-a=team-a".to_string())];
+        assert!(!config_service.reload_with_vars(vars).await.unwrap().restart_required);
+        assert_eq!(config_service.wallet_service.get_command_wallet(Some("key-a")).unwrap(), Some(CommandWallet::new("team-a".to_string())));
+
+        // wallets missing from the key store are refused
+        let vars = vec![("ANTTP_WALLET_API_KEYS".to_string(), "key-b=team-b".to_string())];
+        assert!(matches!(config_service.reload_with_vars(vars).await, Err(UpdateError::InvalidData(_))));
+        assert!(config_service.wallet_service.get_command_wallet(Some("key-a")).is_ok());
+    }
+}
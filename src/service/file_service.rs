@@ -13,6 +13,7 @@ use mockall::mock;
 use mockall_double::double;
 #[double]
 use crate::client::ChunkCachingClient;
+use crate::controller::is_no_cache;
 use crate::error::{GetError, GetStreamError};
 use crate::error::chunk_error::ChunkError;
 use crate::service::resolver_service::ResolvedAddress;
@@ -92,6 +93,9 @@ impl FileService {
         offset_modifier: u64,
         size_modifier: u64,
     ) -> Result<(ChunkReceiver, RangeProps), ChunkError> {
+        if is_no_cache(request.headers()) {
+            self.chunk_caching_client.chunk_negative_cache_bypass(&xor_name);
+        }
         let data_map_chunk: DataChunk = self.chunk_caching_client.chunk_get_internal(&xor_name).await?;
 
         let chunk_streamer = ChunkStreamer::new(xor_name.encode_hex(), data_map_chunk.content, self.chunk_caching_client.clone(), self.download_threads);
@@ -440,4 +444,30 @@ mod tests {
         }
         assert!(!received_data.is_empty());
     }
+
+    #[actix_web::test]
+    async fn test_download_data_request_no_cache_bypasses_negative_cache() {
+        let xor_name = XorName::default();
+
+        let mut mock_chunk_client = MockChunkCachingClient::default();
+        mock_chunk_client.expect_chunk_negative_cache_bypass()
+            .with(mockall::predicate::eq(xor_name))
+            .times(1)
+            .returning(|_| ());
+        mock_chunk_client.expect_chunk_get_internal()
+            .with(mockall::predicate::eq(xor_name))
+            .times(1)
+            .returning(|_| Err(ChunkError::GetError(GetError::RecordNotFound("not found".to_string()))));
+
+        let service = FileService {
+            chunk_caching_client: mock_chunk_client,
+            download_threads: 8,
+        };
+        let req = TestRequest::default()
+            .insert_header((header::CACHE_CONTROL, "no-cache"))
+            .to_http_request();
+
+        let result = service.download_data_request(&req, "test.txt".to_string(), xor_name, 0, 0).await;
+        assert!(result.is_err());
+    }
 }
\ No newline at end of file
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::time::{Duration, SystemTime};
 use actix_files::file_extension_to_mime;
 use actix_http::header;
@@ -8,13 +9,14 @@ use hex::ToHex;
 use mime::Mime;
 
 pub struct HeaderBuilder {
-    cached_mutable_ttl: u64
+    cached_mutable_ttl: u64,
+    mime_types: HashMap<String, Mime>,
 }
 
 impl HeaderBuilder {
     
-    pub fn new(cached_mutable_ttl: u64) -> Self {
-        Self { cached_mutable_ttl }
+    pub fn new(cached_mutable_ttl: u64, mime_types: HashMap<String, Mime>) -> Self {
+        Self { cached_mutable_ttl, mime_types }
     }
     
     pub fn build_cache_control_header(&self, is_resolved_from_mutable: bool) -> CacheControl {
@@ -34,7 +36,9 @@ impl HeaderBuilder {
     }
 
     pub fn build_content_type_header(&self, extension: &str) -> ContentType {
-        if extension != "" {
+        if let Some(mime) = self.mime_types.get(&extension.to_lowercase()) {
+            ContentType(mime.clone())
+        } else if !extension.is_empty() {
             ContentType(file_extension_to_mime(extension))
         } else {
             ContentType(mime::TEXT_HTML) // default to text/html
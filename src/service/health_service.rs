@@ -0,0 +1,263 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use actix_web::web::Data;
+use foyer::HybridCache;
+use indexmap::IndexMap;
+use log::info;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use utoipa::ToSchema;
+use crate::client::client_harness::{ClientHarness, NetworkStatus};
+use crate::client::command::command_control::CommandControl;
+use crate::client::command::command_details::{CommandDetails, CommandState};
+use crate::config::anttp_config::AntTpConfig;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct Liveness {
+    #[schema(read_only)]
+    pub alive: bool,
+    #[schema(read_only)]
+    pub uptime_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct Readiness {
+    #[schema(read_only)]
+    pub ready: bool,
+    /// Reasons for not being ready, if any.
+    #[schema(read_only)]
+    pub reasons: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default, PartialEq)]
+pub struct CacheUsage {
+    #[schema(read_only)]
+    pub memory_usage: u64,
+    #[schema(read_only)]
+    pub memory_capacity: u64,
+    #[schema(read_only)]
+    pub disk_capacity_bytes: u64,
+    #[schema(read_only)]
+    pub disk_read_bytes: u64,
+    #[schema(read_only)]
+    pub disk_write_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, Default, PartialEq)]
+pub struct CommandQueueStatus {
+    #[schema(read_only)]
+    pub waiting: u64,
+    #[schema(read_only)]
+    pub running: u64,
+    #[schema(read_only)]
+    pub buffer_size: u64,
+    #[schema(read_only)]
+    pub stopping: bool,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct Listener {
+    #[schema(read_only)]
+    pub name: String,
+    #[schema(read_only)]
+    pub address: String,
+}
+
+impl Listener {
+    pub fn new(name: &str, address: String) -> Self {
+        Listener { name: name.to_string(), address }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct ServerStatus {
+    #[schema(read_only)]
+    pub version: String,
+    #[schema(read_only)]
+    pub uptime_seconds: u64,
+    #[schema(read_only)]
+    pub readiness: Readiness,
+    #[schema(read_only)]
+    pub network: NetworkStatus,
+    #[schema(read_only)]
+    pub cache: CacheUsage,
+    #[schema(read_only)]
+    pub commands: CommandQueueStatus,
+    #[schema(read_only)]
+    pub listeners: Vec<Listener>,
+}
+
+#[derive(Clone)]
+pub struct HealthService {
+    client_harness: Data<ClientHarness>,
+    hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
+    command_status: Data<Mutex<IndexMap<u128, CommandDetails>>>,
+    command_control: CommandControl,
+    ant_tp_config: AntTpConfig,
+    listeners: Vec<Listener>,
+    started_at: Instant,
+    is_reconnecting: Arc<AtomicBool>,
+}
+
+impl HealthService {
+    pub fn new(client_harness: Data<ClientHarness>, hybrid_cache: Data<HybridCache<String, Vec<u8>>>,
+               command_status: Data<Mutex<IndexMap<u128, CommandDetails>>>, command_control: CommandControl,
+               ant_tp_config: AntTpConfig, listeners: Vec<Listener>) -> Self {
+        HealthService {
+            client_harness, hybrid_cache, command_status, command_control, ant_tp_config, listeners,
+            started_at: Instant::now(), is_reconnecting: Arc::new(AtomicBool::new(false))
+        }
+    }
+
+    pub fn get_liveness(&self) -> Liveness {
+        Liveness { alive: true, uptime_seconds: self.started_at.elapsed().as_secs() }
+    }
+
+    /// Ready unless shutting down or the last attempt to connect to the network failed.
+    ///
+    /// Connecting is retried in the background while not ready, so that readiness recovers
+    /// without needing requests to be routed to this instance.
+    pub async fn get_readiness(&self) -> Readiness {
+        let network_status = self.get_network_status();
+        self.get_readiness_for(&network_status)
+    }
+
+    pub async fn get_status(&self) -> ServerStatus {
+        let network = self.get_network_status();
+        ServerStatus {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            readiness: self.get_readiness_for(&network),
+            network,
+            cache: self.get_cache_usage(),
+            commands: self.get_command_queue_status().await,
+            listeners: self.listeners.clone(),
+        }
+    }
+
+    fn get_readiness_for(&self, network_status: &NetworkStatus) -> Readiness {
+        let mut reasons = Vec::new();
+        if self.command_control.is_stopping() {
+            reasons.push("shutting down".to_string());
+        }
+        if let Some(last_connect_error) = &network_status.last_connect_error {
+            reasons.push(format!("failed to connect to network: {}", last_connect_error));
+            self.reconnect();
+        }
+        Readiness { ready: reasons.is_empty(), reasons }
+    }
+
+    fn get_network_status(&self) -> NetworkStatus {
+        self.client_harness.status()
+    }
+
+    fn reconnect(&self) {
+        if self.is_reconnecting.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let client_harness = self.client_harness.clone();
+        let is_reconnecting = self.is_reconnecting.clone();
+        tokio::spawn(async move {
+            if client_harness.get_ref().get_network_backend().await.is_ok() {
+                info!("reconnected to network");
+            }
+            is_reconnecting.store(false, Ordering::SeqCst);
+        });
+    }
+
+    fn get_cache_usage(&self) -> CacheUsage {
+        let statistics = self.hybrid_cache.statistics();
+        CacheUsage {
+            memory_usage: self.hybrid_cache.memory().usage() as u64,
+            memory_capacity: self.hybrid_cache.memory().capacity() as u64,
+            disk_capacity_bytes: self.ant_tp_config.immutable_disk_cache_size as u64 * 1024 * 1024,
+            disk_read_bytes: statistics.disk_read_bytes() as u64,
+            disk_write_bytes: statistics.disk_write_bytes() as u64,
+        }
+    }
+
+    async fn get_command_queue_status(&self) -> CommandQueueStatus {
+        let command_status = self.command_status.lock().await;
+        let count = |command_state: CommandState| command_status.values()
+            .filter(|command_details| command_details.state() == &command_state)
+            .count() as u64;
+        CommandQueueStatus {
+            waiting: count(CommandState::WAITING),
+            running: count(CommandState::RUNNING),
+            buffer_size: self.ant_tp_config.command_buffer_size as u64,
+            stopping: self.command_control.is_stopping(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use clap::Parser;
+    use evmlib::Network::ArbitrumOne;
+    use foyer::HybridCacheBuilder;
+    use ant_core::data::DataChunk;
+    use crate::client::command::Command;
+    use crate::client::command::chunk::create_chunk_command::CreateChunkCommand;
+
+    async fn create_health_service(args: &[&str]) -> (HealthService, Data<Mutex<IndexMap<u128, CommandDetails>>>, CommandControl) {
+        let ant_tp_config = AntTpConfig::try_parse_from(args).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let hybrid_cache = Data::new(HybridCacheBuilder::new().memory(1024).storage().build().await.unwrap());
+        let command_status = Data::new(Mutex::new(IndexMap::new()));
+        let command_control = CommandControl::new(10);
+        let listeners = vec![Listener::new("http", ant_tp_config.listen_address.to_string())];
+        let health_service = HealthService::new(
+            client_harness, hybrid_cache, command_status.clone(), command_control.clone(), ant_tp_config, listeners);
+        (health_service, command_status, command_control)
+    }
+
+    #[tokio::test]
+    async fn test_get_status() {
+        let (health_service, command_status, _) = create_health_service(&["anttp", "--fake-network"]).await;
+        let client_harness = health_service.client_harness.clone();
+        for command_state in [CommandState::WAITING, CommandState::WAITING, CommandState::RUNNING, CommandState::COMPLETED] {
+            let command: Box<dyn Command> = Box::new(CreateChunkCommand::new(client_harness.clone(), DataChunk::from_content(Bytes::from("chunk"))));
+            let mut command_details = CommandDetails::new(&command);
+            command_details.set_state(command_state);
+            command_status.lock().await.insert(command.id(), command_details);
+        }
+
+        let server_status = health_service.get_status().await;
+        assert!(server_status.readiness.ready);
+        assert_eq!(server_status.network.backend, "fake");
+        assert!(server_status.network.connected);
+        assert_eq!(server_status.commands.waiting, 2);
+        assert_eq!(server_status.commands.running, 1);
+        assert_eq!(server_status.cache.memory_capacity, 1024);
+        assert_eq!(server_status.listeners, vec![Listener::new("http", "0.0.0.0:18888".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_get_readiness_when_stopping() {
+        let (health_service, _, command_control) = create_health_service(&["anttp", "--fake-network"]).await;
+        assert!(health_service.get_liveness().alive);
+        assert!(health_service.get_readiness().await.ready);
+
+        command_control.stop();
+        let readiness = health_service.get_readiness().await;
+        assert!(!readiness.ready);
+        assert_eq!(readiness.reasons, vec!["shutting down".to_string()]);
+        assert!(health_service.get_liveness().alive);
+    }
+
+    #[tokio::test]
+    async fn test_get_readiness_after_connect_error() {
+        // local networks require bootstrap peers, so connecting fails without reaching the network
+        let (health_service, _, _) = create_health_service(&["anttp", "--evm-network", "local"]).await;
+        assert!(health_service.get_readiness().await.ready);
+
+        assert!(health_service.client_harness.get_network_backend().await.is_err());
+        let readiness = health_service.get_readiness().await;
+        assert!(!readiness.ready);
+        assert!(readiness.reasons[0].starts_with("failed to connect to network"));
+        assert!(!health_service.get_status().await.network.connected);
+    }
+}
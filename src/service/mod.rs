@@ -13,8 +13,16 @@ pub mod chunk_service;
 //pub mod graph_service;
 pub mod public_data_service;
 pub mod command_service;
+pub mod command_webhook_service;
+pub mod prefetch_service;
+pub mod cache_service;
+pub mod health_service;
+pub mod config_service;
+pub mod wallet_service;
+pub mod bundle_service;
 pub mod header_builder;
-//pub mod access_checker;
+pub mod access_checker;
+pub mod access_list_service;
 //pub mod bookmark_resolver;
 //pub mod pointer_name_resolver;
 //pub mod pnr_service;
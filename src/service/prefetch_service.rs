@@ -0,0 +1,156 @@
+use ant_core::data::XorName;
+use hex::FromHex;
+use log::{info, warn};
+use mockall_double::double;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+#[double]
+use crate::client::ArchiveCachingClient;
+#[double]
+use crate::client::CachingClient;
+#[double]
+use crate::client::ChunkCachingClient;
+use crate::client::command::Command;
+use crate::client::command::prefetch::prefetch_command::PrefetchCommand;
+use crate::error::GetError;
+use crate::error::chunk_error::ChunkError;
+#[double]
+use crate::service::resolver_service::ResolverService;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug)]
+pub struct Prefetch {
+    #[schema(read_only)]
+    pub address: String,
+    #[schema(read_only)]
+    pub command_id: String,
+}
+
+impl Prefetch {
+    pub fn new(address: String, command_id: String) -> Self {
+        Prefetch { address, command_id }
+    }
+}
+
+pub struct PrefetchService {
+    caching_client: CachingClient,
+    chunk_caching_client: ChunkCachingClient,
+    archive_caching_client: ArchiveCachingClient,
+    resolver_service: ResolverService,
+    concurrency: usize,
+}
+
+impl PrefetchService {
+    pub fn new(caching_client: CachingClient, chunk_caching_client: ChunkCachingClient, archive_caching_client: ArchiveCachingClient,
+               resolver_service: ResolverService, concurrency: usize) -> Self {
+        PrefetchService { caching_client, chunk_caching_client, archive_caching_client, resolver_service, concurrency }
+    }
+
+    pub async fn prefetch(&self, address: String) -> Result<Prefetch, ChunkError> {
+        let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
+        let xor_name = match XorName::from_hex(resolved_address.as_str()) {
+            Ok(xor_name) => xor_name,
+            Err(e) => return Err(ChunkError::GetError(GetError::BadAddress(e.to_string())))
+        };
+        let command = Box::new(
+            PrefetchCommand::new(self.chunk_caching_client.clone(), self.archive_caching_client.clone(), xor_name, self.concurrency)
+        );
+        let command_id = command.id();
+        self.caching_client.send_get_command(command).await?;
+        info!("Queued command to prefetch [{}]", resolved_address);
+        Ok(Prefetch::new(resolved_address, command_id.to_string()))
+    }
+
+    pub async fn prefetch_file(&self, path: &str) -> Result<Vec<Prefetch>, ChunkError> {
+        let addresses = match tokio::fs::read_to_string(path).await {
+            Ok(addresses) => addresses,
+            Err(e) => return Err(ChunkError::GetError(GetError::RecordNotFound(format!("Failed to read prefetch file [{}]: {}", path, e))))
+        };
+        let mut prefetches = Vec::new();
+        for address in addresses.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            match self.prefetch(address.to_string()).await {
+                Ok(prefetch) => prefetches.push(prefetch),
+                Err(e) => warn!("Failed to prefetch [{}] from prefetch file [{}]: [{:?}]", address, path, e),
+            }
+        }
+        Ok(prefetches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    use crate::client::{MockArchiveCachingClient, MockCachingClient, MockChunkCachingClient};
+    use crate::service::resolver_service::MockResolverService;
+
+    const ADDRESS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn create_test_service(send_count: usize) -> PrefetchService {
+        let mut mock_caching_client = MockCachingClient::default();
+        mock_caching_client
+            .expect_send_get_command()
+            .times(send_count)
+            .returning(|_| Ok(()));
+        let mut mock_chunk_caching_client = MockChunkCachingClient::default();
+        mock_chunk_caching_client
+            .expect_clone()
+            .returning(MockChunkCachingClient::default);
+        let mut mock_archive_caching_client = MockArchiveCachingClient::default();
+        mock_archive_caching_client
+            .expect_clone()
+            .returning(MockArchiveCachingClient::default);
+        let mut mock_resolver = MockResolverService::default();
+        mock_resolver
+            .expect_resolve_name()
+            .returning(|_| None);
+        PrefetchService::new(mock_caching_client, mock_chunk_caching_client, mock_archive_caching_client, mock_resolver, 4)
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_success() {
+        let service = create_test_service(1);
+        let result = service.prefetch(ADDRESS.to_string()).await;
+
+        assert!(result.is_ok());
+        let prefetch = result.unwrap();
+        assert_eq!(prefetch.address, ADDRESS);
+        assert!(!prefetch.command_id.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_bad_address_error() {
+        let service = create_test_service(0);
+        let result = service.prefetch("invalid_address".to_string()).await;
+
+        match result.unwrap_err() {
+            ChunkError::GetError(GetError::BadAddress(_)) => (),
+            _ => panic!("Expected BadAddress error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_file_skips_comments_and_blank_lines() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "# event content").unwrap();
+        writeln!(file, "{}", ADDRESS).unwrap();
+        writeln!(file).unwrap();
+        writeln!(file, "  {}  ", ADDRESS).unwrap();
+
+        let service = create_test_service(2);
+        let result = service.prefetch_file(file.path().to_str().unwrap()).await;
+
+        assert_eq!(result.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_file_missing_error() {
+        let service = create_test_service(0);
+        let result = service.prefetch_file("/nonexistent/prefetch.txt").await;
+
+        match result.unwrap_err() {
+            ChunkError::GetError(GetError::RecordNotFound(_)) => (),
+            _ => panic!("Expected RecordNotFound error"),
+        }
+    }
+}
@@ -13,6 +13,7 @@ use crate::error::public_data_error::PublicDataError;
 #[double]
 use crate::service::resolver_service::ResolverService;
 use crate::service::chunk_service::Chunk;
+use crate::model::quote::Quote;
 use mockall::mock;
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -33,6 +34,7 @@ mock! {
         pub async fn create_public_data(&self, bytes: Bytes, store_type: StoreType) -> Result<Chunk, PublicDataError>;
         pub async fn push_public_data(&self, address: String, store_type: StoreType) -> Result<Chunk, PublicDataError>;
         pub async fn get_public_data_binary(&self, address: String) -> Result<Bytes, PublicDataError>;
+        pub async fn quote_public_data(&self, bytes: Bytes) -> Result<Quote, PublicDataError>;
     }
     impl Clone for PublicDataService {
         fn clone(&self) -> Self;
@@ -51,6 +53,13 @@ impl PublicDataService {
         Ok(Chunk::new(None, Some(xor_name_hex)))
     }
 
+    /// Quotes uploading the public data, without uploading it.
+    pub async fn quote_public_data(&self, bytes: Bytes) -> Result<Quote, PublicDataError> {
+        let (xor_name, chunks_quote) = self.public_data_caching_client.data_quote_public(bytes).await?;
+        info!("Quoted [{}] for public data at [{}]", chunks_quote.cost, xor_name.encode_hex::<String>());
+        Ok(Quote::new(xor_name, chunks_quote))
+    }
+
     pub async fn push_public_data(&self, address: String, store_type: StoreType) -> Result<Chunk, PublicDataError> {
         let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
         let data_address = match XorName::from_hex(resolved_address.as_str()) {
@@ -76,7 +85,9 @@ impl PublicDataService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ant_core::data::U256;
     use crate::client::MockPublicDataCachingClient;
+    use crate::client::network_backend::ChunksQuote;
     use crate::service::resolver_service::MockResolverService;
 
     fn create_test_service(mock_client: MockPublicDataCachingClient) -> PublicDataService {
@@ -105,6 +116,26 @@ mod tests {
         assert_eq!(chunk.address, Some(expected_hex));
     }
 
+    #[tokio::test]
+    async fn test_quote_public_data_success() {
+        let mut mock_client = MockPublicDataCachingClient::default();
+        let xor_name = XorName::default();
+
+        mock_client
+            .expect_data_quote_public()
+            .times(1)
+            .returning(move |_| Ok((xor_name, ChunksQuote { chunks: 3, bytes: 300, chunks_stored: 1, bytes_stored: 100, cost: U256::from(200) })));
+
+        let service = create_test_service(mock_client);
+        let quote = service.quote_public_data(Bytes::from("test data")).await.unwrap();
+
+        assert_eq!(quote.address, xor_name.encode_hex::<String>());
+        assert_eq!(quote.chunks, 3);
+        assert_eq!(quote.chunks_stored, 1);
+        assert_eq!(quote.bytes_stored, 100);
+        assert_eq!(quote.cost, "200");
+    }
+
     #[tokio::test]
     async fn test_push_public_data_success() {
         let mut mock_client = MockPublicDataCachingClient::default();
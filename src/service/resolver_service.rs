@@ -1,18 +1,21 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use actix_http::header::{HeaderMap, IF_NONE_MATCH};
+use actix_web::web::Data;
 use ant_core::data::XorName;
 use hex::{FromHex, ToHex};
 use log::{debug, error, info};
 use mockall::mock;
 #[double]
 use crate::client::ArchiveCachingClient;
+use crate::controller::is_no_cache;
 /*#[double]
 use crate::client::PointerCachingClient;*/
 /*#[double]
 use crate::client::RegisterCachingClient;*/
 use crate::model::archive::Archive;
 use crate::model::resolve::Resolve;
-/*#[double]
-use crate::service::access_checker::AccessChecker;*/
+#[double]
+use crate::service::access_checker::AccessChecker;
 /*#[double]
 use crate::service::pointer_name_resolver::PointerNameResolver;*/
 /*#[double]
@@ -42,22 +45,22 @@ pub struct ResolverService {
     archive_caching_client: ArchiveCachingClient,
     /*pointer_caching_client: PointerCachingClient,
     register_caching_client: RegisterCachingClient,*/
-    /*access_checker: Data<tokio::sync::Mutex<AccessChecker>>,
-    bookmark_resolver: Data<tokio::sync::Mutex<BookmarkResolver>>,*/
+    access_checker: Data<tokio::sync::Mutex<AccessChecker>>,
+    /*bookmark_resolver: Data<tokio::sync::Mutex<BookmarkResolver>>,*/
     /*pointer_name_resolver: Data<PointerNameResolver>,*/
-    ttl_default: u64,
+    ttl_default: Data<AtomicU64>,
 }
 
 impl ResolverService {
     pub fn new(archive_caching_client: ArchiveCachingClient,
                /*pointer_caching_client: PointerCachingClient,
                register_caching_client: RegisterCachingClient,*/
-               /*access_checker: Data<tokio::sync::Mutex<AccessChecker>>,
-               bookmark_resolver: Data<tokio::sync::Mutex<BookmarkResolver>>,*/
+               access_checker: Data<tokio::sync::Mutex<AccessChecker>>,
+               /*bookmark_resolver: Data<tokio::sync::Mutex<BookmarkResolver>>,*/
                /*pointer_name_resolver: Data<PointerNameResolver>,*/
                ttl_default: u64,
     ) -> ResolverService {
-        ResolverService { archive_caching_client, /*pointer_caching_client, register_caching_client,*/ /*access_checker, bookmark_resolver,*/ /*pointer_name_resolver,*/ ttl_default }
+        ResolverService { archive_caching_client, /*pointer_caching_client, register_caching_client,*/ access_checker, /*bookmark_resolver,*/ /*pointer_name_resolver,*/ ttl_default: Data::new(AtomicU64::new(ttl_default)) }
     }
 }
 
@@ -66,8 +69,8 @@ mock! {
         pub fn new(archive_caching_client: ArchiveCachingClient,
                /*pointer_caching_client: PointerCachingClient,
                register_caching_client: RegisterCachingClient,*/
-               /*access_checker: Data<tokio::sync::Mutex<AccessChecker>>,
-               bookmark_resolver: Data<tokio::sync::Mutex<BookmarkResolver>>,*/
+               access_checker: Data<tokio::sync::Mutex<AccessChecker>>,
+               /*bookmark_resolver: Data<tokio::sync::Mutex<BookmarkResolver>>,*/
                /*pointer_name_resolver: Data<PointerNameResolver>,*/
                ttl_default: u64,
         ) -> Self;
@@ -76,6 +79,7 @@ mock! {
                              path: &str,
                              headers: &HeaderMap
         ) -> Option<ResolvedAddress>;
+        pub fn set_ttl_default(&self, ttl_default: u64);
         pub fn is_immutable_address(&self, chunk_address: &String) -> bool;
         pub fn is_mutable_address(&self, hex_address: &String) -> bool;
         pub async fn resolve_bookmark(&self, name: &String) -> Option<String>;
@@ -94,10 +98,9 @@ impl ResolverService {
     ) -> Option<ResolvedAddress> {
         let path_parts = self.get_path_parts(&hostname, &path).await;
         let (archive_addr, archive_file_name, file_path) = self.assign_path_parts(&path_parts);
-        /*let is_allowed_default = self.access_checker.lock().await.is_allowed_default();*/
-        let is_allowed_default = true;
+        // allowed once the access list allows any address resolved along the way
         self.resolve_archive_or_file(
-            &archive_addr, &archive_file_name, &file_path, false, is_allowed_default, headers, 0, self.ttl_default).await
+            &archive_addr, &archive_file_name, &file_path, false, false, headers, 0, self.ttl_default.load(Ordering::Relaxed)).await
     }
 
     async fn resolve_archive_or_file(
@@ -151,6 +154,9 @@ impl ResolverService {
             if !is_modified || !is_allowed {
                 Some(ResolvedAddress::new(true, None, archive_directory_xor_name, archive_file_path.clone(), is_resolved_from_mutable, is_modified, is_allowed, ttl))
             } else {
+                if is_no_cache(headers) {
+                    self.archive_caching_client.archive_negative_cache_bypass(&xor_name);
+                }
                 match self.archive_caching_client.archive_get(xor_name).await {
                     Ok(archive) => {
                         debug!("Found archive at [{}]", archive_directory_xor_name_hex);
@@ -192,9 +198,8 @@ impl ResolverService {
     }
 
     async fn is_allowed(&self, address: &String) -> bool {
-        /*let access_checker = self.access_checker.lock().await;
-        access_checker.is_allowed(address)*/
-        true
+        let access_checker = self.access_checker.lock().await;
+        access_checker.is_allowed(address)
     }
 
     /*async fn analyze_simple(&self, address: &String) -> Option<DataAddress> {
@@ -230,6 +235,11 @@ impl ResolverService {
         }
     }
     
+    /// Changes the TTL of resolved mutable addresses, as when the configuration is reloaded.
+    pub fn set_ttl_default(&self, ttl_default: u64) {
+        self.ttl_default.store(ttl_default, Ordering::Relaxed);
+    }
+
     pub fn is_immutable_address(&self, chunk_address: &String) -> bool {
         chunk_address.len() == 64 && XorName::from_hex(chunk_address).ok().is_some()
     }
@@ -322,8 +332,8 @@ mod tests {
     use super::*;
     use crate::client::{MockArchiveCachingClient/*, MockPointerCachingClient, MockRegisterCachingClient*/};
     /*use crate::service::pointer_name_resolver::{MockPointerNameResolver, ResolvedRecord};*/
-    /*use crate::service::bookmark_resolver::MockBookmarkResolver;
-    use crate::service::access_checker::MockAccessChecker;*/
+    /*use crate::service::bookmark_resolver::MockBookmarkResolver;*/
+    use crate::service::access_checker::MockAccessChecker;
     use tokio::sync::Mutex;
     use actix_http::header::HeaderMap;
 
@@ -331,16 +341,16 @@ mod tests {
         archive_caching_client: MockArchiveCachingClient,
         /*pointer_caching_client: MockPointerCachingClient,
         register_caching_client: MockRegisterCachingClient,*/
-        /*access_checker: MockAccessChecker,
-        bookmark_resolver: MockBookmarkResolver,*/
+        access_checker: MockAccessChecker,
+        /*bookmark_resolver: MockBookmarkResolver,*/
         /*pointer_name_resolver: MockPointerNameResolver,*/
     ) -> ResolverService {
         ResolverService::new(
             archive_caching_client,
             /*pointer_caching_client,
             register_caching_client,*/
-            /*Data::new(Mutex::new(access_checker)),
-            Data::new(Mutex::new(bookmark_resolver)),*/
+            Data::new(Mutex::new(access_checker)),
+            /*Data::new(Mutex::new(bookmark_resolver)),*/
             /*Data::new(pointer_name_resolver),*/
             3600,
         )
@@ -352,8 +362,8 @@ mod tests {
             MockArchiveCachingClient::default(),
             /*MockPointerCachingClient::default(),
             MockRegisterCachingClient::default(),*/
-            /*Data::new(Mutex::new(MockAccessChecker::default())),
-            Data::new(Mutex::new(MockBookmarkResolver::default())),*/
+            Data::new(Mutex::new(MockAccessChecker::default())),
+            /*Data::new(Mutex::new(MockBookmarkResolver::default())),*/
             /*Data::new(MockPointerNameResolver::default()),*/
             3600,
         );
@@ -363,6 +373,30 @@ mod tests {
         assert!(!service.is_immutable_address(&invalid_hex));
     }
 
+    #[test]
+    fn test_set_ttl_default_is_shared_by_clones() {
+        let mut mock_archive = MockArchiveCachingClient::default();
+        mock_archive.expect_clone().returning(MockArchiveCachingClient::default);
+        let service = create_test_service(mock_archive, MockAccessChecker::default());
+        let cloned_service = service.clone();
+        service.set_ttl_default(60);
+        assert_eq!(cloned_service.ttl_default.load(Ordering::Relaxed), 60);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_denied_address() {
+        let address = "a40e045a6fbed33b27039aa8383c9dbf286e19a7265141c2da3085e0c8571527".to_string();
+        let mut mock_access = MockAccessChecker::default();
+        mock_access.expect_is_allowed().with(mockall::predicate::eq(address.clone())).return_const(false);
+        // the archive is not fetched for a denied address
+        let service = create_test_service(MockArchiveCachingClient::default(), mock_access);
+
+        let resolved_address = service.resolve("localhost", &address, &HeaderMap::new()).await.unwrap();
+        assert!(resolved_address.is_found);
+        assert!(!resolved_address.is_allowed);
+        assert!(resolved_address.archive.is_none());
+    }
+
     /*#[tokio::test]
     async fn test_is_mutable_address() {
         let service = ResolverService::new(
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use actix_multipart::form::MultipartForm;
 use actix_web::web::Bytes;
-use ant_core::data::{Wallet, XorName};
+use ant_core::data::XorName;
 use log::{debug, info, warn};
 use sanitize_filename::sanitize;
 use uuid::Uuid;
@@ -29,9 +29,11 @@ use crate::error::UpdateError;
 use crate::controller::StoreType;
 use crate::model::tarchive::Tarchive;
 use crate::model::archive::Archive;
+use crate::model::quote::Quote;
 use crate::config::anttp_config::AntTpConfig;
 use tokio::sync::Mutex as TokioMutex;
 use crate::service::archive_service::{ArchiveRaw, ArchiveResponse, PublicArchiveForm, Upload};
+use crate::client::command::command_batch::CommandBatch;
 
 #[derive(Clone)]
 pub struct TarchiveService {
@@ -78,21 +80,10 @@ impl TarchiveService {
         }
     }
 
-    pub async fn create_tarchive(&self, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>, evm_wallet: Wallet, store_type: StoreType) -> Result<Upload, TarchiveError> {
+    pub async fn create_tarchive(&self, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>, store_type: StoreType) -> Result<Upload, TarchiveError> {
         info!("Creating new tarchive");
         let tmp_dir = Self::create_tmp_dir()?;
-        let tar_path = tmp_dir.join("archive.tar");
-
-        // Create new tar file
-        {
-            let tar_file = fs::File::create(&tar_path)?;
-            let mut builder = Builder::new(tar_file);
-            self.build_tar_from_form(&mut builder, target_path, tarchive_form)?;
-            builder.finish()?;
-        }
-
-        // Generate index and create final tar
-        let final_tar_path = self.rebuild_with_index(&tar_path, &tmp_dir)?;
+        let final_tar_path = self.build_tarchive(&tmp_dir, target_path, tarchive_form)?;
 
         // Upload as public data
         let result = self.upload_tar(&final_tar_path, store_type).await;
@@ -100,13 +91,25 @@ impl TarchiveService {
         result
     }
 
-    pub async fn push_tarchive(&self, address: String, evm_wallet: Wallet, store_type: StoreType) -> Result<Upload, TarchiveError> {
+    /// Quotes creating a tarchive from the form, without uploading it.
+    pub async fn quote_tarchive(&self, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>) -> Result<Quote, TarchiveError> {
+        info!("Quoting new tarchive");
+        let tmp_dir = Self::create_tmp_dir()?;
+        let final_tar_path = self.build_tarchive(&tmp_dir, target_path, tarchive_form)?;
+
+        let result = self.quote_tar(&final_tar_path).await;
+        Self::purge_tmp_dir(&tmp_dir);
+        result
+    }
+
+    pub async fn push_tarchive(&self, address: String, store_type: StoreType) -> Result<Upload, TarchiveError> {
         let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
-        Ok(self.public_data_service.push_public_data(resolved_address, store_type).await
-            .map(|chunk| Upload::new(chunk.address))?)
+        let is_network = store_type == StoreType::Network;
+        let (command_batch, result) = CommandBatch::join_or_start(self.public_data_service.push_public_data(resolved_address, store_type)).await;
+        Ok(Self::batch_upload(result?.address, command_batch, is_network))
     }
 
-    pub async fn update_tarchive(&self, address: String, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>, evm_wallet: Wallet, store_type: StoreType) -> Result<Upload, TarchiveError> {
+    pub async fn update_tarchive(&self, address: String, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>, store_type: StoreType) -> Result<Upload, TarchiveError> {
         // Acquire per-address lock to prevent concurrent updates to the same tarchive (based on input name/address)
         let addr_lock = {
             let mut locks = self.address_locks.lock().await;
@@ -117,6 +120,42 @@ impl TarchiveService {
         let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
         info!("Updating tarchive at address [{}]", resolved_address);
         let tmp_dir = Self::create_tmp_dir()?;
+        let final_tar_path = self.build_updated_tarchive(&tmp_dir, resolved_address, target_path, tarchive_form).await?;
+
+        // Upload as public data
+        let result = self.upload_tar(&final_tar_path, store_type).await;
+        Self::purge_tmp_dir(&tmp_dir);
+        result
+    }
+
+    /// Quotes updating the tarchive with files from the form, without uploading it.
+    pub async fn quote_tarchive_update(&self, address: String, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>) -> Result<Quote, TarchiveError> {
+        let resolved_address = self.resolver_service.resolve_name(&address).await.unwrap_or(address);
+        info!("Quoting update of tarchive at address [{}]", resolved_address);
+        let tmp_dir = Self::create_tmp_dir()?;
+        let final_tar_path = self.build_updated_tarchive(&tmp_dir, resolved_address, target_path, tarchive_form).await?;
+
+        let result = self.quote_tar(&final_tar_path).await;
+        Self::purge_tmp_dir(&tmp_dir);
+        result
+    }
+
+    fn build_tarchive(&self, tmp_dir: &PathBuf, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>) -> Result<PathBuf, TarchiveError> {
+        let tar_path = tmp_dir.join("archive.tar");
+
+        // Create new tar file
+        {
+            let tar_file = fs::File::create(&tar_path)?;
+            let mut builder = Builder::new(tar_file);
+            self.build_tar_from_form(&mut builder, target_path, tarchive_form)?;
+            builder.finish()?;
+        }
+
+        // Generate index and create final tar
+        self.rebuild_with_index(&tar_path, tmp_dir)
+    }
+
+    async fn build_updated_tarchive(&self, tmp_dir: &PathBuf, resolved_address: String, target_path: Option<String>, tarchive_form: MultipartForm<PublicArchiveForm>) -> Result<PathBuf, TarchiveError> {
         let tar_path = tmp_dir.join("archive.tar");
 
         // Download existing tar
@@ -150,15 +189,10 @@ impl TarchiveService {
         }
 
         // Generate index and create final tar
-        let final_tar_path = self.rebuild_with_index(&updated_tar_path, &tmp_dir)?;
-
-        // Upload as public data
-        let result = self.upload_tar(&final_tar_path, store_type).await;
-        Self::purge_tmp_dir(&tmp_dir);
-        result
+        self.rebuild_with_index(&updated_tar_path, tmp_dir)
     }
 
-    pub async fn truncate_tarchive(&self, address: String, path: String, evm_wallet: Wallet, store_type: StoreType) -> Result<Upload, TarchiveError> {
+    pub async fn truncate_tarchive(&self, address: String, path: String, store_type: StoreType) -> Result<Upload, TarchiveError> {
         // Acquire per-address lock to prevent concurrent truncations to the same tarchive (based on input name/address)
         let addr_lock = {
             let mut locks = self.address_locks.lock().await;
@@ -277,8 +311,19 @@ impl TarchiveService {
 
     async fn upload_tar(&self, tar_path: &PathBuf, store_type: StoreType) -> Result<Upload, TarchiveError> {
         let tar_data = fs::read(tar_path)?;
-        let chunk = self.public_data_service.create_public_data(Bytes::from(tar_data), store_type).await?;
-        Ok(Upload::new(chunk.address))
+        let is_network = store_type == StoreType::Network;
+        let (command_batch, result) = CommandBatch::join_or_start(self.public_data_service.create_public_data(Bytes::from(tar_data), store_type)).await;
+        Ok(Self::batch_upload(result?.address, command_batch, is_network))
+    }
+
+    async fn quote_tar(&self, tar_path: &PathBuf) -> Result<Quote, TarchiveError> {
+        let tar_data = fs::read(tar_path)?;
+        Ok(self.public_data_service.quote_public_data(Bytes::from(tar_data)).await?)
+    }
+
+    // only network uploads queue commands, so only they have a batch to follow
+    fn batch_upload(address: Option<String>, command_batch: CommandBatch, is_network: bool) -> Upload {
+        Upload { address, batch_id: is_network.then_some(command_batch.id) }
     }
 
     fn create_tmp_dir() -> Result<PathBuf, io::Error> {
@@ -0,0 +1,281 @@
+use std::path::Path;
+use std::sync::RwLock;
+use actix_web::web::Data;
+use ant_core::data::Wallet;
+use evmlib::Network;
+use log::info;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use crate::client::client_harness::ClientHarness;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::client::wallet_ledger::WalletLedger;
+use crate::config::anttp_config::AntTpConfig;
+use crate::config::key_store::{KeyStore, KeyType};
+use crate::error::{GetError, UpdateError};
+use crate::error::wallet_error::WalletError;
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct WalletDetails {
+    #[schema(read_only)]
+    pub address: String,
+    #[schema(read_only)]
+    pub network: String,
+    /// Payment token balance, in atto tokens.
+    #[schema(read_only)]
+    pub token_balance: String,
+    /// Gas token balance, in wei.
+    #[schema(read_only)]
+    pub gas_balance: String,
+    #[schema(read_only)]
+    pub payment_vault_address: String,
+    /// Payment tokens which the payment vault is approved to spend, in atto tokens.
+    #[schema(read_only)]
+    pub payment_allowance: String,
+    /// Whether the payment vault is approved to spend tokens. If not, approval is made with the first payment.
+    #[schema(read_only)]
+    pub payment_approved: bool,
+}
+
+/// What a wallet has paid for uploads since startup.
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct WalletSpend {
+    /// Name of the keystore wallet, or none for the default wallet.
+    #[schema(read_only)]
+    pub name: Option<String>,
+    #[schema(read_only)]
+    pub uploads: u64,
+    #[schema(read_only)]
+    pub bytes_uploaded: u64,
+    /// Storage paid for, in atto tokens.
+    #[schema(read_only)]
+    pub storage_cost: String,
+    /// Gas paid, in wei.
+    #[schema(read_only)]
+    pub gas_cost: String,
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+pub struct WalletLoad {
+    /// Name of the EVM wallet in the key store.
+    pub name: String,
+}
+
+pub struct WalletService {
+    client_harness: Data<ClientHarness>,
+    ant_tp_config: AntTpConfig,
+    wallet_ledger: WalletLedger,
+    wallet_api_keys: RwLock<Vec<(String, String)>>,
+}
+
+impl WalletService {
+    pub fn new(client_harness: Data<ClientHarness>, ant_tp_config: AntTpConfig, wallet_ledger: WalletLedger) -> Self {
+        WalletService { client_harness, ant_tp_config, wallet_ledger, wallet_api_keys: RwLock::new(Vec::new()) }
+    }
+
+    /// Details of the wallet selected by the request, otherwise the default wallet.
+    pub async fn get_wallet(&self) -> Result<WalletDetails, WalletError> {
+        let wallet = self.client_harness.get_paying_wallet()?;
+        let wallet_backend = self.client_harness.get_wallet_backend()?;
+        let (token_balance, gas_balance, payment_allowance) = tokio::try_join!(
+            wallet_backend.token_balance(),
+            wallet_backend.gas_balance(),
+            wallet_backend.payment_allowance()
+        )?;
+        Ok(WalletDetails {
+            address: wallet_backend.address().to_string(),
+            network: wallet.network().identifier().to_string(),
+            token_balance: token_balance.to_string(),
+            gas_balance: gas_balance.to_string(),
+            payment_vault_address: wallet_backend.payment_vault_address().to_string(),
+            payment_allowance: payment_allowance.to_string(),
+            payment_approved: !payment_allowance.is_zero(),
+        })
+    }
+
+    /// Names of the EVM wallets in the key store.
+    pub async fn get_wallets(&self) -> Result<Vec<String>, WalletError> {
+        let key_store = self.open_key_store().await?;
+        Ok(key_store.names().into_iter()
+            .filter(|name| key_store.get(name).is_ok_and(|stored_key| stored_key.key_type == KeyType::Evm))
+            .collect())
+    }
+
+    /// Loads an EVM wallet from the key store to pay for uploads.
+    pub async fn load_wallet(&self, name: String) -> Result<WalletDetails, WalletError> {
+        let key_store = self.open_key_store().await?;
+        let wallet = Self::read_wallet(&key_store, &name, self.client_harness.get_wallet().network().clone())?;
+        info!("loaded wallet [{}] with address [{}] from key store", name, wallet.address());
+        self.client_harness.set_wallet(wallet);
+        self.get_wallet().await
+    }
+
+    /// Loads the key store wallets which API keys select, so that requests may pay with them.
+    ///
+    /// The API keys are only accepted once all of their wallets are loaded.
+    pub async fn load_api_key_wallets(&self, wallet_api_keys: Vec<(String, String)>) -> Result<(), WalletError> {
+        if !wallet_api_keys.is_empty() {
+            let key_store = self.open_key_store().await?;
+            for (_, name) in wallet_api_keys.iter() {
+                let wallet = Self::read_wallet(&key_store, name, self.client_harness.get_wallet().network().clone())?;
+                self.client_harness.add_wallet(name.clone(), wallet);
+            }
+        }
+        *self.wallet_api_keys.write().unwrap() = wallet_api_keys;
+        Ok(())
+    }
+
+    /// Finds the wallet selected by a request's API key, which may be absent. Unknown API keys are refused.
+    pub fn get_command_wallet(&self, api_key: Option<&str>) -> Result<Option<CommandWallet>, UpdateError> {
+        CommandWallet::from_api_key(api_key, &self.wallet_api_keys.read().unwrap())
+    }
+
+    /// What each wallet has paid for uploads since startup.
+    pub fn get_wallet_spends(&self) -> Vec<WalletSpend> {
+        self.wallet_ledger.totals().into_iter()
+            .map(|(name, wallet_totals)| WalletSpend {
+                name,
+                uploads: wallet_totals.uploads,
+                bytes_uploaded: wallet_totals.bytes_uploaded,
+                storage_cost: wallet_totals.storage_cost.to_string(),
+                gas_cost: wallet_totals.gas_cost.to_string(),
+            })
+            .collect()
+    }
+
+    /// Opens the key store on a blocking thread, as deriving its key is deliberately slow.
+    async fn open_key_store(&self) -> Result<KeyStore, WalletError> {
+        if self.ant_tp_config.key_store_file.is_empty() {
+            return Err(UpdateError::InvalidData("key store file is not configured".to_string()).into());
+        }
+        let key_store_file = self.ant_tp_config.key_store_file.clone();
+        let key_store_passphrase = self.ant_tp_config.key_store_passphrase.clone();
+        tokio::task::spawn_blocking(move || KeyStore::open(Path::new(&key_store_file), &key_store_passphrase))
+            .await
+            .map_err(|e| UpdateError::TemporaryStorage(e.to_string()))?
+            .map_err(|e| UpdateError::TemporaryStorage(e.to_string()).into())
+    }
+
+    fn read_wallet(key_store: &KeyStore, name: &String, evm_network: Network) -> Result<Wallet, WalletError> {
+        let stored_key = key_store.get(name)
+            .map_err(|_| GetError::RecordNotFound(format!("wallet [{}] not found in key store", name)))?;
+        if stored_key.key_type != KeyType::Evm {
+            return Err(UpdateError::InvalidData(format!("key [{}] is not an EVM wallet", name)).into());
+        }
+        Ok(Wallet::new_from_private_key(evm_network, &stored_key.private_key)
+            .map_err(|e| UpdateError::InvalidData(format!("invalid private key for wallet [{}]: {}", name, e)))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use evmlib::Network::ArbitrumOne;
+    use tempfile::TempDir;
+
+    fn create_wallet_service(key_store_file: &str) -> WalletService {
+        let ant_tp_config = AntTpConfig::try_parse_from([
+            "anttp", "--fake-network", "--fake-wallet-balance", "1000", "--key-store-file", key_store_file, "--key-store-passphrase", "passphrase"
+        ]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        WalletService::new(client_harness, ant_tp_config, WalletLedger::new())
+    }
+
+    fn create_key_store_file(directory: &TempDir, evm_keys: &[(&str, &str)]) -> String {
+        let path = directory.path().join("keys.json");
+        let mut key_store = KeyStore::open_or_create(&path, "passphrase").unwrap().with_iterations(1_000);
+        for (name, private_key) in evm_keys {
+            key_store.import(name, KeyType::Evm, private_key).unwrap();
+        }
+        key_store.generate("app", KeyType::MlDsa).unwrap();
+        key_store.save().unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_get_wallet() {
+        let directory = tempfile::tempdir().unwrap();
+        let wallet_service = create_wallet_service(&create_key_store_file(&directory, &[]));
+        let wallet_details = wallet_service.get_wallet().await.unwrap();
+        assert_eq!(wallet_details.network, ArbitrumOne.identifier());
+        assert_eq!(wallet_details.token_balance, "1000");
+        assert_eq!(wallet_details.gas_balance, "1000");
+        assert!(wallet_details.payment_approved);
+    }
+
+    #[tokio::test]
+    async fn test_load_wallet() {
+        let directory = tempfile::tempdir().unwrap();
+        let private_key = Wallet::random_private_key();
+        let wallet_service = create_wallet_service(&create_key_store_file(&directory, &[("team-a", &private_key)]));
+        assert_eq!(wallet_service.get_wallets().await.unwrap(), vec!["team-a".to_string()]);
+
+        let wallet_details = wallet_service.load_wallet("team-a".to_string()).await.unwrap();
+        let expected_address = Wallet::new_from_private_key(ArbitrumOne, &private_key).unwrap().address();
+        assert_eq!(wallet_details.address, expected_address.to_string());
+        assert_eq!(wallet_service.get_wallet().await.unwrap().address, expected_address.to_string());
+
+        assert!(matches!(wallet_service.load_wallet("team-b".to_string()).await, Err(WalletError::GetError(GetError::RecordNotFound(_)))));
+        assert!(matches!(wallet_service.load_wallet("app".to_string()).await, Err(WalletError::UpdateError(UpdateError::InvalidData(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_load_wallet_with_wrong_passphrase() {
+        let directory = tempfile::tempdir().unwrap();
+        let key_store_file = create_key_store_file(&directory, &[("team-a", &Wallet::random_private_key())]);
+        let ant_tp_config = AntTpConfig::try_parse_from([
+            "anttp", "--fake-network", "--key-store-file", &key_store_file, "--key-store-passphrase", "wrong"
+        ]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let wallet_service = WalletService::new(client_harness, ant_tp_config, WalletLedger::new());
+        assert!(matches!(wallet_service.load_wallet("team-a".to_string()).await, Err(WalletError::UpdateError(UpdateError::TemporaryStorage(_)))));
+    }
+
+    fn wallet_api_keys(wallet_api_keys: &[(&str, &str)]) -> Vec<(String, String)> {
+        wallet_api_keys.iter().map(|(api_key, name)| (api_key.to_string(), name.to_string())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_load_api_key_wallets() {
+        let directory = tempfile::tempdir().unwrap();
+        let private_key = Wallet::random_private_key();
+        let wallet_service = create_wallet_service(&create_key_store_file(&directory, &[("team-a", &private_key)]));
+        assert!(wallet_service.get_command_wallet(Some("key-a")).is_err());
+        wallet_service.load_api_key_wallets(wallet_api_keys(&[("key-a", "team-a")])).await.unwrap();
+
+        let command_wallet = wallet_service.get_command_wallet(Some("key-a")).unwrap();
+        assert_eq!(command_wallet, Some(CommandWallet::new("team-a".to_string())));
+        assert_eq!(wallet_service.get_command_wallet(None).unwrap(), None);
+        assert!(wallet_service.get_command_wallet(Some("key-b")).is_err());
+
+        let expected_address = Wallet::new_from_private_key(ArbitrumOne, &private_key).unwrap().address().to_string();
+        let wallet_details = command_wallet.unwrap().scope(wallet_service.get_wallet()).await.unwrap();
+        assert_eq!(wallet_details.address, expected_address);
+        assert_ne!(wallet_service.get_wallet().await.unwrap().address, expected_address);
+    }
+
+    #[tokio::test]
+    async fn test_load_api_key_wallets_not_in_key_store() {
+        let directory = tempfile::tempdir().unwrap();
+        let wallet_service = create_wallet_service(&create_key_store_file(&directory, &[]));
+        assert!(matches!(wallet_service.load_api_key_wallets(wallet_api_keys(&[("key-a", "team-a")])).await, Err(WalletError::GetError(GetError::RecordNotFound(_)))));
+        assert!(wallet_service.get_command_wallet(Some("key-a")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_api_key_wallets_without_api_keys() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--fake-network"]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let wallet_service = WalletService::new(client_harness, ant_tp_config, WalletLedger::new());
+        wallet_service.load_api_key_wallets(vec![]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_wallet_without_keystore() {
+        let ant_tp_config = AntTpConfig::try_parse_from(["anttp", "--fake-network"]).unwrap();
+        let client_harness = Data::new(ClientHarness::new(ArbitrumOne, ant_tp_config.clone()));
+        let wallet_service = WalletService::new(client_harness, ant_tp_config, WalletLedger::new());
+        assert!(matches!(wallet_service.load_wallet("team-a".to_string()).await, Err(WalletError::UpdateError(UpdateError::InvalidData(_)))));
+        assert!(wallet_service.get_wallets().await.is_err());
+    }
+}
@@ -15,7 +15,8 @@ use crate::controller::StoreType;
 use crate::error::archive_error::ArchiveError;
 use crate::service::archive_service::{ArchiveForm, ArchiveResponse, Upload, ArchiveRaw};
 use crate::model::archive::ArchiveType;
-use crate::tool::McpTool;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::tool::{parse_command_schedule, McpTool};
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
 pub struct ArchiveTypeParam(pub String);
@@ -30,6 +31,12 @@ struct CreateArchiveRequest {
     path: Option<String>,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional priority of queued commands; higher priorities run first")]
+    priority: Option<i32>,
+    #[schemars(description = "Optional RFC 3339 time before which queued commands will not run")]
+    not_before: Option<String>,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
@@ -44,6 +51,12 @@ struct UpdateArchiveRequest {
     path: Option<String>,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional priority of queued commands; higher priorities run first")]
+    priority: Option<i32>,
+    #[schemars(description = "Optional RFC 3339 time before which queued commands will not run")]
+    not_before: Option<String>,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
@@ -56,6 +69,8 @@ struct TruncateArchiveRequest {
     path: String,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
@@ -76,6 +91,8 @@ struct PushArchiveRequest {
     address: String,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 impl From<ArchiveResponse> for CallToolResult {
@@ -112,53 +129,53 @@ impl McpTool {
     #[tool(description = "Create a new archive")]
     async fn create_archive(
         &self,
-        Parameters(CreateArchiveRequest { archive_type, files, path, store_type }): Parameters<CreateArchiveRequest>,
+        Parameters(CreateArchiveRequest { archive_type, files, path, store_type, priority, not_before, api_key }): Parameters<CreateArchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let archive_form = self.map_to_archive_multipart_form(files)?;
         let atype = self.parse_archive_type(&archive_type)?;
+        let command_schedule = parse_command_schedule(priority, not_before)?;
+        let command_wallet = self.parse_command_wallet(api_key)?;
         
         match atype {
-            ArchiveType::Public => Ok(self.archive_service.create_public_archive(
+            ArchiveType::Public => Ok(CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.archive_service.create_public_archive(
                 path,
                 archive_form,
-                self.evm_wallet.get_ref().clone(),
                 StoreType::from(store_type)
-            ).await?.into()),
-            ArchiveType::Tarchive => Ok(self.archive_service.create_tarchive(
+            ))).await?.into()),
+            ArchiveType::Tarchive => Ok(CommandWallet::scope_optional(command_wallet, command_schedule.scope(self.archive_service.create_tarchive(
                 path,
                 archive_form,
-                self.evm_wallet.get_ref().clone(),
                 StoreType::from(store_type)
-            ).await?.into()),
+            ))).await?.into()),
         }
     }
 
     #[tool(description = "Update an existing archive")]
     async fn update_archive(
         &self,
-        Parameters(UpdateArchiveRequest { address, files, path, store_type, .. }): Parameters<UpdateArchiveRequest>,
+        Parameters(UpdateArchiveRequest { address, files, path, store_type, priority, not_before, api_key, .. }): Parameters<UpdateArchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let archive_form = self.map_to_archive_multipart_form(files)?;
-        Ok(self.archive_service.update_archive(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, parse_command_schedule(priority, not_before)?.scope(self.archive_service.update_archive(
             address,
             path,
             archive_form,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(store_type),
-        ).await?.into())
+        ))).await?.into())
     }
 
     #[tool(description = "Truncate an archive (delete file or directory)")]
     async fn truncate_archive(
         &self,
-        Parameters(TruncateArchiveRequest { address, path, store_type, .. }): Parameters<TruncateArchiveRequest>,
+        Parameters(TruncateArchiveRequest { address, path, store_type, api_key, .. }): Parameters<TruncateArchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        Ok(self.archive_service.truncate_archive(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, self.archive_service.truncate_archive(
             address,
             path,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(store_type),
-        ).await?.into())
+        )).await?.into())
     }
 
     #[tool(description = "Get a file from an archive")]
@@ -172,13 +189,13 @@ impl McpTool {
     #[tool(description = "Push a staged archive from cache to a target store type (default: network)")]
     async fn push_archive(
         &self,
-        Parameters(PushArchiveRequest { address, store_type, .. }): Parameters<PushArchiveRequest>,
+        Parameters(PushArchiveRequest { address, store_type, api_key, .. }): Parameters<PushArchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        Ok(self.archive_service.push_archive(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, self.archive_service.push_archive(
             address,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(store_type),
-        ).await?.into())
+        )).await?.into())
     }
 
     pub(crate) fn map_to_archive_multipart_form(&self, files: HashMap<String, String>) -> Result<MultipartForm<ArchiveForm>, ErrorData> {
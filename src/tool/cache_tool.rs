@@ -0,0 +1,24 @@
+#![allow(dead_code)]
+
+use crate::service::cache_service::CacheStats;
+use crate::tool::McpTool;
+use rmcp::model::CallToolResult;
+use rmcp::{tool, tool_router, ErrorData};
+use serde_json::json;
+
+impl From<CacheStats> for CallToolResult {
+    fn from(cache_stats: CacheStats) -> CallToolResult {
+        CallToolResult::structured(json!(cache_stats))
+    }
+}
+
+#[tool_router(router = cache_tool_router, vis = "pub")]
+impl McpTool {
+
+    #[tool(description = "Get cache statistics, including negative cache hits and corrupted chunks found")]
+    async fn get_cache_stats(
+        &self,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.cache_service.get_cache_stats().into())
+    }
+}
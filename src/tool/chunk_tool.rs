@@ -10,7 +10,8 @@ use serde_json::json;
 use crate::controller::StoreType;
 use crate::error::chunk_error::ChunkError;
 use crate::service::chunk_service::Chunk;
-use crate::tool::McpTool;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::tool::{parse_command_schedule, McpTool};
 
 #[derive(Debug, Deserialize, JsonSchema)]
 struct CreateChunkRequest {
@@ -18,6 +19,18 @@ struct CreateChunkRequest {
     content: String,
     #[schemars(description = "Store chunk on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional priority of queued commands; higher priorities run first")]
+    priority: Option<i32>,
+    #[schemars(description = "Optional RFC 3339 time before which queued commands will not run")]
+    not_before: Option<String>,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct QuoteChunkRequest {
+    #[schemars(description = "Base64 encoded content of the chunk")]
+    content: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -44,12 +57,21 @@ impl McpTool {
     #[tool(description = "Create a new chunk with base64 encoded content")]
     async fn create_chunk(
         &self,
-        Parameters(CreateChunkRequest { content, store_type }): Parameters<CreateChunkRequest>,
+        Parameters(CreateChunkRequest { content, store_type, priority, not_before, api_key }): Parameters<CreateChunkRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let chunk = Chunk::new(Some(content), None);
-        Ok(self.chunk_service.create_chunk(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, parse_command_schedule(priority, not_before)?.scope(self.chunk_service.create_chunk(
             chunk, StoreType::from(store_type)
-        ).await?.into())
+        ))).await?.into())
+    }
+
+    #[tool(description = "Quote the cost of uploading a chunk with base64 encoded content, without uploading it")]
+    async fn quote_chunk(
+        &self,
+        Parameters(QuoteChunkRequest { content }): Parameters<QuoteChunkRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.chunk_service.quote_chunk(Chunk::new(Some(content), None)).await?.into())
     }
 
     #[tool(description = "Get a chunk by its address")]
@@ -1,17 +1,51 @@
 #![allow(dead_code)]
 
-use crate::service::command_service::CommandList;
+use crate::error::command_error::CommandError;
+use crate::service::command_service::{Batch, Command, CommandList};
 use crate::tool::McpTool;
+use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::{CallToolResult, ErrorCode};
-use rmcp::{tool, tool_router, ErrorData};
+use rmcp::schemars::JsonSchema;
+use rmcp::{schemars, tool, tool_router, ErrorData};
+use serde::Deserialize;
 use serde_json::json;
 
+#[derive(Debug, Deserialize, JsonSchema)]
+struct CommandRequest {
+    #[schemars(description = "Id of the command")]
+    id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct BatchRequest {
+    #[schemars(description = "Id of the command batch")]
+    id: String,
+}
+
 impl From<CommandList> for CallToolResult {
     fn from(command_list: CommandList) -> CallToolResult {
         CallToolResult::structured(json!(command_list))
     }
 }
 
+impl From<Command> for CallToolResult {
+    fn from(command: Command) -> CallToolResult {
+        CallToolResult::structured(json!(command))
+    }
+}
+
+impl From<Batch> for CallToolResult {
+    fn from(batch: Batch) -> CallToolResult {
+        CallToolResult::structured(json!(batch))
+    }
+}
+
+impl From<CommandError> for ErrorData {
+    fn from(command_error: CommandError) -> Self {
+        ErrorData::new(ErrorCode::INTERNAL_ERROR, command_error.to_string(), None)
+    }
+}
+
 #[tool_router(router = command_tool_router, vis = "pub")]
 impl McpTool {
 
@@ -23,4 +57,62 @@ impl McpTool {
             .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
             .into())
     }
+
+    #[tool(description = "Get a command queued or executed by id, including its attempts, error, resulting address and cost")]
+    async fn get_command(
+        &self,
+        Parameters(CommandRequest { id }): Parameters<CommandRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.command_service.get_command(id).await?.into())
+    }
+
+    #[tool(description = "Cancel a waiting or running command by id")]
+    async fn cancel_command(
+        &self,
+        Parameters(CommandRequest { id }): Parameters<CommandRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.command_service.cancel_command(id).await?.into())
+    }
+
+    #[tool(description = "Retry a failed command by id")]
+    async fn retry_command(
+        &self,
+        Parameters(CommandRequest { id }): Parameters<CommandRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.command_service.retry_command(id).await?.into())
+    }
+
+    #[tool(description = "Purge completed, aborted and failed commands from the command list")]
+    async fn purge_commands(
+        &self,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.command_service.purge_commands().await?.into())
+    }
+
+    #[tool(description = "Get aggregate progress, bytes uploaded and failures of a batch of related commands by id")]
+    async fn get_command_batch(
+        &self,
+        Parameters(BatchRequest { id }): Parameters<BatchRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.command_service.get_batch(id).await?.into())
+    }
+
+    #[tool(description = "Retry all failed commands in a batch by id")]
+    async fn retry_command_batch(
+        &self,
+        Parameters(BatchRequest { id }): Parameters<BatchRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.command_service.retry_batch(id).await?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_request_serialization() {
+        let request: CommandRequest = serde_json::from_str(r#"{"id": "42"}"#).unwrap();
+        assert_eq!(request.id, "42");
+    }
 }
@@ -13,11 +13,17 @@ use crate::service::tarchive_service::TarchiveService;
 use crate::service::resolver_service::ResolverService;
 /*use crate::service::key_value_service::KeyValueService;*/
 use crate::service::crypto_service::CryptoService;
+use crate::service::prefetch_service::PrefetchService;
+use crate::service::cache_service::CacheService;
+use crate::service::wallet_service::WalletService;
 use actix_web::web::Data;
-use ant_core::data::Wallet;
 use rmcp::handler::server::tool::ToolRouter;
-use rmcp::model::{ServerCapabilities, ServerInfo};
-use rmcp::{tool_handler, ServerHandler};
+use rmcp::model::{CallToolResult, ErrorCode, ServerCapabilities, ServerInfo};
+use rmcp::{tool_handler, ErrorData, ServerHandler};
+use serde_json::json;
+use crate::client::command::command_schedule::CommandSchedule;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::model::quote::Quote;
 
 
 pub mod archive_tool;
@@ -35,6 +41,9 @@ pub mod tarchive_tool;
 pub mod resolver_tool;
 pub mod key_value_tool;
 pub mod crypto_tool;
+pub mod prefetch_tool;
+pub mod cache_tool;
+pub mod wallet_tool;
 
 #[derive(Clone)]
 pub struct McpTool {
@@ -52,7 +61,9 @@ pub struct McpTool {
     resolver_service: Data<ResolverService>,
     /*key_value_service: Data<KeyValueService>,*/
     crypto_service: Data<CryptoService>,
-    evm_wallet: Data<Wallet>,
+    prefetch_service: Data<PrefetchService>,
+    cache_service: Data<CacheService>,
+    wallet_service: Data<WalletService>,
     tool_router: ToolRouter<Self>,
 }
 
@@ -72,7 +83,9 @@ impl McpTool {
         resolver_service: Data<ResolverService>,
         /*key_value_service: Data<KeyValueService>,*/
         crypto_service: Data<CryptoService>,
-        evm_wallet: Data<Wallet>
+        prefetch_service: Data<PrefetchService>,
+        cache_service: Data<CacheService>,
+        wallet_service: Data<WalletService>
     ) -> Self {
         Self {
             command_service,
@@ -89,7 +102,9 @@ impl McpTool {
             resolver_service,
             /*key_value_service,*/
             crypto_service,
-            evm_wallet,
+            prefetch_service,
+            cache_service,
+            wallet_service,
             tool_router: Self::chunk_tool_router()
                 /*+ Self::pnr_tool_router()*/
                 + Self::command_tool_router()
@@ -105,6 +120,9 @@ impl McpTool {
                 + Self::resolver_tool_router()
                 /*+ Self::key_value_tool_router()*/
                 + Self::crypto_tool_router()
+                + Self::prefetch_tool_router()
+                + Self::cache_tool_router()
+                + Self::wallet_tool_router()
         }
     }
 }
@@ -119,3 +137,23 @@ impl ServerHandler for McpTool {
         }
     }
 }
+
+impl From<Quote> for CallToolResult {
+    fn from(quote: Quote) -> CallToolResult {
+        CallToolResult::structured(json!(quote))
+    }
+}
+
+/// Schedule for commands queued by a tool call, from its optional priority and RFC 3339 not before time.
+fn parse_command_schedule(priority: Option<i32>, not_before: Option<String>) -> Result<CommandSchedule, ErrorData> {
+    CommandSchedule::with_priority(priority, not_before.as_deref())
+        .map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, e.to_string(), None))
+}
+
+impl McpTool {
+    /// Wallet paying for commands queued by a tool call, selected by its optional API key.
+    fn parse_command_wallet(&self, api_key: Option<String>) -> Result<Option<CommandWallet>, ErrorData> {
+        self.wallet_service.get_command_wallet(api_key.as_deref())
+            .map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, e.to_string(), None))
+    }
+}
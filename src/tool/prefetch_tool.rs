@@ -0,0 +1,49 @@
+#![allow(dead_code)]
+
+use rmcp::{handler::server::{
+    wrapper::Parameters,
+}, schemars, tool, tool_router, ErrorData};
+use rmcp::model::CallToolResult;
+use rmcp::schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::json;
+use crate::service::prefetch_service::Prefetch;
+use crate::tool::McpTool;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct PrefetchRequest {
+    #[schemars(description = "Address of the data map or archive to warm the cache with")]
+    address: String,
+}
+
+impl From<Prefetch> for CallToolResult {
+    fn from(prefetch: Prefetch) -> CallToolResult {
+        CallToolResult::structured(json!(prefetch))
+    }
+}
+
+#[tool_router(router = prefetch_tool_router, vis = "pub")]
+impl McpTool {
+
+    #[tool(description = "Queue a job to prefetch all chunks of a data map or archive into the cache")]
+    async fn prefetch(
+        &self,
+        Parameters(PrefetchRequest { address }): Parameters<PrefetchRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.prefetch_service.prefetch(address).await?.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prefetch_request_serialization() {
+        let json = r#"{
+            "address": "0000000000000000000000000000000000000000000000000000000000000000"
+        }"#;
+        let request: PrefetchRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.address, "0000000000000000000000000000000000000000000000000000000000000000");
+    }
+}
@@ -12,7 +12,8 @@ use serde::Deserialize;
 use serde_json::json;
 use crate::controller::StoreType;
 use crate::error::public_data_error::PublicDataError;
-use crate::tool::McpTool;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::tool::{parse_command_schedule, McpTool};
 
 #[derive(Debug, Deserialize, JsonSchema)]
 struct CreatePublicDataRequest {
@@ -20,6 +21,18 @@ struct CreatePublicDataRequest {
     content: String,
     #[schemars(description = "Store public data on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional priority of queued commands; higher priorities run first")]
+    priority: Option<i32>,
+    #[schemars(description = "Optional RFC 3339 time before which queued commands will not run")]
+    not_before: Option<String>,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct QuotePublicDataRequest {
+    #[schemars(description = "Base64 encoded content of the public data")]
+    content: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -40,16 +53,26 @@ impl McpTool {
     #[tool(description = "Create a new public data with base64 encoded content")]
     async fn create_public_data(
         &self,
-        Parameters(CreatePublicDataRequest { content, store_type }): Parameters<CreatePublicDataRequest>,
+        Parameters(CreatePublicDataRequest { content, store_type, priority, not_before, api_key }): Parameters<CreatePublicDataRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let decoded_content = BASE64_STANDARD.decode(content).map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, format!("Invalid base64 content: {}", e), None))?;
-        let chunk = self.public_data_service.create_public_data(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        let chunk = CommandWallet::scope_optional(command_wallet, parse_command_schedule(priority, not_before)?.scope(self.public_data_service.create_public_data(
             Bytes::from(decoded_content),
             StoreType::from(store_type)
-        ).await?;
+        ))).await?;
         Ok(CallToolResult::structured(json!(chunk)))
     }
 
+    #[tool(description = "Quote the cost of uploading public data with base64 encoded content, without uploading it")]
+    async fn quote_public_data(
+        &self,
+        Parameters(QuotePublicDataRequest { content }): Parameters<QuotePublicDataRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let decoded_content = BASE64_STANDARD.decode(content).map_err(|e| ErrorData::new(ErrorCode::INVALID_PARAMS, format!("Invalid base64 content: {}", e), None))?;
+        Ok(self.public_data_service.quote_public_data(Bytes::from(decoded_content)).await?.into())
+    }
+
     #[tool(description = "Get public data by its address")]
     async fn get_public_data(
         &self,
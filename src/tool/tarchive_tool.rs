@@ -16,7 +16,8 @@ use crate::controller::StoreType;
 use crate::error::tarchive_error::TarchiveError;
 use crate::service::archive_service::PublicArchiveForm;
 /*use crate::service::public_archive_service::PublicArchiveForm;*/
-use crate::tool::McpTool;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::tool::{parse_command_schedule, McpTool};
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
 struct CreateTarchiveRequest {
@@ -26,6 +27,12 @@ struct CreateTarchiveRequest {
     path: Option<String>,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional priority of queued commands; higher priorities run first")]
+    priority: Option<i32>,
+    #[schemars(description = "Optional RFC 3339 time before which queued commands will not run")]
+    not_before: Option<String>,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
@@ -38,6 +45,30 @@ struct UpdateTarchiveRequest {
     path: Option<String>,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional priority of queued commands; higher priorities run first")]
+    priority: Option<i32>,
+    #[schemars(description = "Optional RFC 3339 time before which queued commands will not run")]
+    not_before: Option<String>,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct QuoteTarchiveRequest {
+    #[schemars(description = "Base64 encoded content of the files to archive (map of filename to base64 content)")]
+    files: HashMap<String, String>,
+    #[schemars(description = "Optional shared target path (directory) for all files in the archive")]
+    path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema, Serialize)]
+struct QuoteUpdateTarchiveRequest {
+    #[schemars(description = "Address of the tarchive")]
+    address: String,
+    #[schemars(description = "Base64 encoded content of the files to add to archive (map of filename to base64 content)")]
+    files: HashMap<String, String>,
+    #[schemars(description = "Optional shared target path (directory) for all files in the archive")]
+    path: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
@@ -48,6 +79,8 @@ struct TruncateTarchiveRequest {
     path: String,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema, Serialize)]
@@ -72,6 +105,8 @@ struct PushTarchiveRequest {
     address: String,
     #[schemars(description = "Store archive on memory, disk or network")]
     store_type: String,
+    #[schemars(description = "Optional API key selecting the key store wallet which pays for queued commands")]
+    api_key: Option<String>,
 }
 
 
@@ -87,43 +122,61 @@ impl McpTool {
     #[tool(description = "Create a new tarchive")]
     async fn create_tarchive(
         &self,
-        Parameters(CreateTarchiveRequest { files, path, store_type }): Parameters<CreateTarchiveRequest>,
+        Parameters(CreateTarchiveRequest { files, path, store_type, priority, not_before, api_key }): Parameters<CreateTarchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let tarchive_form = self.map_to_tarchive_multipart_form(files)?;
-        Ok(self.tarchive_service.create_tarchive(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, parse_command_schedule(priority, not_before)?.scope(self.tarchive_service.create_tarchive(
             path,
             tarchive_form,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(store_type)
-        ).await?.into())
+        ))).await?.into())
     }
 
     #[tool(description = "Update an existing tarchive")]
     async fn update_tarchive(
         &self,
-        Parameters(UpdateTarchiveRequest { address, files, path, store_type }): Parameters<UpdateTarchiveRequest>,
+        Parameters(UpdateTarchiveRequest { address, files, path, store_type, priority, not_before, api_key }): Parameters<UpdateTarchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         let tarchive_form = self.map_to_tarchive_multipart_form(files)?;
-        Ok(self.tarchive_service.update_tarchive(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, parse_command_schedule(priority, not_before)?.scope(self.tarchive_service.update_tarchive(
             address,
             path,
             tarchive_form,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(store_type)
-        ).await?.into())
+        ))).await?.into())
+    }
+
+    #[tool(description = "Quote the cost of creating a new tarchive, without uploading it")]
+    async fn quote_tarchive(
+        &self,
+        Parameters(QuoteTarchiveRequest { files, path }): Parameters<QuoteTarchiveRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let tarchive_form = self.map_to_tarchive_multipart_form(files)?;
+        Ok(self.tarchive_service.quote_tarchive(path, tarchive_form).await?.into())
+    }
+
+    #[tool(description = "Quote the cost of updating an existing tarchive, without uploading it")]
+    async fn quote_update_tarchive(
+        &self,
+        Parameters(QuoteUpdateTarchiveRequest { address, files, path }): Parameters<QuoteUpdateTarchiveRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let tarchive_form = self.map_to_tarchive_multipart_form(files)?;
+        Ok(self.tarchive_service.quote_tarchive_update(address, path, tarchive_form).await?.into())
     }
 
     #[tool(description = "Truncate an existing tarchive (delete file or directory)")]
     async fn truncate_tarchive(
         &self,
-        Parameters(TruncateTarchiveRequest { address, path, store_type }): Parameters<TruncateTarchiveRequest>,
+        Parameters(TruncateTarchiveRequest { address, path, store_type, api_key }): Parameters<TruncateTarchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        Ok(self.tarchive_service.truncate_tarchive(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, self.tarchive_service.truncate_tarchive(
             address,
             path,
-            self.evm_wallet.get_ref().clone(),
             StoreType::from(store_type)
-        ).await?.into())
+        )).await?.into())
     }
 
     #[tool(description = "List files in a tarchive")]
@@ -158,12 +211,13 @@ impl McpTool {
     #[tool(description = "Push a tarchive to the network")]
     async fn push_tarchive(
         &self,
-        Parameters(PushTarchiveRequest { address, store_type }): Parameters<PushTarchiveRequest>,
+        Parameters(PushTarchiveRequest { address, store_type, api_key }): Parameters<PushTarchiveRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        Ok(self.public_data_service.push_public_data(
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, self.public_data_service.push_public_data(
             address,
             StoreType::from(store_type)
-        ).await?.into())
+        )).await?.into())
     }
 
     fn map_to_tarchive_multipart_form(&self, files: HashMap<String, String>) -> Result<MultipartForm<PublicArchiveForm>, ErrorData> {
@@ -204,11 +258,15 @@ mod tests {
             files,
             path: None,
             store_type: "memory".to_string(),
+            priority: Some(10),
+            not_before: None,
+            api_key: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         let deserialized: CreateTarchiveRequest = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.store_type, "memory");
         assert_eq!(deserialized.files.get("test.txt").unwrap(), "SGVsbG8gd29ybGQ=");
+        assert_eq!(deserialized.priority, Some(10));
     }
 
     #[tokio::test]
@@ -220,6 +278,9 @@ mod tests {
             files,
             path: Some("secret".to_string()),
             store_type: "disk".to_string(),
+            priority: None,
+            not_before: None,
+            api_key: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         let deserialized: UpdateTarchiveRequest = serde_json::from_str(&json).unwrap();
@@ -235,6 +296,7 @@ mod tests {
             address: "0x123".to_string(),
             path: "folder/".to_string(),
             store_type: "memory".to_string(),
+            api_key: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         let deserialized: TruncateTarchiveRequest = serde_json::from_str(&json).unwrap();
@@ -272,6 +334,7 @@ mod tests {
         let request = PushTarchiveRequest {
             address: "0x123".to_string(),
             store_type: "network".to_string(),
+            api_key: Some("key-a".to_string()),
         };
         let json = serde_json::to_string(&request).unwrap();
         let deserialized: PushTarchiveRequest = serde_json::from_str(&json).unwrap();
@@ -0,0 +1,72 @@
+#![allow(dead_code)]
+
+use rmcp::{handler::server::{
+    wrapper::Parameters,
+}, schemars, tool, tool_router, ErrorData};
+use rmcp::model::{CallToolResult, ErrorCode};
+use rmcp::schemars::JsonSchema;
+use serde::{Deserialize};
+use serde_json::json;
+use crate::client::command::command_wallet::CommandWallet;
+use crate::error::wallet_error::WalletError;
+use crate::service::wallet_service::WalletDetails;
+use crate::tool::McpTool;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct GetWalletRequest {
+    #[schemars(description = "Optional API key selecting a key store wallet instead of the default wallet")]
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+struct LoadWalletRequest {
+    #[schemars(description = "Name of the EVM wallet in the key store")]
+    name: String,
+}
+
+impl From<WalletDetails> for CallToolResult {
+    fn from(wallet_details: WalletDetails) -> CallToolResult {
+        CallToolResult::structured(json!(wallet_details))
+    }
+}
+
+impl From<WalletError> for ErrorData {
+    fn from(wallet_error: WalletError) -> Self {
+        ErrorData::new(ErrorCode::INTERNAL_ERROR, wallet_error.to_string(), None)
+    }
+}
+
+#[tool_router(router = wallet_tool_router, vis = "pub")]
+impl McpTool {
+
+    #[tool(description = "Get the address, network and balances of the wallet which pays for uploads")]
+    async fn get_wallet(
+        &self,
+        Parameters(GetWalletRequest { api_key }): Parameters<GetWalletRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let command_wallet = self.parse_command_wallet(api_key)?;
+        Ok(CommandWallet::scope_optional(command_wallet, self.wallet_service.get_wallet()).await?.into())
+    }
+
+    #[tool(description = "List the names of EVM wallets in the key store")]
+    async fn get_wallet_keystore(
+        &self,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(CallToolResult::structured(json!({ "names": self.wallet_service.get_wallets().await? })))
+    }
+
+    #[tool(description = "Get what each wallet has paid for uploads since startup")]
+    async fn get_wallet_spend(
+        &self,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(CallToolResult::structured(json!({ "wallets": self.wallet_service.get_wallet_spends() })))
+    }
+
+    #[tool(description = "Load an EVM wallet from the key store to pay for uploads")]
+    async fn load_wallet(
+        &self,
+        Parameters(LoadWalletRequest { name }): Parameters<LoadWalletRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(self.wallet_service.load_wallet(name).await?.into())
+    }
+}